@@ -0,0 +1,424 @@
+use crate::float::{recip_approx_f64, rsqrt_approx_f64};
+use core::f64::consts::{FRAC_2_PI, FRAC_PI_2, FRAC_PI_4, LN_2, LOG2_E};
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#floating-point-instructions-rcp-approx-ftz-f64
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#floating-point-instructions-rsqrt
+https://git.musl-libc.org/cgit/musl/tree/src/math?h=v1.1.24
+
+The f64 approximations behind `FastNum for f64`. PTX only approximates the f64 reciprocal and reciprocal
+square root, and only to about 20 bits, there is no f64 `ex2`, `lg2`, `sin` or `cos`. So the seeds are
+refined with Newton iterations, and the transcendentals are range reduced with FMA and evaluated with
+polynomials, fdlibm's for sin, cos & log, without tables or divisions, as f64 throughput is the limit.
+
+The maximum errors of the `FastNum` functions are their f64 rows in ulp/table.txt, measured by the ulp
+crate against libm: at most 3 ulp, but for the reciprocal & division of the flushed operands below, and for gelu & pow, which
+amplify the rounding of their argument. exp & exp2 round again when their result is subnormal.
+sin & cos reduce arguments up to 2^20 * pi/2 with a 3 part pi/2, larger arguments go to libm, sincos
+shares the reduction. tanh is an odd polynomial below 0.625 and 1 - 2 / (e^2x + 1) above. atan2 takes
+fdlibm's atan of the smaller magnitude over the larger, which rounds once more.
+The seeds flush subnormal inputs, so the reciprocal of a subnormal is infinite, 2^53 ulp apart as bit
+patterns, and the reciprocal of a number over 2^1022 is zero instead of a subnormal, up to 2^52 ulp.
+sqrt of a subnormal takes the exact `sqrt.rn.f64`.
+*/
+
+const SHIFTER: f64 = 6755399441055744.0;
+/// ln(2) - LN_2
+const LN_2_LO: f64 = 2.3190468138462996e-17;
+
+fn fma(a: f64, b: f64, c: f64) -> f64 {
+    unsafe { core::intrinsics::fmaf64(a, b, c) }
+}
+
+/// round to nearest integer for |x| < 2^51
+fn round(x: f64) -> f64 {
+    (x + SHIFTER) - SHIFTER
+}
+
+/// x * 2^n for |n| <= 2044, only a subnormal result is rounded
+fn scale(x: f64, n: i32) -> f64 {
+    let h = n >> 1;
+    let p0 = f64::from_bits(((h + 1023) as u64) << 52);
+    let p1 = f64::from_bits(((n - h + 1023) as u64) << 52);
+    x * p0 * p1
+}
+
+/// 1 / x, 2 Newton iterations from `rcp.approx.ftz.f64`
+pub(crate) fn recip(x: f64) -> f64 {
+    let mut r = unsafe { recip_approx_f64(x) };
+    // the reciprocals of 0, inf & NaN are already exact
+    if r == 0.0 || !r.is_finite() {
+        return r;
+    }
+    for _ in 0..2 {
+        let e = fma(-x, r, 1.0);
+        r = fma(r, e, r);
+    }
+    r
+}
+
+/// 1 / sqrt(x), 2 Newton iterations from `rsqrt.approx.f64`
+pub(crate) fn rsqrt(x: f64) -> f64 {
+    let mut y = unsafe { rsqrt_approx_f64(x) };
+    if y == 0.0 || !y.is_finite() {
+        return y;
+    }
+    for _ in 0..2 {
+        let e = fma(-(x * y), y, 1.0);
+        y = fma(0.5 * y, e, y);
+    }
+    y
+}
+
+/// x * rsqrt(x), with one more Newton iteration on the square root
+pub(crate) fn sqrt(x: f64) -> f64 {
+    let y = rsqrt(x);
+    if y == 0.0 || !y.is_finite() {
+        // 0, inf, negative, NaN or subnormal
+        return unsafe { core::intrinsics::sqrtf64(x) };
+    }
+    let s = x * y;
+    let e = fma(-s, s, x);
+    fma(0.5 * y, e, s)
+}
+
+/// e^(r + r_lo) for |r| <= ln(2) / 2, a degree 12 polynomial near minimax for (e^r - 1 - r) / r^2
+fn exp_kernel(r: f64, r_lo: f64) -> f64 {
+    const C: [f64; 11] = [
+        2.0914679376583935e-09,
+        2.510520637395701e-08,
+        2.7557273661348637e-07,
+        2.7557255425746435e-06,
+        2.4801587325533363e-05,
+        0.00019841269874800493,
+        0.0013888888888883752,
+        0.008333333333326141,
+        0.04166666666666667,
+        0.1666666666666667,
+        0.5,
+    ];
+    let mut p = C[0];
+    for c in &C[1..] {
+        p = fma(p, r, *c);
+    }
+    // e^r - 1
+    let p = fma(p, r * r, r);
+    1.0 + fma(p, r_lo, p + r_lo)
+}
+
+pub(crate) fn exp2(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let x = x.clamp(-1100.0, 1100.0);
+    let n = round(x);
+    let f = x - n;
+    let r = f * LN_2;
+    let r_lo = fma(f, LN_2, -r) + f * LN_2_LO;
+    scale(exp_kernel(r, r_lo), n as i32)
+}
+
+pub(crate) fn exp(x: f64) -> f64 {
+    exp_tail(x, 0.0)
+}
+
+/// e^(x + x_lo), for |x_lo| much smaller than ulp(x), e.g. the rounding error of x
+pub(crate) fn exp_tail(x: f64, x_lo: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let x = x.clamp(-800.0, 800.0);
+    let n = round(x * LOG2_E);
+    // exact, as n * LN_2 is a multiple of 2^-53 and |x - n * LN_2| < 1
+    let r0 = fma(-n, LN_2, x);
+    let w = n * LN_2_LO;
+    let r = r0 - w;
+    let r_lo = (r0 - r) - w + x_lo;
+    scale(exp_kernel(r, r_lo), n as i32)
+}
+
+/// ln(x) = k * ln(2) + f - hfsq + sr, with 1 + f in [sqrt(1/2), sqrt(2)), hfsq = f^2 / 2,
+/// s = f / (2 + f) and sr = s * (hfsq + R(s^2))
+struct LogParts {
+    k: f64,
+    f: f64,
+    hfsq: f64,
+    sr: f64,
+}
+
+/// the reduction of x, or the result for NaN, 0, negatives & inf
+fn log_parts(x: f64) -> Result<LogParts, f64> {
+    const LG: [f64; 7] = [
+        0.6666666666666735,
+        0.3999999999940942,
+        0.2857142874366239,
+        0.22222198432149784,
+        0.1818357216161805,
+        0.15313837699209373,
+        0.14798198605116586,
+    ];
+    let mut bits = x.to_bits();
+    let mut k = 0i32;
+    if x.is_nan() || x == f64::INFINITY {
+        return Err(x);
+    } else if x == 0.0 {
+        return Err(f64::NEG_INFINITY);
+    } else if x < 0.0 {
+        return Err(f64::NAN);
+    } else if bits < 0x0010_0000_0000_0000 {
+        bits = (x * 18014398509481984.0).to_bits();
+        k = -54;
+    }
+    // moves the exponent so the mantissa falls in [sqrt(1/2), sqrt(2))
+    let hx = (bits >> 32) as u32 + (0x3ff0_0000 - 0x3fe6_a09e);
+    k += (hx >> 20) as i32 - 0x3ff;
+    let hx = (hx & 0x000f_ffff) + 0x3fe6_a09e;
+    let m = f64::from_bits(((hx as u64) << 32) | (bits & 0xffff_ffff));
+    let f = m - 1.0;
+    let hfsq = 0.5 * f * f;
+    let s = f * recip(2.0 + f);
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * fma(w, fma(w, LG[5], LG[3]), LG[1]);
+    let t2 = z * fma(w, fma(w, fma(w, LG[6], LG[4]), LG[2]), LG[0]);
+    Ok(LogParts {
+        k: k as f64,
+        f,
+        hfsq,
+        sr: s * (hfsq + t2 + t1),
+    })
+}
+
+impl LogParts {
+    /// f - hfsq + s * (hfsq + r) as hi + lo, with hi's low 32 bits cleared so products with it are exact
+    fn split(&self) -> (f64, f64) {
+        let hi = f64::from_bits((self.f - self.hfsq).to_bits() & (u64::MAX << 32));
+        let lo = self.f - hi - self.hfsq + self.sr;
+        (hi, lo)
+    }
+}
+
+pub(crate) fn ln(x: f64) -> f64 {
+    const LN2_HI: f64 = 0.6931471803691238;
+    const LN2_LO: f64 = 1.9082149292705877e-10;
+    match log_parts(x) {
+        Ok(LogParts { k, f, hfsq, sr }) => sr + k * LN2_LO - hfsq + f + k * LN2_HI,
+        Err(y) => y,
+    }
+}
+
+pub(crate) fn log2(x: f64) -> f64 {
+    const IVLN2_HI: f64 = 1.4426950407214463;
+    const IVLN2_LO: f64 = 1.6751713164886512e-10;
+    match log_parts(x) {
+        Ok(parts) => {
+            let (hi, lo) = parts.split();
+            let val_hi = hi * IVLN2_HI;
+            let mut val_lo = (lo + hi) * IVLN2_LO + lo * IVLN2_HI;
+            let w = parts.k + val_hi;
+            val_lo += (parts.k - w) + val_hi;
+            val_lo + w
+        }
+        Err(y) => y,
+    }
+}
+
+pub(crate) fn log10(x: f64) -> f64 {
+    const IVLN10_HI: f64 = 0.4342944818781689;
+    const IVLN10_LO: f64 = 2.5082946711645275e-11;
+    const LOG10_2_HI: f64 = 0.30102999566361177;
+    const LOG10_2_LO: f64 = 3.694239077158931e-13;
+    match log_parts(x) {
+        Ok(parts) => {
+            let (hi, lo) = parts.split();
+            let val_hi = hi * IVLN10_HI;
+            let y = parts.k * LOG10_2_HI;
+            let mut val_lo = parts.k * LOG10_2_LO + (lo + hi) * IVLN10_LO + lo * IVLN10_HI;
+            let w = y + val_hi;
+            val_lo += (y - w) + val_hi;
+            val_lo + w
+        }
+        Err(y) => y,
+    }
+}
+
+/// beyond it the 3 part pi / 2 loses too many bits, 2^20 * pi / 2
+const REDUCE_MAX: f64 = 1647099.3291652855;
+
+/// x = n * pi / 2 + r + r_lo, with |r| about pi / 4 or less
+fn reduce(x: f64) -> (i32, f64, f64) {
+    const PIO2_MID: f64 = 6.123233995736766e-17;
+    const PIO2_LO: f64 = -1.4973849048591698e-33;
+    let n = round(x * FRAC_2_PI);
+    // exact, as n * FRAC_PI_2 is a multiple of 2^-52 and |x - n * FRAC_PI_2| < 1
+    let r0 = fma(-n, FRAC_PI_2, x);
+    let w = n * PIO2_MID;
+    let w_lo = fma(n, PIO2_MID, -w);
+    // two sum, r0 may be smaller than w
+    let r = r0 - w;
+    let b = r - r0;
+    let e = (r0 - (r - b)) - (w + b);
+    (n as i32, r, e - (w_lo + n * PIO2_LO))
+}
+
+/// sin(x + y) for |x| <= pi / 4, |y| <= ulp(x) / 2
+fn sin_kernel(x: f64, y: f64) -> f64 {
+    const S: [f64; 6] = [
+        -0.16666666666666632,
+        0.00833333333332249,
+        -0.0001984126982985795,
+        2.7557313707070068e-06,
+        -2.5050760253406863e-08,
+        1.58969099521155e-10,
+    ];
+    let z = x * x;
+    let w = z * z;
+    let r = fma(z, fma(z, S[3], S[2]), S[1]) + z * w * fma(z, S[5], S[4]);
+    let v = z * x;
+    x - ((z * (0.5 * y - v * r) - y) - v * S[0])
+}
+
+/// cos(x + y) for |x| <= pi / 4, |y| <= ulp(x) / 2
+fn cos_kernel(x: f64, y: f64) -> f64 {
+    const C: [f64; 6] = [
+        0.0416666666666666,
+        -0.001388888888887411,
+        2.480158728947673e-05,
+        -2.7557314351390663e-07,
+        2.087572321298175e-09,
+        -1.1359647557788195e-11,
+    ];
+    let z = x * x;
+    let w = z * z;
+    let r = z * fma(z, fma(z, C[2], C[1]), C[0]) + w * w * fma(z, fma(z, C[5], C[4]), C[3]);
+    let hz = 0.5 * z;
+    let w = 1.0 - hz;
+    w + (((1.0 - w) - hz) + (z * r - x * y))
+}
+
+pub(crate) fn sin(x: f64) -> f64 {
+    if x.abs() < 1.4901161193847656e-8 {
+        // also keeps the sign of -0
+        return x;
+    } else if x.abs() > REDUCE_MAX {
+        return libm::sin(x);
+    }
+    let (n, r, r_lo) = reduce(x);
+    match n & 3 {
+        0 => sin_kernel(r, r_lo),
+        1 => cos_kernel(r, r_lo),
+        2 => -sin_kernel(r, r_lo),
+        _ => -cos_kernel(r, r_lo),
+    }
+}
+
+pub(crate) fn cos(x: f64) -> f64 {
+    if x.abs() < 1.4901161193847656e-8 {
+        return 1.0;
+    } else if x.abs() > REDUCE_MAX {
+        return libm::cos(x);
+    }
+    let (n, r, r_lo) = reduce(x);
+    match n & 3 {
+        0 => cos_kernel(r, r_lo),
+        1 => -sin_kernel(r, r_lo),
+        2 => -cos_kernel(r, r_lo),
+        _ => sin_kernel(r, r_lo),
+    }
+}
+
+/// (sin(x), cos(x)) with one reduction
+pub(crate) fn sincos(x: f64) -> (f64, f64) {
+    if x.abs() < 1.4901161193847656e-8 {
+        return (x, 1.0);
+    } else if x.abs() > REDUCE_MAX {
+        return libm::sincos(x);
+    }
+    let (n, r, r_lo) = reduce(x);
+    let (s, c) = (sin_kernel(r, r_lo), cos_kernel(r, r_lo));
+    match n & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+pub(crate) fn tanh(x: f64) -> f64 {
+    /// (tanh(x) - x) / x^3 in x^2, over [0, 0.625^2]
+    const T: [f64; 11] = [
+        -0.3333333333333332,
+        0.13333333333326658,
+        -0.05396825396139557,
+        0.021869488260559115,
+        -0.008863229830925709,
+        0.0035920589774734554,
+        -0.001455309297534642,
+        0.0005874372860094381,
+        -0.00023077616269519857,
+        7.959955735264808e-05,
+        -1.724487449484433e-05,
+    ];
+    let a = x.abs();
+    if a < 1.4901161193847656e-8 {
+        // also keeps the sign of -0
+        x
+    } else if a < 0.625 {
+        let z = x * x;
+        let p = T.iter().rev().fold(0.0, |p, &t| fma(p, z, t));
+        fma(x * z, p, x)
+    } else {
+        // e^2a is infinite past 355, and its reciprocal 0
+        let t = 1.0 - 2.0 * recip(exp(2.0 * a) + 1.0);
+        if x < 0.0 {
+            -t
+        } else {
+            t
+        }
+    }
+}
+
+/// atan(t) for 0 <= t <= 1, fdlibm's polynomial below 7/16 and its reductions by atan(1/2) & atan(1)
+/// above, without the reductions of larger arguments as `fast_atan2` divides the smaller magnitude
+/// by the larger
+pub(crate) fn atan(t: f64) -> f64 {
+    const AT: [f64; 11] = [
+        0.3333333333333293,
+        -0.19999999999876483,
+        0.14285714272503466,
+        -0.11111110405462356,
+        0.09090887133436507,
+        -0.0769187620504483,
+        0.06661073137387531,
+        -0.058335701337905735,
+        0.049768779946159324,
+        -0.036531572744216916,
+        0.016285820115365782,
+    ];
+    /// atan(1/2) & atan(1), and what rounding them left
+    const ATAN_HI: [f64; 2] = [0.4636476090008061, FRAC_PI_4];
+    const ATAN_LO: [f64; 2] = [2.2698777452961687e-17, 3.061616997868383e-17];
+    let (x, id) = if t < 0.4375 {
+        (t, None)
+    } else if t < 0.6875 {
+        (fma(2.0, t, -1.0) * recip(2.0 + t), Some(0))
+    } else {
+        ((t - 1.0) * recip(t + 1.0), Some(1))
+    };
+    let z = x * x;
+    let w = z * z;
+    let s1 = z * fma(
+        w,
+        fma(
+            w,
+            fma(w, fma(w, fma(w, AT[10], AT[8]), AT[6]), AT[4]),
+            AT[2],
+        ),
+        AT[0],
+    );
+    let s2 = w * fma(w, fma(w, fma(w, fma(w, AT[9], AT[7]), AT[5]), AT[3]), AT[1]);
+    match id {
+        None => x - x * (s1 + s2),
+        Some(id) => ATAN_HI[id] - ((x * (s1 + s2) - ATAN_LO[id]) - x),
+    }
+}
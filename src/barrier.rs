@@ -14,6 +14,13 @@ extern "C" {
     #[ffi_const]
     #[link_name = "llvm.nvvm.barrier0.popc"]
     fn __syncthreads_count(test: u32) -> u32;
+
+    #[link_name = "llvm.nvvm.membar.cta"]
+    fn __threadfence_block();
+    #[link_name = "llvm.nvvm.membar.gl"]
+    fn __threadfence();
+    #[link_name = "llvm.nvvm.membar.sys"]
+    fn __threadfence_system();
 }
 
 #[doc = r#"
@@ -62,3 +69,21 @@ pub fn syncthreads_or(test: bool) -> bool {
 pub fn syncthreads_count(test: bool) -> u32 {
     unsafe { __syncthreads_count(if test { 1 } else { 0 }) as u32 }
 }
+
+/// orders all memory accesses of the executing thread before the fence ahead of the ones after it,
+/// as observed by the other threads of its block
+pub fn threadfence_block() {
+    unsafe { __threadfence_block() }
+}
+
+/// orders all memory accesses of the executing thread before the fence ahead of the ones after it,
+/// as observed by all threads of the device
+pub fn threadfence() {
+    unsafe { __threadfence() }
+}
+
+/// orders all memory accesses of the executing thread before the fence ahead of the ones after it,
+/// as observed by all threads of the device, host threads and peer devices
+pub fn threadfence_system() {
+    unsafe { __threadfence_system() }
+}
@@ -0,0 +1,70 @@
+/*
+The compare-exchange schedule of the warp's bitonic sorts, which needs no device, so it can be checked
+on the host. A sort of n elements merges the sorted runs of k / 2 into runs of k for k = 2, 4, .. n,
+each merge compares the elements j = k / 2, k / 4, .. 1 apart, and element i keeps the smaller of
+itself and element i ^ j where both bits agree, so runs alternate between ascending and descending.
+*/
+use core::iter::successors;
+
+/// the distances `j` of the steps of one bitonic merge of `n` elements, `n / 2` down to 1
+pub fn bitonic_merge_distances(n: u32) -> impl Iterator<Item = u32> {
+    successors(Some(n >> 1), |&j| Some(j >> 1)).take_while(|&j| j > 0)
+}
+
+/// the steps `(k, j)` of a bitonic sort of `n` elements, `n` a power of two
+pub fn bitonic_sort_steps(n: u32) -> impl Iterator<Item = (u32, u32)> {
+    successors(Some(2u32), |&k| k.checked_mul(2))
+        .take_while(move |&k| k <= n)
+        .flat_map(|k| bitonic_merge_distances(k).map(move |j| (k, j)))
+}
+
+/// whether element `index` keeps the smaller of itself and element `index ^ j` in step `(k, j)`
+pub fn bitonic_keeps_min(index: u32, k: u32, j: u32) -> bool {
+    (index & j == 0) == (index & k == 0)
+}
+
+/// whether the partner is exchanged into this position, both partners always agree
+pub fn bitonic_take_other<T: PartialOrd>(value: &T, other: &T, keep_min: bool) -> bool {
+    if keep_min {
+        other < value
+    } else {
+        other > value
+    }
+}
+
+pub fn bitonic_exchange<T: PartialOrd + Copy>(value: T, other: T, keep_min: bool) -> T {
+    if bitonic_take_other(&value, &other, keep_min) {
+        other
+    } else {
+        value
+    }
+}
+
+/// step `(k, j)` among the items of one lane, for `j < N`, the items are elements `base..base + N`
+pub fn bitonic_exchange_items<T: PartialOrd + Copy, const N: usize>(
+    items: &mut [T; N],
+    base: u32,
+    k: u32,
+    j: u32,
+) {
+    let j = j as usize;
+    for i in (0..N).filter(|i| i & j == 0) {
+        if bitonic_take_other(
+            &items[i],
+            &items[i | j],
+            bitonic_keeps_min(base + i as u32, k, j as u32),
+        ) {
+            items.swap(i, i | j);
+        }
+    }
+}
+
+/// the first step of merging two ascending runs, `b` reversed: lane `l` pairs `a[l]` with
+/// `b[width - 1 - l]`, which leaves two bitonic halves, all of the lower below all of the upper
+pub fn bitonic_merge_pair<T: PartialOrd + Copy>(a: T, b_reversed: T) -> (T, T) {
+    if b_reversed < a {
+        (b_reversed, a)
+    } else {
+        (a, b_reversed)
+    }
+}
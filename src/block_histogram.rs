@@ -0,0 +1,108 @@
+use crate::{blockDim, laneid, syncthreads, thread_rank, Match};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Shared bins of a [`BlockHistogram`], must live in shared memory and be visible to the whole block
+#[repr(transparent)]
+pub struct BlockHistogramStorage<const BINS: usize>([AtomicU32; BINS]);
+
+impl<const BINS: usize> BlockHistogramStorage<BINS> {
+    pub const fn new() -> Self {
+        Self([const { AtomicU32::new(0) }; BINS])
+    }
+}
+
+impl<const BINS: usize> Default for BlockHistogramStorage<BINS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn block_size() -> u32 {
+    blockDim::x() * blockDim::y() * blockDim::z()
+}
+
+/// Histogram privatized to a block: samples are counted with shared-memory atomics and the block's
+/// counts are added to a global histogram once at the end, instead of contending on global bins.
+///
+/// ```ignore
+/// let histogram = BlockHistogram::new(&BINS_STORAGE);
+/// histogram.init();
+/// for sample in tile { histogram.composite(bin_of(sample)) }
+/// histogram.flush(global_bins);
+/// ```
+pub struct BlockHistogram<'a, const BINS: usize> {
+    storage: &'a BlockHistogramStorage<BINS>,
+}
+
+impl<'a, const BINS: usize> BlockHistogram<'a, BINS> {
+    pub fn new(storage: &'a BlockHistogramStorage<BINS>) -> Self {
+        Self { storage }
+    }
+
+    /// zeroes the shared bins, must be called by every thread of the block before compositing
+    pub fn init(&self) {
+        let mut bin = thread_rank() as usize;
+        while bin < BINS {
+            self.storage.0[bin].store(0, Ordering::Relaxed);
+            bin += block_size() as usize;
+        }
+        syncthreads();
+    }
+
+    /// counts one sample into `bin`
+    pub fn composite(&self, bin: u32) {
+        debug_assert!((bin as usize) < BINS);
+        self.storage.0[bin as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// counts one sample per item into the given bins
+    pub fn composite_items<const ITEMS: usize>(&self, bins: [u32; ITEMS]) {
+        for bin in bins {
+            self.composite(bin);
+        }
+    }
+
+    /// counts one sample into `bin`, first collapsing the lanes of `membermask` that target the same bin
+    /// with `match.any` so only one lane per distinct bin performs the atomic.
+    ///
+    /// Worth it when samples are skewed, e.g. images with large uniform regions.
+    /// `membermask` must name every lane executing the call.
+    pub fn composite_aggregated(&self, bin: u32, membermask: u32) {
+        debug_assert!((bin as usize) < BINS);
+        let peers = bin.match_any(membermask);
+        if laneid() == peers.trailing_zeros() {
+            self.storage.0[bin as usize].fetch_add(peers.count_ones(), Ordering::Relaxed);
+        }
+    }
+
+    /// counts one sample per item into the given bins with warp aggregation, see [`Self::composite_aggregated`]
+    pub fn composite_items_aggregated<const ITEMS: usize>(
+        &self,
+        bins: [u32; ITEMS],
+        membermask: u32,
+    ) {
+        for bin in bins {
+            self.composite_aggregated(bin, membermask);
+        }
+    }
+
+    /// adds the block's counts to the global histogram `global`, which must have at least `BINS` bins.
+    /// Must be called by every thread of the block after compositing.
+    pub fn flush(&self, global: &[AtomicU32]) {
+        assert!(global.len() >= BINS);
+        syncthreads();
+        let mut bin = thread_rank() as usize;
+        while bin < BINS {
+            let count = self.storage.0[bin].load(Ordering::Relaxed);
+            if count != 0 {
+                global[bin].fetch_add(count, Ordering::Relaxed);
+            }
+            bin += block_size() as usize;
+        }
+    }
+
+    /// reads the block's count of `bin`, only meaningful after a [`syncthreads`] following compositing
+    pub fn count(&self, bin: u32) -> u32 {
+        self.storage.0[bin as usize].load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{thread_rank, warp_sync, ALL_MEMBER_MASK, WARP_SIZE};
+use core::cell::UnsafeCell;
+use core::mem::{align_of, size_of, MaybeUninit};
+
+/*
+Arrangements of a tile of `THREADS * ITEMS` items, where thread `t` holds `items[i]`:
+- blocked: tile item `t * ITEMS + i`, what most per-thread algorithms want
+- striped: tile item `i * THREADS + t`, what coalesces when read directly
+- warp-striped: tile item `warp * WARP_SIZE * ITEMS + i * WARP_SIZE + lane`, striped within each warp
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLoadAlgorithm {
+    /// reads a blocked arrangement directly, uncoalesced for larger `ITEMS`
+    Direct,
+    /// reads a striped arrangement directly, the result stays striped
+    Striped,
+    /// reads a blocked arrangement directly with 16 or 8-byte vector loads, falls back to `Direct`
+    /// for partial tiles or insufficiently aligned data
+    Vectorized,
+    /// reads a warp-striped arrangement and transposes it to blocked through shared memory,
+    /// requires `THREADS` to be a multiple of the warp size
+    WarpTranspose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockStoreAlgorithm {
+    /// writes a blocked arrangement directly, uncoalesced for larger `ITEMS`
+    Direct,
+    /// writes a striped arrangement directly
+    Striped,
+    /// writes a blocked arrangement directly with 16 or 8-byte vector stores, falls back to `Direct`
+    /// for partial tiles or insufficiently aligned data
+    Vectorized,
+    /// transposes a blocked arrangement to warp-striped through shared memory before writing it,
+    /// requires `THREADS` to be a multiple of the warp size
+    WarpTranspose,
+}
+
+/// Shared scratch space of a [`BlockLoad`] or [`BlockStore`], must live in shared memory and be visible to
+/// the whole block. Only the `WarpTranspose` algorithms touch it.
+#[repr(transparent)]
+pub struct BlockExchangeStorage<T, const THREADS: usize, const ITEMS: usize>(
+    UnsafeCell<MaybeUninit<[[T; ITEMS]; THREADS]>>,
+);
+
+unsafe impl<T: Send, const THREADS: usize, const ITEMS: usize> Sync
+    for BlockExchangeStorage<T, THREADS, ITEMS>
+{
+}
+
+impl<T, const THREADS: usize, const ITEMS: usize> BlockExchangeStorage<T, THREADS, ITEMS> {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+
+    fn as_mut_ptr(&self) -> *mut T {
+        self.0.get() as *mut T
+    }
+}
+
+impl<T, const THREADS: usize, const ITEMS: usize> Default
+    for BlockExchangeStorage<T, THREADS, ITEMS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[repr(C, align(16))]
+#[derive(Clone, Copy)]
+struct Vector16([u32; 4]);
+
+#[repr(C, align(8))]
+#[derive(Clone, Copy)]
+struct Vector8([u32; 2]);
+
+fn vectorizable<T, V, const ITEMS: usize>(global: *const T) -> bool {
+    size_of::<[T; ITEMS]>().is_multiple_of(size_of::<V>())
+        && (global as usize).is_multiple_of(align_of::<V>())
+}
+
+/// loads `ITEMS` items from aligned global memory with `V`-sized vector accesses if possible
+unsafe fn vector_load<T, V: Copy, const ITEMS: usize>(src: *const T, dst: *mut T) -> bool {
+    if !vectorizable::<T, V, ITEMS>(src) {
+        return false;
+    }
+    for v in 0..size_of::<[T; ITEMS]>() / size_of::<V>() {
+        unsafe {
+            (dst as *mut V)
+                .add(v)
+                .write_unaligned((src as *const V).add(v).read())
+        };
+    }
+    true
+}
+
+/// stores `ITEMS` items to aligned global memory with `V`-sized vector accesses if possible
+unsafe fn vector_store<T, V: Copy, const ITEMS: usize>(src: *const T, dst: *mut T) -> bool {
+    if !vectorizable::<T, V, ITEMS>(dst) {
+        return false;
+    }
+    for v in 0..size_of::<[T; ITEMS]>() / size_of::<V>() {
+        unsafe {
+            (dst as *mut V)
+                .add(v)
+                .write((src as *const V).add(v).read_unaligned())
+        };
+    }
+    true
+}
+
+/// Cooperative load of a tile of `THREADS * ITEMS` items into registers.
+///
+/// The tile is given as a slice starting at the first item of the tile, every thread of the block must
+/// pass the same slice. Thread ranks must be below `THREADS`.
+pub struct BlockLoad<'a, T, const THREADS: usize, const ITEMS: usize> {
+    storage: &'a BlockExchangeStorage<T, THREADS, ITEMS>,
+    algorithm: BlockLoadAlgorithm,
+}
+
+impl<'a, T: Copy, const THREADS: usize, const ITEMS: usize> BlockLoad<'a, T, THREADS, ITEMS> {
+    pub fn new(
+        storage: &'a BlockExchangeStorage<T, THREADS, ITEMS>,
+        algorithm: BlockLoadAlgorithm,
+    ) -> Self {
+        debug_assert!(
+            algorithm != BlockLoadAlgorithm::WarpTranspose
+                || THREADS.is_multiple_of(WARP_SIZE as usize)
+        );
+        Self { storage, algorithm }
+    }
+
+    /// loads a full tile, `tile` must hold at least `THREADS * ITEMS` items
+    pub fn load(&self, tile: &[T]) -> [T; ITEMS] {
+        assert!(tile.len() >= THREADS * ITEMS);
+        let tid = thread_rank() as usize;
+        debug_assert!(tid < THREADS);
+        let src = tile.as_ptr();
+        match self.algorithm {
+            BlockLoadAlgorithm::Direct => {
+                core::array::from_fn(|i| unsafe { src.add(tid * ITEMS + i).read() })
+            }
+            BlockLoadAlgorithm::Striped => {
+                core::array::from_fn(|i| unsafe { src.add(i * THREADS + tid).read() })
+            }
+            BlockLoadAlgorithm::Vectorized => {
+                let mut items = MaybeUninit::<[T; ITEMS]>::uninit();
+                let (src, dst) = (
+                    unsafe { src.add(tid * ITEMS) },
+                    items.as_mut_ptr() as *mut T,
+                );
+                unsafe {
+                    if vector_load::<T, Vector16, ITEMS>(src, dst)
+                        || vector_load::<T, Vector8, ITEMS>(src, dst)
+                    {
+                        items.assume_init()
+                    } else {
+                        core::array::from_fn(|i| src.add(i).read())
+                    }
+                }
+            }
+            BlockLoadAlgorithm::WarpTranspose => {
+                self.warp_transpose(|idx| unsafe { src.add(idx).read() })
+            }
+        }
+    }
+
+    /// loads a partial tile of `tile.len()` valid items, items past the end of `tile` are set to `fill`
+    pub fn load_partial(&self, tile: &[T], fill: T) -> [T; ITEMS] {
+        if tile.len() >= THREADS * ITEMS {
+            return self.load(tile);
+        }
+        let tid = thread_rank() as usize;
+        debug_assert!(tid < THREADS);
+        let get = |idx: usize| tile.get(idx).copied().unwrap_or(fill);
+        match self.algorithm {
+            BlockLoadAlgorithm::Direct | BlockLoadAlgorithm::Vectorized => {
+                core::array::from_fn(|i| get(tid * ITEMS + i))
+            }
+            BlockLoadAlgorithm::Striped => core::array::from_fn(|i| get(i * THREADS + tid)),
+            BlockLoadAlgorithm::WarpTranspose => self.warp_transpose(get),
+        }
+    }
+
+    fn warp_transpose<G: Fn(usize) -> T>(&self, get: G) -> [T; ITEMS] {
+        let tid = thread_rank() as usize;
+        let lane = tid % WARP_SIZE as usize;
+        let warp_offset = (tid - lane) * ITEMS;
+        let exchange = unsafe { self.storage.as_mut_ptr().add(warp_offset) };
+        // the warp may still be reading the previous exchange
+        warp_sync(ALL_MEMBER_MASK);
+        for i in 0..ITEMS {
+            let striped = i * WARP_SIZE as usize + lane;
+            unsafe { exchange.add(striped).write(get(warp_offset + striped)) };
+        }
+        warp_sync(ALL_MEMBER_MASK);
+        core::array::from_fn(|i| unsafe { exchange.add(lane * ITEMS + i).read() })
+    }
+}
+
+/// Cooperative store of a tile of `THREADS * ITEMS` items from registers.
+///
+/// The tile is given as a pointer to its first item, every thread of the block must pass the same
+/// pointer, and each writes only its own items. Thread ranks must be below `THREADS`.
+pub struct BlockStore<'a, T, const THREADS: usize, const ITEMS: usize> {
+    storage: &'a BlockExchangeStorage<T, THREADS, ITEMS>,
+    algorithm: BlockStoreAlgorithm,
+}
+
+impl<'a, T: Copy, const THREADS: usize, const ITEMS: usize> BlockStore<'a, T, THREADS, ITEMS> {
+    pub fn new(
+        storage: &'a BlockExchangeStorage<T, THREADS, ITEMS>,
+        algorithm: BlockStoreAlgorithm,
+    ) -> Self {
+        debug_assert!(
+            algorithm != BlockStoreAlgorithm::WarpTranspose
+                || THREADS.is_multiple_of(WARP_SIZE as usize)
+        );
+        Self { storage, algorithm }
+    }
+
+    /// stores a full tile
+    ///
+    /// # Safety
+    /// `tile` must be valid for writes of `THREADS * ITEMS` items, which nothing else accesses until the
+    /// block has synchronized
+    pub unsafe fn store(&self, tile: *mut T, items: [T; ITEMS]) {
+        let tid = thread_rank() as usize;
+        debug_assert!(tid < THREADS);
+        match self.algorithm {
+            BlockStoreAlgorithm::Direct => {
+                for (i, item) in items.into_iter().enumerate() {
+                    unsafe { tile.add(tid * ITEMS + i).write(item) };
+                }
+            }
+            BlockStoreAlgorithm::Striped => {
+                for (i, item) in items.into_iter().enumerate() {
+                    unsafe { tile.add(i * THREADS + tid).write(item) };
+                }
+            }
+            BlockStoreAlgorithm::Vectorized => unsafe {
+                let (src, dst) = (items.as_ptr(), tile.add(tid * ITEMS));
+                if !vector_store::<T, Vector16, ITEMS>(src, dst)
+                    && !vector_store::<T, Vector8, ITEMS>(src, dst)
+                {
+                    for (i, item) in items.into_iter().enumerate() {
+                        dst.add(i).write(item);
+                    }
+                }
+            },
+            BlockStoreAlgorithm::WarpTranspose => {
+                self.warp_transpose(items, |idx, item| unsafe { tile.add(idx).write(item) })
+            }
+        }
+    }
+
+    /// stores a partial tile, only the first `len` items of the tile are written
+    ///
+    /// # Safety
+    /// `tile` must be valid for writes of `min(len, THREADS * ITEMS)` items, which nothing else accesses
+    /// until the block has synchronized
+    pub unsafe fn store_partial(&self, tile: *mut T, len: usize, items: [T; ITEMS]) {
+        if len >= THREADS * ITEMS {
+            return unsafe { self.store(tile, items) };
+        }
+        let tid = thread_rank() as usize;
+        debug_assert!(tid < THREADS);
+        let set = |idx: usize, item: T| {
+            if idx < len {
+                unsafe { tile.add(idx).write(item) };
+            }
+        };
+        match self.algorithm {
+            BlockStoreAlgorithm::Direct | BlockStoreAlgorithm::Vectorized => {
+                for (i, item) in items.into_iter().enumerate() {
+                    set(tid * ITEMS + i, item);
+                }
+            }
+            BlockStoreAlgorithm::Striped => {
+                for (i, item) in items.into_iter().enumerate() {
+                    set(i * THREADS + tid, item);
+                }
+            }
+            BlockStoreAlgorithm::WarpTranspose => self.warp_transpose(items, set),
+        }
+    }
+
+    fn warp_transpose<S: FnMut(usize, T)>(&self, items: [T; ITEMS], mut set: S) {
+        let tid = thread_rank() as usize;
+        let lane = tid % WARP_SIZE as usize;
+        let warp_offset = (tid - lane) * ITEMS;
+        let exchange = unsafe { self.storage.as_mut_ptr().add(warp_offset) };
+        // the warp may still be reading the previous exchange
+        warp_sync(ALL_MEMBER_MASK);
+        for (i, item) in items.into_iter().enumerate() {
+            unsafe { exchange.add(lane * ITEMS + i).write(item) };
+        }
+        warp_sync(ALL_MEMBER_MASK);
+        for i in 0..ITEMS {
+            let striped = i * WARP_SIZE as usize + lane;
+            set(warp_offset + striped, unsafe {
+                exchange.add(striped).read()
+            });
+        }
+    }
+}
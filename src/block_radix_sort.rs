@@ -0,0 +1,143 @@
+use crate::{
+    radix_digit, radix_passes, radix_thread_ranks, syncthreads, thread_rank, BlockScan,
+    BlockScanStorage, RadixKey, RADIX_DIGITS,
+};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+
+/// Shared scratch space of a [`BlockRadixSort`], must live in shared memory and be visible to the whole block
+#[repr(C)]
+pub struct BlockRadixSortStorage<K, V, const THREADS: usize, const ITEMS: usize> {
+    keys: UnsafeCell<MaybeUninit<[[K; ITEMS]; THREADS]>>,
+    values: UnsafeCell<MaybeUninit<[[V; ITEMS]; THREADS]>>,
+    /// per-thread digit counters, addressed digit-major so their scan yields the stable scatter offsets
+    counters: UnsafeCell<MaybeUninit<[[u32; RADIX_DIGITS]; THREADS]>>,
+    scan: BlockScanStorage<u32>,
+}
+
+unsafe impl<K: Send, V: Send, const THREADS: usize, const ITEMS: usize> Sync
+    for BlockRadixSortStorage<K, V, THREADS, ITEMS>
+{
+}
+
+impl<K, V, const THREADS: usize, const ITEMS: usize> BlockRadixSortStorage<K, V, THREADS, ITEMS> {
+    pub const fn new() -> Self {
+        Self {
+            keys: UnsafeCell::new(MaybeUninit::uninit()),
+            values: UnsafeCell::new(MaybeUninit::uninit()),
+            counters: UnsafeCell::new(MaybeUninit::uninit()),
+            scan: BlockScanStorage::new(),
+        }
+    }
+}
+
+impl<K, V, const THREADS: usize, const ITEMS: usize> Default
+    for BlockRadixSortStorage<K, V, THREADS, ITEMS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Stable least-significant-digit radix sort of a tile of `THREADS * ITEMS` keys held in registers.
+///
+/// Every thread passes its `ITEMS` keys in a blocked arrangement (thread `t` owns tile items
+/// `t * ITEMS..(t + 1) * ITEMS`) and receives the sorted keys in the same arrangement.
+/// Use `()` as `V` to sort keys only. Every thread of the block must call the same method.
+pub struct BlockRadixSort<'a, K, V, const THREADS: usize, const ITEMS: usize> {
+    storage: &'a BlockRadixSortStorage<K, V, THREADS, ITEMS>,
+}
+
+impl<'a, K: RadixKey, V: Copy, const THREADS: usize, const ITEMS: usize>
+    BlockRadixSort<'a, K, V, THREADS, ITEMS>
+{
+    pub fn new(storage: &'a BlockRadixSortStorage<K, V, THREADS, ITEMS>) -> Self {
+        Self { storage }
+    }
+
+    /// sorts the keys of the tile in ascending order
+    pub fn sort(&self, keys: &mut [K; ITEMS]) {
+        self.sort_bits(keys, 0, K::BITS)
+    }
+
+    /// sorts the keys of the tile in ascending order of their radix bits `begin_bit..end_bit`
+    pub fn sort_bits(&self, keys: &mut [K; ITEMS], begin_bit: u32, end_bit: u32) {
+        self.sort_impl(keys, None, begin_bit, end_bit)
+    }
+
+    /// sorts the keys of the tile in ascending order, moving each value along with its key
+    pub fn sort_pairs(&self, keys: &mut [K; ITEMS], values: &mut [V; ITEMS]) {
+        self.sort_pairs_bits(keys, values, 0, K::BITS)
+    }
+
+    /// sorts the keys of the tile in ascending order of their radix bits `begin_bit..end_bit`,
+    /// moving each value along with its key
+    pub fn sort_pairs_bits(
+        &self,
+        keys: &mut [K; ITEMS],
+        values: &mut [V; ITEMS],
+        begin_bit: u32,
+        end_bit: u32,
+    ) {
+        self.sort_impl(keys, Some(values), begin_bit, end_bit)
+    }
+
+    fn sort_impl(
+        &self,
+        keys: &mut [K; ITEMS],
+        mut values: Option<&mut [V; ITEMS]>,
+        begin_bit: u32,
+        end_bit: u32,
+    ) {
+        debug_assert!(begin_bit <= end_bit && end_bit <= K::BITS);
+        let tid = thread_rank() as usize;
+        debug_assert!(tid < THREADS);
+        let scan = BlockScan::<u32, THREADS>::new(&self.storage.scan);
+        let counters = self.storage.counters.get() as *mut u32;
+        let key_exchange = self.storage.keys.get() as *mut K;
+        let value_exchange = self.storage.values.get() as *mut V;
+
+        for (bit, pass_bits) in radix_passes(begin_bit, end_bit) {
+            let digits = keys.map(|key| radix_digit(key, bit, pass_bits));
+            let mut counts = [0u32; RADIX_DIGITS];
+            let mut ranks = radix_thread_ranks(&digits, &mut counts);
+
+            // the previous pass may still be reading the counters and exchange buffers
+            syncthreads();
+            unsafe {
+                for (d, &count) in counts.iter().enumerate() {
+                    counters.add(d * THREADS + tid).write(count);
+                }
+            }
+            syncthreads();
+
+            unsafe {
+                let raking = counters.add(tid * RADIX_DIGITS) as *mut [u32; RADIX_DIGITS];
+                raking.write(scan.exclusive_sum_items(raking.read()));
+            }
+            syncthreads();
+
+            unsafe {
+                for (i, (rank, &d)) in ranks.iter_mut().zip(digits.iter()).enumerate() {
+                    *rank += counters.add(d * THREADS + tid).read();
+                    key_exchange.add(*rank as usize).write(keys[i]);
+                    if let Some(values) = values.as_deref() {
+                        value_exchange.add(*rank as usize).write(values[i]);
+                    }
+                }
+            }
+            syncthreads();
+
+            unsafe {
+                for (i, key) in keys.iter_mut().enumerate() {
+                    *key = key_exchange.add(tid * ITEMS + i).read();
+                }
+                if let Some(values) = values.as_deref_mut() {
+                    for (i, value) in values.iter_mut().enumerate() {
+                        *value = value_exchange.add(tid * ITEMS + i).read();
+                    }
+                }
+            }
+        }
+    }
+}
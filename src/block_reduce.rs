@@ -0,0 +1,179 @@
+use crate::{
+    lane_mask, syncthreads, syncthreads_and, syncthreads_count, syncthreads_or, thread_rank,
+    warp_reduce, Shuffle, WARP_SIZE,
+};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ops::Add;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockReduceAlgorithm {
+    /// every warp reduces its values with shuffles, then thread 0 combines the per-warp aggregates.
+    /// Needs a single barrier and only one shared slot per warp.
+    WarpReductions,
+    /// every thread stores its value, then the first warp rakes sequentially over the shared slots
+    /// before a final warp reduction. Keeps the other warps idle, which suits expensive operators.
+    Raking,
+}
+
+/// Shared scratch space of a [`BlockReduce`], must live in shared memory and be visible to the whole block
+#[repr(transparent)]
+pub struct BlockReduceStorage<T, const THREADS: usize>(UnsafeCell<MaybeUninit<[T; THREADS]>>);
+
+unsafe impl<T: Send, const THREADS: usize> Sync for BlockReduceStorage<T, THREADS> {}
+
+impl<T, const THREADS: usize> BlockReduceStorage<T, THREADS> {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+
+    fn slot(&self, idx: u32) -> *mut T {
+        debug_assert!((idx as usize) < THREADS);
+        unsafe { (self.0.get() as *mut T).add(idx as usize) }
+    }
+}
+
+impl<T, const THREADS: usize> Default for BlockReduceStorage<T, THREADS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cooperative reduction over all `THREADS` threads of a block.
+///
+/// Every thread of the block must call the same method with the same arguments (except `value`).
+/// The storage may only be reused after a [`syncthreads`].
+pub struct BlockReduce<'a, T, const THREADS: usize> {
+    storage: &'a BlockReduceStorage<T, THREADS>,
+    algorithm: BlockReduceAlgorithm,
+}
+
+impl<'a, T, const THREADS: usize> BlockReduce<'a, T, THREADS> {
+    pub fn new(storage: &'a BlockReduceStorage<T, THREADS>) -> Self {
+        Self::with_algorithm(storage, BlockReduceAlgorithm::WarpReductions)
+    }
+
+    pub fn with_algorithm(
+        storage: &'a BlockReduceStorage<T, THREADS>,
+        algorithm: BlockReduceAlgorithm,
+    ) -> Self {
+        Self { storage, algorithm }
+    }
+}
+
+impl<'a, T: Shuffle + Copy, const THREADS: usize> BlockReduce<'a, T, THREADS> {
+    /// reduces `value` over the block in thread order, the result is only valid in thread 0
+    pub fn reduce<F: Fn(T, T) -> T>(&self, value: T, op: F) -> T {
+        self.reduce_partial(value, THREADS as u32, op)
+    }
+
+    /// reduces `value` over the first `valid` threads of the block, the result is only valid in thread 0.
+    /// `valid` must be at least 1.
+    pub fn reduce_partial<F: Fn(T, T) -> T>(&self, value: T, valid: u32, op: F) -> T {
+        debug_assert!(valid >= 1 && valid as usize <= THREADS);
+        match self.algorithm {
+            BlockReduceAlgorithm::WarpReductions => self.warp_reductions(value, valid, op),
+            BlockReduceAlgorithm::Raking => self.raking(value, valid, op),
+        }
+    }
+
+    /// reduces `value` over the block in thread order and broadcasts the result to every thread
+    pub fn all_reduce<F: Fn(T, T) -> T>(&self, value: T, op: F) -> T {
+        let aggregate = self.reduce(value, op);
+        let tid = thread_rank();
+        if tid == 0 {
+            unsafe { self.storage.slot(0).write(aggregate) };
+        }
+        syncthreads();
+        unsafe { self.storage.slot(0).read() }
+    }
+
+    pub fn sum(&self, value: T) -> T
+    where
+        T: Add<Output = T>,
+    {
+        self.reduce(value, |a, b| a + b)
+    }
+
+    pub fn min(&self, value: T) -> T
+    where
+        T: PartialOrd,
+    {
+        self.reduce(value, |a, b| if b < a { b } else { a })
+    }
+
+    pub fn max(&self, value: T) -> T
+    where
+        T: PartialOrd,
+    {
+        self.reduce(value, |a, b| if b > a { b } else { a })
+    }
+
+    fn warp_reductions<F: Fn(T, T) -> T>(&self, value: T, valid: u32, op: F) -> T {
+        let tid = thread_rank();
+        let warp = tid / WARP_SIZE;
+        let warp_base = warp * WARP_SIZE;
+        let warp_threads = (THREADS as u32 - warp_base).min(WARP_SIZE);
+        let warp_valid = valid.saturating_sub(warp_base).min(WARP_SIZE);
+
+        let partial = warp_reduce(value, lane_mask(warp_threads), warp_valid, &op);
+        if tid == warp_base && warp_valid > 0 {
+            unsafe { self.storage.slot(warp).write(partial) };
+        }
+        syncthreads();
+
+        if tid == 0 {
+            let valid_warps = valid.div_ceil(WARP_SIZE);
+            let mut aggregate = partial;
+            for w in 1..valid_warps {
+                aggregate = op(aggregate, unsafe { self.storage.slot(w).read() });
+            }
+            aggregate
+        } else {
+            partial
+        }
+    }
+
+    fn raking<F: Fn(T, T) -> T>(&self, value: T, valid: u32, op: F) -> T {
+        let tid = thread_rank();
+        if tid < valid {
+            unsafe { self.storage.slot(tid).write(value) };
+        }
+        syncthreads();
+
+        if tid >= WARP_SIZE {
+            return value;
+        }
+        let segment = (THREADS as u32).div_ceil(WARP_SIZE);
+        let start = tid * segment;
+        let end = (start + segment).min(valid);
+        let mut partial = value;
+        if start < end {
+            partial = unsafe { self.storage.slot(start).read() };
+            for i in (start + 1)..end {
+                partial = op(partial, unsafe { self.storage.slot(i).read() });
+            }
+        }
+        let raking_threads = (THREADS as u32).min(WARP_SIZE);
+        let raking_valid = valid.div_ceil(segment);
+        warp_reduce(partial, lane_mask(raking_threads), raking_valid, op)
+    }
+}
+
+/// Predicate reductions need no scratch space, they map directly onto the `bar.red` barrier variants
+impl<'a, const THREADS: usize> BlockReduce<'a, bool, THREADS> {
+    /// number of threads in the block for which `pred` holds, returned to every thread
+    pub fn count(&self, pred: bool) -> u32 {
+        syncthreads_count(pred)
+    }
+
+    /// whether `pred` holds for every thread in the block, returned to every thread
+    pub fn all(&self, pred: bool) -> bool {
+        syncthreads_and(pred)
+    }
+
+    /// whether `pred` holds for any thread in the block, returned to every thread
+    pub fn any(&self, pred: bool) -> bool {
+        syncthreads_or(pred)
+    }
+}
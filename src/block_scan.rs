@@ -0,0 +1,213 @@
+use crate::{
+    compose_scan, lane_mask, seed_prefix, syncthreads, thread_exclusive_scan,
+    thread_inclusive_scan, thread_rank, thread_reduce, warp_inclusive_scan, Shuffle, Zero,
+    WARP_SIZE,
+};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ops::Add;
+
+const PREFIX_SLOT: u32 = WARP_SIZE;
+
+/// Shared scratch space of a [`BlockScan`], must live in shared memory and be visible to the whole block.
+/// Holds one aggregate per warp and the tile prefix handed out by a running-prefix callback.
+#[repr(transparent)]
+pub struct BlockScanStorage<T>(UnsafeCell<MaybeUninit<[T; WARP_SIZE as usize + 1]>>);
+
+unsafe impl<T: Send> Sync for BlockScanStorage<T> {}
+
+impl<T> BlockScanStorage<T> {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::uninit()))
+    }
+
+    fn slot(&self, idx: u32) -> *mut T {
+        debug_assert!(idx <= WARP_SIZE);
+        unsafe { (self.0.get() as *mut T).add(idx as usize) }
+    }
+}
+
+impl<T> Default for BlockScanStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cooperative prefix scan over all `THREADS` threads of a block, in thread rank order.
+///
+/// Every thread of the block must call the same method. Apart from the `*_with_prefix` variants,
+/// which end on a barrier, the storage may only be reused after a [`syncthreads`].
+pub struct BlockScan<'a, T, const THREADS: usize> {
+    storage: &'a BlockScanStorage<T>,
+}
+
+impl<'a, T, const THREADS: usize> BlockScan<'a, T, THREADS> {
+    pub fn new(storage: &'a BlockScanStorage<T>) -> Self {
+        debug_assert!(THREADS as u32 <= WARP_SIZE * WARP_SIZE);
+        Self { storage }
+    }
+}
+
+impl<'a, T: Shuffle + Copy, const THREADS: usize> BlockScan<'a, T, THREADS> {
+    /// scans `value` and returns the thread's inclusive prefix, its exclusive prefix (`None` for thread 0)
+    /// and the aggregate of the whole block
+    fn scan<F: Fn(T, T) -> T>(&self, value: T, op: &F) -> (T, Option<T>, T) {
+        let tid = thread_rank();
+        let lane = tid % WARP_SIZE;
+        let warp = tid / WARP_SIZE;
+        let warp_threads = (THREADS as u32 - warp * WARP_SIZE).min(WARP_SIZE);
+        let membermask = lane_mask(warp_threads);
+
+        let inclusive = warp_inclusive_scan(value, membermask, op);
+        let warp_exclusive = inclusive.shfl_up(membermask, 1);
+        if lane == warp_threads - 1 {
+            unsafe { self.storage.slot(warp).write(inclusive) };
+        }
+        syncthreads();
+
+        compose_scan(
+            warp,
+            lane,
+            inclusive,
+            warp_exclusive,
+            (THREADS as u32).div_ceil(WARP_SIZE),
+            |w| unsafe { self.storage.slot(w).read() },
+            op,
+        )
+    }
+
+    /// hands the block aggregate to `prefix_op` in thread 0 and broadcasts the tile prefix it returns
+    fn tile_prefix<P: FnMut(T) -> T>(&self, aggregate: T, prefix_op: &mut P) -> T {
+        if thread_rank() == 0 {
+            unsafe { self.storage.slot(PREFIX_SLOT).write(prefix_op(aggregate)) };
+        }
+        syncthreads();
+        unsafe { self.storage.slot(PREFIX_SLOT).read() }
+    }
+
+    pub fn inclusive_scan<F: Fn(T, T) -> T>(&self, value: T, op: F) -> T {
+        self.inclusive_scan_with_aggregate(value, op).0
+    }
+
+    /// returns the thread's inclusive prefix and the aggregate of the whole block
+    pub fn inclusive_scan_with_aggregate<F: Fn(T, T) -> T>(&self, value: T, op: F) -> (T, T) {
+        let (inclusive, _, aggregate) = self.scan(value, &op);
+        (inclusive, aggregate)
+    }
+
+    /// `prefix_op` is called by thread 0 with the block aggregate and returns the prefix the whole tile
+    /// is seeded with, which lets consecutive tiles carry a running prefix (see [`RunningPrefix`])
+    pub fn inclusive_scan_with_prefix<F: Fn(T, T) -> T, P: FnMut(T) -> T>(
+        &self,
+        value: T,
+        op: F,
+        prefix_op: &mut P,
+    ) -> T {
+        let (inclusive, _, aggregate) = self.scan(value, &op);
+        let prefix = self.tile_prefix(aggregate, prefix_op);
+        op(prefix, inclusive)
+    }
+
+    /// thread 0 receives `initial`, which is also folded in front of every other thread's prefix
+    pub fn exclusive_scan<F: Fn(T, T) -> T>(&self, value: T, initial: T, op: F) -> T {
+        self.exclusive_scan_with_aggregate(value, initial, op).0
+    }
+
+    /// returns the thread's exclusive prefix and the aggregate of the whole block, excluding `initial`
+    pub fn exclusive_scan_with_aggregate<F: Fn(T, T) -> T>(
+        &self,
+        value: T,
+        initial: T,
+        op: F,
+    ) -> (T, T) {
+        let (_, exclusive, aggregate) = self.scan(value, &op);
+        (seed_prefix(initial, exclusive, &op), aggregate)
+    }
+
+    /// `prefix_op` is called by thread 0 with the block aggregate and returns the prefix the whole tile
+    /// is seeded with, thread 0 receives that prefix
+    pub fn exclusive_scan_with_prefix<F: Fn(T, T) -> T, P: FnMut(T) -> T>(
+        &self,
+        value: T,
+        op: F,
+        prefix_op: &mut P,
+    ) -> T {
+        let (_, exclusive, aggregate) = self.scan(value, &op);
+        let prefix = self.tile_prefix(aggregate, prefix_op);
+        seed_prefix(prefix, exclusive, &op)
+    }
+
+    /// inclusive scan over `ITEMS` consecutive items per thread, i.e. over a blocked arrangement
+    pub fn inclusive_scan_items<F: Fn(T, T) -> T, const ITEMS: usize>(
+        &self,
+        items: [T; ITEMS],
+        op: F,
+    ) -> [T; ITEMS] {
+        let (_, exclusive, _) = self.scan(thread_reduce(&items, &op), &op);
+        thread_inclusive_scan(items, exclusive, &op)
+    }
+
+    /// exclusive scan over `ITEMS` consecutive items per thread, i.e. over a blocked arrangement
+    pub fn exclusive_scan_items<F: Fn(T, T) -> T, const ITEMS: usize>(
+        &self,
+        items: [T; ITEMS],
+        initial: T,
+        op: F,
+    ) -> [T; ITEMS] {
+        let (_, exclusive, _) = self.scan(thread_reduce(&items, &op), &op);
+        let prefix = seed_prefix(initial, exclusive, &op);
+        thread_exclusive_scan(items, prefix, &op)
+    }
+
+    /// inclusive scan over `ITEMS` consecutive items per thread seeded by a running-prefix callback
+    pub fn inclusive_scan_items_with_prefix<
+        F: Fn(T, T) -> T,
+        P: FnMut(T) -> T,
+        const ITEMS: usize,
+    >(
+        &self,
+        items: [T; ITEMS],
+        op: F,
+        prefix_op: &mut P,
+    ) -> [T; ITEMS] {
+        let (_, exclusive, aggregate) = self.scan(thread_reduce(&items, &op), &op);
+        let prefix = seed_prefix(self.tile_prefix(aggregate, prefix_op), exclusive, &op);
+        thread_inclusive_scan(items, Some(prefix), &op)
+    }
+
+    /// exclusive scan over `ITEMS` consecutive items per thread seeded by a running-prefix callback
+    pub fn exclusive_scan_items_with_prefix<
+        F: Fn(T, T) -> T,
+        P: FnMut(T) -> T,
+        const ITEMS: usize,
+    >(
+        &self,
+        items: [T; ITEMS],
+        op: F,
+        prefix_op: &mut P,
+    ) -> [T; ITEMS] {
+        let (_, exclusive, aggregate) = self.scan(thread_reduce(&items, &op), &op);
+        let prefix = seed_prefix(self.tile_prefix(aggregate, prefix_op), exclusive, &op);
+        thread_exclusive_scan(items, prefix, &op)
+    }
+}
+
+impl<'a, T: Shuffle + Copy + Zero + Add<Output = T>, const THREADS: usize>
+    BlockScan<'a, T, THREADS>
+{
+    pub fn inclusive_sum(&self, value: T) -> T {
+        self.inclusive_scan(value, |a, b| a + b)
+    }
+
+    pub fn exclusive_sum(&self, value: T) -> T {
+        self.exclusive_scan(value, T::zero(), |a, b| a + b)
+    }
+
+    pub fn inclusive_sum_items<const ITEMS: usize>(&self, items: [T; ITEMS]) -> [T; ITEMS] {
+        self.inclusive_scan_items(items, |a, b| a + b)
+    }
+
+    pub fn exclusive_sum_items<const ITEMS: usize>(&self, items: [T; ITEMS]) -> [T; ITEMS] {
+        self.exclusive_scan_items(items, T::zero(), |a, b| a + b)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::FastNum;
+use core::ops::*;
+use num_traits::{Float, One, Zero};
+
+/// A complex number computed with the [`FastNum`] methods, laid out like `num_complex::Complex`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FastComplex<F> {
+    pub re: F,
+    pub im: F,
+}
+
+impl<F> FastComplex<F> {
+    pub const fn new(re: F, im: F) -> Self {
+        Self { re, im }
+    }
+}
+
+impl<F: FastNum> FastComplex<F> {
+    pub fn i() -> Self {
+        Self::new(F::zero(), F::one())
+    }
+
+    /// cos(theta) + i sin(theta), with a single `fast_sincos`
+    pub fn cis(theta: F) -> Self {
+        let (sin, cos) = theta.fast_sincos();
+        Self::new(cos, sin)
+    }
+
+    pub fn from_polar(r: F, theta: F) -> Self {
+        Self::cis(theta).scale(r)
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub fn scale(self, k: F) -> Self {
+        Self::new(self.re.fast_mul(k), self.im.fast_mul(k))
+    }
+
+    /// re^2 + im^2
+    pub fn norm_sqr(self) -> F {
+        FastNum::fma(self.re, self.re, self.im.fast_mul(self.im))
+    }
+
+    /// the square root of `norm_sqr`, without `hypot`'s rescaling, so it overflows once a component
+    /// is beyond the square root of the largest float
+    pub fn abs(self) -> F {
+        self.norm_sqr().fast_sqrt()
+    }
+
+    pub fn arg(self) -> F {
+        self.im.fast_atan2(self.re)
+    }
+
+    /// (abs, arg)
+    pub fn to_polar(self) -> (F, F) {
+        (self.abs(), self.arg())
+    }
+
+    pub fn recip(self) -> Self {
+        self.conj().scale(self.norm_sqr().fast_recip())
+    }
+
+    pub fn exp(self) -> Self {
+        Self::cis(self.im).scale(self.re.fast_exp())
+    }
+
+    /// the principal logarithm, ln|self| from `norm_sqr` so it overflows like `abs`
+    pub fn ln(self) -> Self {
+        let half = F::one() / (F::one() + F::one());
+        Self::new(half.fast_mul(self.norm_sqr().fast_ln()), self.arg())
+    }
+
+    /// the principal square root, with a non-negative real part
+    pub fn sqrt(self) -> Self {
+        let half = F::one() / (F::one() + F::one());
+        let t = (self.abs().fast_add(self.re.fast_abs()))
+            .fast_mul(half)
+            .fast_sqrt();
+        if t == F::zero() {
+            return Self::new(F::zero(), self.im);
+        }
+        let u = self.im.fast_mul(half).fast_div(t);
+        if Float::is_sign_negative(self.re) {
+            Self::new(u.fast_abs(), t.fast_copysign(self.im))
+        } else {
+            Self::new(t, u)
+        }
+    }
+
+    /// by repeated squaring, the reciprocal for negative `n`
+    pub fn powi(self, n: i32) -> Self {
+        let mut base = self;
+        let mut power = Self::one();
+        let mut exp = n.unsigned_abs();
+        while exp != 0 {
+            if exp & 1 == 1 {
+                power *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        if n < 0 {
+            power.recip()
+        } else {
+            power
+        }
+    }
+}
+
+impl<F: FastNum> Add for FastComplex<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re.fast_add(rhs.re), self.im.fast_add(rhs.im))
+    }
+}
+
+impl<F: FastNum> Sub for FastComplex<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re.fast_sub(rhs.re), self.im.fast_sub(rhs.im))
+    }
+}
+
+/// each component with a single `fma`
+impl<F: FastNum> Mul for FastComplex<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            FastNum::fma(self.re, rhs.re, -self.im.fast_mul(rhs.im)),
+            FastNum::fma(self.re, rhs.im, self.im.fast_mul(rhs.re)),
+        )
+    }
+}
+
+/// multiplies by the reciprocal
+impl<F: FastNum> Div for FastComplex<F> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+impl<F: FastNum> Mul<F> for FastComplex<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self {
+        self.scale(rhs)
+    }
+}
+
+impl<F: FastNum> Div<F> for FastComplex<F> {
+    type Output = Self;
+
+    fn div(self, rhs: F) -> Self {
+        self.scale(rhs.fast_recip())
+    }
+}
+
+impl<F: FastNum> Neg for FastComplex<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+macro_rules! impl_assign {
+    ($($Assign: ident, $assign: ident, $op: ident, $Rhs: ty);+) => {
+        $(impl<F: FastNum> $Assign<$Rhs> for FastComplex<F> {
+            fn $assign(&mut self, rhs: $Rhs) {
+                *self = (*self).$op(rhs)
+            }
+        })+
+    };
+}
+
+impl_assign! {
+    AddAssign, add_assign, add, Self;
+    SubAssign, sub_assign, sub, Self;
+    MulAssign, mul_assign, mul, Self;
+    DivAssign, div_assign, div, Self;
+    MulAssign, mul_assign, mul, F;
+    DivAssign, div_assign, div, F
+}
+
+impl<F: FastNum> Zero for FastComplex<F> {
+    fn zero() -> Self {
+        Self::new(F::zero(), F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+}
+
+impl<F: FastNum> One for FastComplex<F> {
+    fn one() -> Self {
+        Self::new(F::one(), F::zero())
+    }
+}
+
+impl<F: Zero> From<F> for FastComplex<F> {
+    fn from(re: F) -> Self {
+        Self::new(re, F::zero())
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl<F> From<num_complex::Complex<F>> for FastComplex<F> {
+    fn from(value: num_complex::Complex<F>) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl<F> From<FastComplex<F>> for num_complex::Complex<F> {
+    fn from(value: FastComplex<F>) -> Self {
+        num_complex::Complex::new(value.re, value.im)
+    }
+}
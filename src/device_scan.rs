@@ -0,0 +1,182 @@
+use crate::{syncthreads, thread_rank, threadfence};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/*
+Single-pass scan by decoupled look-back, Merrill & Garland 2016
+https://research.nvidia.com/publication/2016-03_single-pass-parallel-prefix-scan-decoupled-look-back
+
+Every tile publishes its aggregate as soon as it is known and its inclusive prefix once its predecessors'
+are, a tile looks back over its predecessors' descriptors until it meets an inclusive prefix.
+*/
+
+const STATUS_INVALID: u32 = 0;
+const STATUS_AGGREGATE: u32 = 1;
+const STATUS_PREFIX: u32 = 2;
+
+/// Look-back descriptor of one tile, lives in global memory.
+///
+/// All-zero bytes are a valid, reset descriptor, so a descriptor array can simply be zeroed by the host.
+#[repr(C)]
+pub struct TileDescriptor<T> {
+    status: AtomicU32,
+    aggregate: UnsafeCell<MaybeUninit<T>>,
+    inclusive: UnsafeCell<MaybeUninit<T>>,
+}
+
+unsafe impl<T: Send> Sync for TileDescriptor<T> {}
+
+impl<T> TileDescriptor<T> {
+    pub const fn new() -> Self {
+        Self {
+            status: AtomicU32::new(STATUS_INVALID),
+            aggregate: UnsafeCell::new(MaybeUninit::uninit()),
+            inclusive: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+impl<T> Default for TileDescriptor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Broadcast slot for a dynamically allocated tile id, must live in shared memory
+pub struct TileIdStorage(UnsafeCell<u32>);
+
+unsafe impl Sync for TileIdStorage {}
+
+impl TileIdStorage {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(0))
+    }
+}
+
+impl Default for TileIdStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Global-memory state of a device-wide single-pass scan over tiles.
+///
+/// Tiles may only wait on tiles with a lower id, so tile ids have to be handed out in the order blocks
+/// start running, with [`Self::allocate_tile`], rather than taken from `blockIdx`.
+pub struct DeviceScanTileState<'a, T> {
+    tiles: &'a [TileDescriptor<T>],
+    tile_counter: &'a AtomicU32,
+}
+
+impl<'a, T: Copy> DeviceScanTileState<'a, T> {
+    /// `tiles` needs one descriptor per tile, both it and `tile_counter` must be reset before the scan
+    pub fn new(tiles: &'a [TileDescriptor<T>], tile_counter: &'a AtomicU32) -> Self {
+        Self {
+            tiles,
+            tile_counter,
+        }
+    }
+
+    pub fn num_tiles(&self) -> u32 {
+        self.tiles.len() as u32
+    }
+
+    /// resets the descriptor of `tile`, for an initialization kernel running a thread per tile
+    pub fn init_tile(&self, tile: u32) {
+        self.tiles[tile as usize]
+            .status
+            .store(STATUS_INVALID, Ordering::Relaxed);
+        if tile == 0 {
+            self.tile_counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// hands out the next tile id in the order the calling blocks started, must be called by every thread
+    /// of the block
+    pub fn allocate_tile(&self, broadcast: &TileIdStorage) -> u32 {
+        if thread_rank() == 0 {
+            let tile = self.tile_counter.fetch_add(1, Ordering::Relaxed);
+            unsafe { broadcast.0.get().write(tile) };
+        }
+        syncthreads();
+        let tile = unsafe { broadcast.0.get().read() };
+        // a subsequent allocation must not overwrite the slot before every thread has read it
+        syncthreads();
+        tile
+    }
+
+    fn publish(&self, tile: u32, status: u32, value: T) {
+        let descriptor = &self.tiles[tile as usize];
+        let field = if status == STATUS_PREFIX {
+            &descriptor.inclusive
+        } else {
+            &descriptor.aggregate
+        };
+        unsafe { (field.get() as *mut T).write_volatile(value) };
+        threadfence();
+        descriptor.status.store(status, Ordering::Release);
+    }
+
+    /// spins until `tile` has published anything and returns its status and the published value
+    fn wait(&self, tile: u32) -> (u32, T) {
+        let descriptor = &self.tiles[tile as usize];
+        let status = loop {
+            let status = descriptor.status.load(Ordering::Acquire);
+            if status != STATUS_INVALID {
+                break status;
+            }
+        };
+        let field = if status == STATUS_PREFIX {
+            &descriptor.inclusive
+        } else {
+            &descriptor.aggregate
+        };
+        (status, unsafe { (field.get() as *const T).read_volatile() })
+    }
+
+    /// publishes the aggregate of `tile`, looks back for its exclusive prefix and publishes its inclusive
+    /// prefix. Called by a single thread of the tile's block, returns `None` for the first tile.
+    pub fn look_back<F: Fn(T, T) -> T>(&self, tile: u32, aggregate: T, op: F) -> Option<T> {
+        if tile == 0 {
+            self.publish(tile, STATUS_PREFIX, aggregate);
+            return None;
+        }
+        self.publish(tile, STATUS_AGGREGATE, aggregate);
+
+        let mut predecessor = tile;
+        let mut exclusive: Option<T> = None;
+        loop {
+            predecessor -= 1;
+            let (status, value) = self.wait(predecessor);
+            exclusive = Some(exclusive.map_or(value, |exclusive| op(value, exclusive)));
+            if status == STATUS_PREFIX {
+                break;
+            }
+        }
+        let exclusive = exclusive.unwrap();
+        self.publish(tile, STATUS_PREFIX, op(exclusive, aggregate));
+        Some(exclusive)
+    }
+
+    /// running-prefix callback for the `*_with_prefix` methods of [`BlockScan`](crate::BlockScan), which
+    /// look back for the prefix of `tile` and seed it with `initial`
+    pub fn prefix_callback<'b, F: Fn(T, T) -> T + Copy + 'b>(
+        &'b self,
+        tile: u32,
+        initial: T,
+        op: F,
+    ) -> impl FnMut(T) -> T + 'b {
+        move |aggregate| match self.look_back(tile, aggregate, op) {
+            None => initial,
+            Some(exclusive) => op(initial, exclusive),
+        }
+    }
+
+    /// inclusive prefix of a tile that has completed its look-back, excluding the scan's initial value
+    pub fn inclusive_prefix(&self, tile: u32) -> T {
+        let (status, value) = self.wait(tile);
+        debug_assert_eq!(status, STATUS_PREFIX);
+        value
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{thread_rank, BlockScan, BlockScanStorage, DeviceScanTileState};
+
+/// Per-tile bodies of device-wide stream compaction, each block processes tiles of `THREADS * ITEMS`
+/// consecutive input items.
+///
+/// The running count of selected items is carried between tiles by a decoupled look-back over
+/// `tile_state`, so a whole selection takes a single pass. Every thread of the block must call the same
+/// method with the same tile, tile ids must come from [`DeviceScanTileState::allocate_tile`]. The output
+/// is a raw pointer as every thread of the grid writes its own items of it.
+pub struct DeviceSelect<'a, const THREADS: usize, const ITEMS: usize> {
+    scan: BlockScan<'a, u32, THREADS>,
+    tile_state: &'a DeviceScanTileState<'a, u32>,
+}
+
+impl<'a, const THREADS: usize, const ITEMS: usize> DeviceSelect<'a, THREADS, ITEMS> {
+    pub const TILE_ITEMS: usize = THREADS * ITEMS;
+
+    pub fn new(
+        storage: &'a BlockScanStorage<u32>,
+        tile_state: &'a DeviceScanTileState<'a, u32>,
+    ) -> Self {
+        Self {
+            scan: BlockScan::new(storage),
+            tile_state,
+        }
+    }
+
+    /// index of the first input item owned by the executing thread in `tile`, in a blocked arrangement
+    fn thread_offset(tile: u32) -> usize {
+        tile as usize * Self::TILE_ITEMS + thread_rank() as usize * ITEMS
+    }
+
+    /// scans the selection flags of the tile, returns each item's output offset
+    fn offsets(&self, tile: u32, flags: [bool; ITEMS]) -> [u32; ITEMS] {
+        let mut prefix = self.tile_state.prefix_callback(tile, 0, |a, b| a + b);
+        self.scan
+            .exclusive_scan_items_with_prefix(flags.map(u32::from), |a, b| a + b, &mut prefix)
+    }
+
+    /// copies the items of `tile` for which `pred` holds to `output`, keeping their order.
+    /// Returns the number of items selected from tiles `0..=tile`, which is the total after the last tile.
+    ///
+    /// # Safety
+    /// `output` must be valid for writes of `input.len()` items, which nothing else accesses until the
+    /// whole grid is done
+    pub unsafe fn select_if<T: Copy, P: Fn(&T) -> bool>(
+        &self,
+        tile: u32,
+        input: &[T],
+        output: *mut T,
+        pred: P,
+    ) -> u32 {
+        let offset = Self::thread_offset(tile);
+        let items: [Option<T>; ITEMS] = core::array::from_fn(|i| input.get(offset + i).copied());
+        let flags = items.map(|item| item.as_ref().is_some_and(&pred));
+        let offsets = self.offsets(tile, flags);
+        for ((item, flag), out) in items.into_iter().zip(flags).zip(offsets) {
+            if let (Some(item), true) = (item, flag) {
+                unsafe { output.add(out as usize).write(item) };
+            }
+        }
+        self.tile_state.inclusive_prefix(tile)
+    }
+
+    /// copies the items of `tile` for which `pred` holds to the front of `output` in order, and the others
+    /// to the back of `output` in reverse order, `output` being `input.len()` items long.
+    /// Returns the number of items selected from tiles `0..=tile`.
+    ///
+    /// # Safety
+    /// `output` must be valid for writes of `input.len()` items, which nothing else accesses until the
+    /// whole grid is done
+    pub unsafe fn partition<T: Copy, P: Fn(&T) -> bool>(
+        &self,
+        tile: u32,
+        input: &[T],
+        output: *mut T,
+        pred: P,
+    ) -> u32 {
+        let offset = Self::thread_offset(tile);
+        let items: [Option<T>; ITEMS] = core::array::from_fn(|i| input.get(offset + i).copied());
+        let flags = items.map(|item| item.as_ref().is_some_and(&pred));
+        let offsets = self.offsets(tile, flags);
+        for (i, ((item, flag), selected_before)) in
+            items.into_iter().zip(flags).zip(offsets).enumerate()
+        {
+            if let Some(item) = item {
+                let rejected_before = offset + i - selected_before as usize;
+                let out = if flag {
+                    selected_before as usize
+                } else {
+                    input.len() - 1 - rejected_before
+                };
+                unsafe { output.add(out).write(item) };
+            }
+        }
+        self.tile_state.inclusive_prefix(tile)
+    }
+
+    /// copies the first item of every run of equal consecutive items of `tile` to `output`, keeping their
+    /// order. Returns the number of items selected from tiles `0..=tile`.
+    ///
+    /// # Safety
+    /// `output` must be valid for writes of `input.len()` items, which nothing else accesses until the
+    /// whole grid is done
+    pub unsafe fn unique<T: Copy + PartialEq>(
+        &self,
+        tile: u32,
+        input: &[T],
+        output: *mut T,
+    ) -> u32 {
+        let offset = Self::thread_offset(tile);
+        let items: [Option<T>; ITEMS] = core::array::from_fn(|i| input.get(offset + i).copied());
+        let flags: [bool; ITEMS] = core::array::from_fn(|i| match items[i] {
+            None => false,
+            Some(_) if offset + i == 0 => true,
+            Some(item) => input[offset + i - 1] != item,
+        });
+        let offsets = self.offsets(tile, flags);
+        for ((item, flag), out) in items.into_iter().zip(flags).zip(offsets) {
+            if let (Some(item), true) = (item, flag) {
+                unsafe { output.add(out as usize).write(item) };
+            }
+        }
+        self.tile_state.inclusive_prefix(tile)
+    }
+}
@@ -0,0 +1,107 @@
+/*
+Granlund & Montgomery, "Division by Invariant Integers using Multiplication", figure 4.1
+
+With l = ceil(log2 d) and m = floor(2^N (2^l - d) / d) + 1, which fits in N bits,
+    t = mul.hi(m, n), q = (t + ((n - t) >> min(l, 1))) >> max(l - 1, 0)
+is n / d for every n, without the N + 1 bit sum of (t + n) >> l.
+*/
+
+use crate::IntBits;
+use core::ops::{Div, Rem};
+
+/// A divisor with its precomputed multiplier & shifts, so the quotient is a `mul.hi`, a subtraction, an
+/// addition and two shifts, for index math by values that are fixed for a kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FastDivisor<T> {
+    divisor: T,
+    multiplier: T,
+    shift1: u32,
+    shift2: u32,
+}
+
+macro_rules! fast_divisor {
+    ($($T:ty, $W:ty);+) => {
+        $(impl FastDivisor<$T> {
+            /// panics if `divisor` is 0
+            pub const fn new(divisor: $T) -> Self {
+                assert!(divisor != 0, "division by zero");
+                let l = <$T>::BITS - (divisor - 1).leading_zeros();
+                let multiplier = ((((1 as $W) << l) - divisor as $W) << <$T>::BITS) / divisor as $W + 1;
+                Self {
+                    divisor,
+                    multiplier: multiplier as $T,
+                    shift1: if l == 0 { 0 } else { 1 },
+                    shift2: l.saturating_sub(1),
+                }
+            }
+
+            pub const fn divisor(&self) -> $T {
+                self.divisor
+            }
+
+            pub fn div(&self, n: $T) -> $T {
+                let t = n.mul_hi(self.multiplier);
+                (t + ((n - t) >> self.shift1)) >> self.shift2
+            }
+
+            pub fn rem(&self, n: $T) -> $T {
+                self.divmod(n).1
+            }
+
+            /// (n / divisor, n % divisor)
+            pub fn divmod(&self, n: $T) -> ($T, $T) {
+                let q = self.div(n);
+                (q, n - q * self.divisor)
+            }
+        }
+
+        impl Div<FastDivisor<$T>> for $T {
+            type Output = $T;
+
+            fn div(self, rhs: FastDivisor<$T>) -> $T {
+                rhs.div(self)
+            }
+        }
+
+        impl Rem<FastDivisor<$T>> for $T {
+            type Output = $T;
+
+            fn rem(self, rhs: FastDivisor<$T>) -> $T {
+                rhs.rem(self)
+            }
+        })+
+    };
+}
+
+fast_divisor! {
+    u32, u64;
+    u64, u128
+}
+
+/// The x & y extents of a block or a grid, for linear indices with x varying fastest, like `thread_rank`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dims {
+    x: FastDivisor<u32>,
+    y: FastDivisor<u32>,
+}
+
+impl Dims {
+    pub const fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: FastDivisor::<u32>::new(x),
+            y: FastDivisor::<u32>::new(y),
+        }
+    }
+
+    /// (x, y, z) of a linear index, with two `divmod`s
+    pub fn unravel(&self, index: u32) -> (u32, u32, u32) {
+        let (yz, x) = self.x.divmod(index);
+        let (z, y) = self.y.divmod(yz);
+        (x, y, z)
+    }
+
+    /// the linear index of (x, y, z)
+    pub fn ravel(&self, x: u32, y: u32, z: u32) -> u32 {
+        x + self.x.divisor() * (y + self.y.divisor() * z)
+    }
+}
@@ -1,13 +1,22 @@
+use crate::{approx_f64, BHalf, Half, Rn, RoundedArith};
+use core::fmt;
+use core::marker::PhantomData;
 use core::ops::*;
 pub use num_traits::{float::FloatCore, AsPrimitive, Float, Num, NumCast, One, ToPrimitive, Zero};
 
+#[cfg(not(target_arch = "nvptx64"))]
+pub(crate) use self::soft::recip_approx_f64;
+#[cfg(not(target_arch = "nvptx64"))]
+use self::soft::{
+    cos_approx, cos_approx_noftz, ex2_approx, ex2_approx_noftz, lg2_approx, lg2_approx_noftz,
+    recip_approx, rsqrt_approx_noftz, saturate_f32, saturate_f64, sin_approx, sin_approx_noftz,
+    sqrt_approx, sqrt_approx_noftz, tanh_approx,
+};
+#[cfg(not(target_arch = "nvptx64"))]
+pub use self::soft::{div_approx, div_full, fma_rn_ftz, rsqrt_approx, rsqrt_approx_f64};
+
+#[cfg(target_arch = "nvptx64")]
 extern "C" {
-    // #[link_name = "llvm.nvvm.add.rn.ftz.f"]
-    // fn add_rn_ftz(lhs: f32, rhs: f32) -> f32;
-    // #[link_name = "llvm.nvvm.sub.rn.ftz.f"]
-    // fn sub_rn_ftz(lhs: f32, rhs: f32) -> f32;
-    // #[link_name = "llvm.nvvm.mul.rn.ftz.f"]
-    // fn mul_rn_ftz(lhs: f32, rhs: f32) -> f32;
     #[ffi_const]
     #[link_name = "llvm.nvvm.fma.rn.ftz.f"]
     pub fn fma_rn_ftz(a: f32, b: f32, c: f32) -> f32;
@@ -17,7 +26,7 @@ extern "C" {
     fn recip_approx(v: f32) -> f32;
     #[ffi_const]
     #[link_name = "llvm.nvvm.rcp.approx.ftz.d"]
-    fn recip_approx_f64(v: f64) -> f64;
+    pub(crate) fn recip_approx_f64(v: f64) -> f64;
 
     #[ffi_const]
     #[link_name = "llvm.nvvm.sqrt.approx.ftz.f"]
@@ -44,13 +53,199 @@ extern "C" {
     #[ffi_const]
     #[link_name = "llvm.nvvm.ex2.approx.ftz.f"]
     fn ex2_approx(v: f32) -> f32;
-    // #[link_name = "llvm.nvvm.ex2.approx.d"]
-    // fn ex2_approx_f64(v: f64) -> f64;
     #[ffi_const]
     #[link_name = "llvm.nvvm.lg2.approx.ftz.f"]
     fn lg2_approx(v: f32) -> f32;
-    // #[link_name = "llvm.nvvm.lg2.approx.ftz.d"]
-    // fn lg2_approx_f64(v: f64) -> f64;
+
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.tanh.approx.f32"]
+    fn tanh_approx(v: f32) -> f32;
+
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.div.full"]
+    pub fn div_full(l: f32, r: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.sqrt.approx.f"]
+    fn sqrt_approx_noftz(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.rsqrt.approx.f"]
+    fn rsqrt_approx_noftz(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.sin.approx.f"]
+    fn sin_approx_noftz(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.cos.approx.f"]
+    fn cos_approx_noftz(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.ex2.approx.f"]
+    fn ex2_approx_noftz(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.lg2.approx.f"]
+    fn lg2_approx_noftz(v: f32) -> f32;
+
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.saturate.f"]
+    fn saturate_f32(v: f32) -> f32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.saturate.d"]
+    fn saturate_f64(v: f64) -> f64;
+}
+
+/*
+The PTX ISA only bounds the error of the approximations, so the host models each one at its bound: the
+float farthest from the exact result within the maximum error, above it for inputs with an even bit pattern
+and below for odd ones, with `.ftz` flushing subnormal inputs and results to zero. Errors measured through
+them are what the device may do at worst.
+    rcp, sqrt   1 ulp       rsqrt   2^-22.9 relative        div         2 ulp       tanh    2^-10.987 relative
+    ex2         2 ulp       lg2     2^-22.6 absolute        sin, cos    2^-20.5 absolute
+    rcp.f64, rsqrt.f64      2^-20 relative
+div also flushes to 0 for 2^126 < |divisor| < 2^128, or NaN for an infinite dividend, div.full is within
+2 ulp over the full range and keeps subnormals.
+*/
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    const RSQRT: f64 = 1.277653530283325e-7;
+    const TANH: f64 = 0.0004927009930882233;
+    const LG2: f64 = 1.5729760059987222e-7;
+    const SIN: f64 = 6.743495761743046e-7;
+    const SEED_F64: f64 = 9.5367431640625e-7;
+
+    fn flush(x: f32) -> f32 {
+        if x.is_subnormal() {
+            0.0f32.copysign(x)
+        } else {
+            x
+        }
+    }
+
+    fn flush_f64(x: f64) -> f64 {
+        if x.is_subnormal() {
+            0.0f64.copysign(x)
+        } else {
+            x
+        }
+    }
+
+    /// the float farthest from `exact` within `ulps` ulp, `rel |exact|` and `abs` of it
+    fn at_bound(exact: f64, ulps: f64, rel: f64, abs: f64, up: bool) -> f32 {
+        let nearest = exact as f32;
+        if !nearest.is_finite() {
+            return nearest;
+        }
+        // 2^(exponent - 23), or the subnormal spacing
+        let ulp = (f32::from_bits(nearest.to_bits() & 0x7f80_0000) as f64 / 8388608.0)
+            .max(f32::from_bits(1) as f64);
+        let error = ulps * ulp + rel * exact.abs() + abs;
+        let moved = if up { exact + error } else { exact - error };
+        // rounded back toward exact
+        let rounded = moved as f32;
+        if up && (rounded as f64) > moved {
+            rounded.next_down()
+        } else if !up && (rounded as f64) < moved {
+            rounded.next_up()
+        } else {
+            rounded
+        }
+    }
+
+    fn approx(x: f32, ftz: bool, bound: (f64, f64, f64), exact: impl Fn(f64) -> f64) -> f32 {
+        let x = if ftz { flush(x) } else { x };
+        let (ulps, rel, abs) = bound;
+        let y = at_bound(exact(x as f64), ulps, rel, abs, x.to_bits() & 1 == 0);
+        if ftz {
+            flush(y)
+        } else {
+            y
+        }
+    }
+
+    macro_rules! unary {
+        ($($name:ident, $ftz:literal, $bound:expr, $exact:expr;)+) => {
+            $(pub fn $name(v: f32) -> f32 {
+                approx(v, $ftz, $bound, $exact)
+            })+
+        };
+    }
+
+    // (ulp, relative, absolute)
+    unary! {
+        recip_approx, true, (1.0, 0.0, 0.0), |x| 1.0 / x;
+        sqrt_approx, true, (1.0, 0.0, 0.0), libm::sqrt;
+        rsqrt_approx, true, (0.0, RSQRT, 0.0), |x| 1.0 / libm::sqrt(x);
+        sin_approx, true, (0.0, 0.0, SIN), libm::sin;
+        cos_approx, true, (0.0, 0.0, SIN), libm::cos;
+        ex2_approx, true, (2.0, 0.0, 0.0), libm::exp2;
+        lg2_approx, true, (0.0, 0.0, LG2), libm::log2;
+        tanh_approx, false, (0.0, TANH, 0.0), libm::tanh;
+        sqrt_approx_noftz, false, (1.0, 0.0, 0.0), libm::sqrt;
+        rsqrt_approx_noftz, false, (0.0, RSQRT, 0.0), |x| 1.0 / libm::sqrt(x);
+        sin_approx_noftz, false, (0.0, 0.0, SIN), libm::sin;
+        cos_approx_noftz, false, (0.0, 0.0, SIN), libm::cos;
+        ex2_approx_noftz, false, (2.0, 0.0, 0.0), libm::exp2;
+        lg2_approx_noftz, false, (0.0, 0.0, LG2), libm::log2;
+    }
+
+    pub fn fma_rn_ftz(a: f32, b: f32, c: f32) -> f32 {
+        flush(libm::fmaf(flush(a), flush(b), flush(c)))
+    }
+
+    /// div.approx is `l * (1 / r)`, the reciprocal of 2^126 < |r| < 2^128 flushes to 0, so that is 0 or NaN
+    fn div_at_bound(l: f32, r: f32) -> f32 {
+        // 2^126
+        if r.is_finite() && r.abs() > f32::from_bits(0x7e80_0000) {
+            return l * 0.0f32.copysign(r);
+        }
+        divide_at_bound(l, r)
+    }
+
+    /// within 2 ulp, but for the exact signed zero of a zero dividend, as `l * (1 / r)` gives
+    fn divide_at_bound(l: f32, r: f32) -> f32 {
+        if l == 0.0 {
+            return l / r;
+        }
+        let up = (l.to_bits() ^ r.to_bits()) & 1 == 0;
+        at_bound(l as f64 / r as f64, 2.0, 0.0, 0.0, up)
+    }
+
+    pub fn div_approx(l: f32, r: f32) -> f32 {
+        flush(div_at_bound(flush(l), flush(r)))
+    }
+
+    pub fn div_full(l: f32, r: f32) -> f32 {
+        divide_at_bound(l, r)
+    }
+
+    pub(crate) fn recip_approx_f64(v: f64) -> f64 {
+        let v = flush_f64(v);
+        let sign = if v.to_bits() & 1 == 0 { 1.0 } else { -1.0 };
+        flush_f64(1.0 / v * (1.0 + sign * SEED_F64))
+    }
+
+    pub fn rsqrt_approx_f64(v: f64) -> f64 {
+        let sign = if v.to_bits() & 1 == 0 { 1.0 } else { -1.0 };
+        1.0 / libm::sqrt(v) * (1.0 + sign * SEED_F64)
+    }
+
+    pub fn saturate_f32(v: f32) -> f32 {
+        if v > 0.0 {
+            v.min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn saturate_f64(v: f64) -> f64 {
+        if v > 0.0 {
+            v.min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// a constant of the format, rounded from f64
+fn constant<F: NumCast>(value: f64) -> F {
+    NumCast::from(value).unwrap()
 }
 
 pub trait FastNum: 'static + Sized + Copy + PartialOrd + PartialEq + FloatCore + Float {
@@ -83,6 +278,76 @@ pub trait FastNum: 'static + Sized + Copy + PartialOrd + PartialEq + FloatCore +
     fn fast_ln(self) -> Self;
     fn fast_exp(self) -> Self;
 
+    /// `fast_sin` after reducing the argument to [-π/4, π/4], so large arguments stay accurate
+    fn fast_sin_reduced(self) -> Self {
+        self.fast_sincos().0
+    }
+    /// `fast_cos` after reducing the argument to [-π/4, π/4], so large arguments stay accurate
+    fn fast_cos_reduced(self) -> Self {
+        self.fast_sincos().1
+    }
+    /// (sin, cos), reduced like `fast_sin_reduced` but only once
+    fn fast_sincos(self) -> (Self, Self) {
+        // Cody-Waite with π/2 split in two of the format's precision, the tail of its f64 included
+        const PIO2_TAIL: f64 = 6.123233995736766e-17;
+        let pio2_hi: Self = constant(core::f64::consts::FRAC_PI_2);
+        let pio2_lo =
+            constant(core::f64::consts::FRAC_PI_2 - pio2_hi.to_f64().unwrap() + PIO2_TAIL);
+        let n = Float::round(self * constant(core::f64::consts::FRAC_2_PI));
+        let r = FastNum::fma(-n, pio2_lo, FastNum::fma(-n, pio2_hi, self));
+        let (sin, cos) = (r.fast_sin(), r.fast_cos());
+        match n.to_i64().map_or(0, |n| n & 3) {
+            0 => (sin, cos),
+            1 => (cos, -sin),
+            2 => (-sin, -cos),
+            _ => (-cos, sin),
+        }
+    }
+    /// 1 - 2 / (e^2|self| + 1) with the sign of self, so only accurate in absolute terms near 0
+    fn fast_tanh(self) -> Self {
+        let one = Self::one();
+        let a = self.fast_abs();
+        (one - (one + one) * ((a + a).fast_exp() + one).fast_recip()).fast_copysign(self)
+    }
+    /// 1 / (1 + e^-self)
+    fn fast_sigmoid(self) -> Self {
+        (Self::one() + (-self).fast_exp()).fast_recip()
+    }
+    /// the tanh approximation of GELU, self / 2 (1 + tanh(sqrt(2 / π) (self + 0.044715 self^3)))
+    fn fast_gelu(self) -> Self {
+        // as self sigmoid(2 sqrt(2 / π) (self + 0.044715 self^3)), the same without the cancellation
+        // of 1 + tanh for negative self
+        let u = FastNum::fma(constant::<Self>(0.044715) * self, self, Self::one());
+        self * (constant::<Self>(1.5957691216057308) * self * u).fast_sigmoid()
+    }
+    /// ln(1 + e^self)
+    fn fast_softplus(self) -> Self {
+        // max(self, 0) + ln(1 + t), t = e^-|self| <= 1, with ln(1 + t) = ln(u) t / (u - 1) for u = 1 + t,
+        // which cancels the rounding error of u
+        let t = (-self.fast_abs()).fast_exp();
+        let u = Self::one() + t;
+        let ln_1p = if u == Self::one() {
+            t
+        } else {
+            u.fast_ln() * t * (u - Self::one()).fast_recip()
+        };
+        if self > Self::zero() {
+            self + ln_1p
+        } else {
+            ln_1p
+        }
+    }
+    /// self^y as 2^(y log2(self)) like CUDA's `__powf`, so NaN for negative self, and the error grows
+    /// with |y log2(self)|
+    fn fast_pow(self, y: Self) -> Self {
+        (y * self.fast_log2()).fast_exp2()
+    }
+    /// the angle of (x, self), like `atan2`, by default `fast_atan2` of f64 rounded to the format
+    fn fast_atan2(self, x: Self) -> Self {
+        let (y, x) = (self.to_f64().unwrap(), x.to_f64().unwrap());
+        constant(y.fast_atan2(x))
+    }
+
     fn fast_abs(self) -> Self;
     fn fast_copysign(self, other: Self) -> Self;
     fn fast_trunc(self) -> Self;
@@ -99,10 +364,10 @@ impl FastNum for f32 {
         unsafe { core::intrinsics::fmaf32(self, b, c) }
     }
 
+    /// `div.approx.ftz.f32`, which fast-math division may lower to anyway, so the quotient of a divisor
+    /// above 2^126 is 0
     fn fast_div(self, rhs: Self) -> Self {
-        // unsafe { div_approx(self, rhs) }
-        unsafe { core::intrinsics::fdiv_fast(self, rhs) }
-        // self / rhs
+        unsafe { div_approx(self, rhs) }
     }
 
     fn fast_recip(self) -> Self {
@@ -148,6 +413,97 @@ impl FastNum for f32 {
         (self * core::f32::consts::LOG2_E).fast_exp2()
     }
 
+    fn fast_sin_reduced(self) -> Self {
+        match reduce_pio2(self) {
+            Some((n, r)) => match n & 3 {
+                0 => r.fast_sin(),
+                1 => r.fast_cos(),
+                2 => -r.fast_sin(),
+                _ => -r.fast_cos(),
+            },
+            None => libm::sinf(self),
+        }
+    }
+
+    fn fast_cos_reduced(self) -> Self {
+        match reduce_pio2(self) {
+            Some((n, r)) => match n & 3 {
+                0 => r.fast_cos(),
+                1 => -r.fast_sin(),
+                2 => -r.fast_cos(),
+                _ => r.fast_sin(),
+            },
+            None => libm::cosf(self),
+        }
+    }
+
+    fn fast_sincos(self) -> (Self, Self) {
+        match reduce_pio2(self) {
+            Some((n, r)) => match n & 3 {
+                0 => (r.fast_sin(), r.fast_cos()),
+                1 => (r.fast_cos(), -r.fast_sin()),
+                2 => (-r.fast_sin(), -r.fast_cos()),
+                _ => (-r.fast_cos(), r.fast_sin()),
+            },
+            None => libm::sincosf(self),
+        }
+    }
+
+    /// `tanh.approx.f32`, sm_75
+    fn fast_tanh(self) -> Self {
+        unsafe { tanh_approx(self) }
+    }
+
+    /// as self sigmoid(u), u = 2 sqrt(2 / π) (self + 0.044715 self^3), and as -e^(u + ln(-self)) where
+    /// sigmoid(u) would flush, scaled by 2^24 so subnormal results round once
+    fn fast_gelu(self) -> Self {
+        const SQRT_8_OVER_PI: f32 = 1.5957692;
+        /// 24 ln(2)
+        const LN_2_24: f32 = 16.635532;
+        let u = SQRT_8_OVER_PI * self * FastNum::fma(0.044715 * self, self, 1.0);
+        if u < -80.0 {
+            -(u + (-self).fast_ln() + LN_2_24).fast_exp() * 5.9604645e-8
+        } else {
+            self * u.fast_sigmoid()
+        }
+    }
+
+    fn fast_softplus(self) -> Self {
+        // max(self, 0) + ln(1 + t), t = e^-|self| <= 1, with the series where lg2.approx's absolute
+        // error would be large relative to ln(1 + t)
+        let t = (-self.fast_abs()).fast_exp();
+        let ln_1p = if t < 0.015625 {
+            t * FastNum::fma(t, FastNum::fma(t, 1.0 / 3.0, -0.5), 1.0)
+        } else {
+            (1.0 + t).fast_ln()
+        };
+        if self > 0.0 {
+            self + ln_1p
+        } else {
+            ln_1p
+        }
+    }
+
+    /// atan of the smaller over the larger magnitude, a minimax polynomial within 2^-24
+    /// relative, then moved to the octant of (x, self)
+    fn fast_atan2(self, x: Self) -> Self {
+        const A: [f32; 9] = [
+            1.0,
+            -0.33333072,
+            0.1999262,
+            -0.14203644,
+            0.10640934,
+            -0.07504295,
+            0.04269152,
+            -0.01606863,
+            0.0028498897,
+        ];
+        atan2(self, x, |t| {
+            let z = t * t;
+            t * A.iter().rev().fold(0.0, |p, &a| FastNum::fma(p, z, a))
+        })
+    }
+
     fn fast_abs(self) -> Self {
         unsafe { core::intrinsics::fabsf32(self) }
     }
@@ -169,56 +525,73 @@ impl FastNum for f32 {
     }
 }
 
+/// refined & polynomial approximations, see `approx_f64` for their error
 impl FastNum for f64 {
     fn fma(self, b: Self, c: Self) -> Self {
         unsafe { core::intrinsics::fmaf64(self, b, c) }
     }
 
     fn fast_recip(self) -> Self {
-        unsafe { recip_approx_f64(self) }
+        approx_f64::recip(self)
     }
 
     fn fast_sqrt(self) -> Self {
-        unsafe { core::intrinsics::sqrtf64(self) }
+        approx_f64::sqrt(self)
     }
 
     fn fast_sin(self) -> Self {
-        libm::sin(self)
+        approx_f64::sin(self)
     }
 
     fn fast_cos(self) -> Self {
-        libm::cos(self)
+        approx_f64::cos(self)
     }
 
     fn fast_rsqrt(self) -> Self {
-        unsafe { rsqrt_approx_f64(self) }
+        approx_f64::rsqrt(self)
     }
 
     fn fast_log2(self) -> Self {
-        libm::log2(self)
-        // unsafe { lg2_approx_f64(self) }
+        approx_f64::log2(self)
     }
 
     fn fast_exp2(self) -> Self {
-        libm::exp2(self)
-        // unsafe { ex2_approx_f64(self) }
+        approx_f64::exp2(self)
     }
 
     fn fast_log10(self) -> Self {
-        libm::log10(self)
-        // const RECIP_LOG2_10: f64 = 1f64 / core::f64::consts::LOG2_10;
-        // self.fast_log2() * RECIP_LOG2_10
+        approx_f64::log10(self)
     }
 
     fn fast_ln(self) -> Self {
-        libm::log(self)
-        // const RECIP_LOG2_E: f64 = 1f64 / core::f64::consts::LOG2_E;
-        // self.fast_log2() * RECIP_LOG2_E
+        approx_f64::ln(self)
     }
 
     fn fast_exp(self) -> Self {
-        libm::exp(self)
-        // (self * core::f64::consts::LOG2_E).fast_exp2()
+        approx_f64::exp(self)
+    }
+
+    /// `fast_sin` is already reduced
+    fn fast_sin_reduced(self) -> Self {
+        approx_f64::sin(self)
+    }
+
+    /// `fast_cos` is already reduced
+    fn fast_cos_reduced(self) -> Self {
+        approx_f64::cos(self)
+    }
+
+    fn fast_sincos(self) -> (Self, Self) {
+        approx_f64::sincos(self)
+    }
+
+    fn fast_tanh(self) -> Self {
+        approx_f64::tanh(self)
+    }
+
+    /// fdlibm's atan of the smaller over the larger magnitude, moved to the octant of (x, self)
+    fn fast_atan2(self, x: Self) -> Self {
+        atan2(self, x, approx_f64::atan)
     }
 
     fn fast_abs(self) -> Self {
@@ -242,29 +615,467 @@ impl FastNum for f64 {
     }
 }
 
+/// atan2 from `atan` on [0, 1] of the smaller over the larger magnitude, moved to the octant of (x, y).
+/// The magnitudes are scaled by a power of two before dividing, so the approximate division neither
+/// meets a divisor so large that it flushes the quotient nor subnormals, two infinities are the diagonal
+fn atan2<F: FastNum>(y: F, x: F, atan: impl Fn(F) -> F) -> F {
+    let (ax, ay) = (x.fast_abs(), y.fast_abs());
+    let (lo, hi) = if ay > ax { (ax, ay) } else { (ay, ax) };
+    let infinity = <F as FloatCore>::infinity();
+    let t = if hi == infinity {
+        if lo == infinity {
+            F::one()
+        } else {
+            // 0, or NaN for a NaN lo
+            lo * F::zero()
+        }
+    } else if hi == F::zero() {
+        // 0, or NaN for a NaN lo
+        lo
+    } else {
+        let quarter = constant::<F>(0.25);
+        let scale = if hi > <F as FloatCore>::max_value() * quarter {
+            quarter
+        } else if hi < F::one() {
+            // 2^(2p - 2), with a subnormal lo normal
+            let epsilon = <F as FloatCore>::epsilon();
+            F::one() / (epsilon * epsilon)
+        } else {
+            F::one()
+        };
+        (lo * scale).fast_div(hi * scale)
+    };
+    let r = atan(t);
+    let r = if ay > ax {
+        constant::<F>(core::f64::consts::FRAC_PI_2) - r
+    } else {
+        r
+    };
+    let r = if FloatCore::is_sign_negative(x) {
+        constant::<F>(core::f64::consts::PI) - r
+    } else {
+        r
+    };
+    r.fast_copysign(y)
+}
+
+/// Cody-Waite reduction with a 3 part π/2, x = n π/2 + r with |r| <= π/4, accurate up to
+/// 105615 like CUDA's, larger arguments need Payne-Hanek
+fn reduce_pio2(x: f32) -> Option<(i32, f32)> {
+    const SHIFTER: f32 = 12582912.0;
+    // 1.570796, π/2 to 21 bits, as bits since clippy takes it for an approximate FRAC_PI_2
+    const PIO2_HI: f32 = f32::from_bits(0x3fc9_0fd8);
+    const PIO2_MID: f32 = 3.1391647e-7;
+    const PIO2_LO: f32 = 5.3903025e-15;
+    if x.abs() > 105615.0 {
+        return None;
+    }
+    let n = FastNum::fma(x, core::f32::consts::FRAC_2_PI, SHIFTER) - SHIFTER;
+    let r = FastNum::fma(n, -PIO2_HI, x);
+    let r = FastNum::fma(n, -PIO2_MID, r);
+    let r = FastNum::fma(n, -PIO2_LO, r);
+    Some((n as i32, r))
+}
+
+/// How a [`FastFloat`] treats subnormals and NaN and whether it clamps its results,
+/// by default with the [`FastNum`] methods, assuming there is no NaN
+pub trait FastPolicy<F: FastNum>: 'static + Copy + Default {
+    /// whether NaN is expected, otherwise `is_nan` is always false
+    const NAN: bool = false;
+    fn add(a: F, b: F) -> F {
+        a.fast_add(b)
+    }
+    fn sub(a: F, b: F) -> F {
+        a.fast_sub(b)
+    }
+    fn mul(a: F, b: F) -> F {
+        a.fast_mul(b)
+    }
+    fn div(a: F, b: F) -> F {
+        a.fast_div(b)
+    }
+    fn rem(a: F, b: F) -> F {
+        a.fast_rem(b)
+    }
+    fn fma(a: F, b: F, c: F) -> F {
+        FastNum::fma(a, b, c)
+    }
+    fn recip(a: F) -> F {
+        a.fast_recip()
+    }
+    fn sqrt(a: F) -> F {
+        a.fast_sqrt()
+    }
+    fn rsqrt(a: F) -> F {
+        a.fast_rsqrt()
+    }
+    fn sin(a: F) -> F {
+        a.fast_sin()
+    }
+    fn cos(a: F) -> F {
+        a.fast_cos()
+    }
+    fn log2(a: F) -> F {
+        a.fast_log2()
+    }
+    fn exp2(a: F) -> F {
+        a.fast_exp2()
+    }
+    fn ln(a: F) -> F {
+        a.fast_ln()
+    }
+    fn log10(a: F) -> F {
+        a.fast_log10()
+    }
+    fn exp(a: F) -> F {
+        a.fast_exp()
+    }
+    fn tanh(a: F) -> F {
+        a.fast_tanh()
+    }
+    fn pow(a: F, b: F) -> F {
+        a.fast_pow(b)
+    }
+    /// the ordering of `a` & `b` when either is NaN, which is assumed never to happen
+    fn unordered(a: F, b: F) -> Option<core::cmp::Ordering> {
+        let _ = (a, b);
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+    /// clamps to [0, 1], NaN becomes 0
+    fn saturate(a: F) -> F {
+        if a > F::zero() {
+            if a < F::one() {
+                a
+            } else {
+                F::one()
+            }
+        } else {
+            F::zero()
+        }
+    }
+}
+
+/// The [`FastNum`] methods as they are, the f32 approximations flush subnormal inputs and results
+/// to zero with `.ftz`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlushToZero;
+
+/// Keeps subnormals: the f32 approximations without `.ftz`, f32 division and reciprocals with `div.full`
+/// as `div.approx` flushes the quotient of a divisor above 2^126, and f64 reciprocals with `rcp.rn.f64`
+/// as `rcp.approx.f64` only exists with `.ftz`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ieee;
+
+/// Computes with `P` and clamps the result of the arithmetic and of every approximation to [0, 1]
+/// like `.sat`, NaN becomes 0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Saturate<P = FlushToZero>(pub P);
+
+/// Computes with `P`, but the arithmetic without the fast-math flags that assume there is no NaN,
+/// and a comparison with NaN prints the operands and the thread then panics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Checked<P = FlushToZero>(pub P);
+
+/// Computes with `P` like [`Checked`], NaN propagates and compares unordered like it does for the
+/// primitive floats
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PropagateNan<P = FlushToZero>(pub P);
+
+impl<F: FastNum> FastPolicy<F> for FlushToZero {}
+
+impl FastPolicy<f32> for Ieee {
+    fn div(a: f32, b: f32) -> f32 {
+        unsafe { div_full(a, b) }
+    }
+
+    fn recip(a: f32) -> f32 {
+        unsafe { div_full(1.0, a) }
+    }
+
+    fn sqrt(a: f32) -> f32 {
+        unsafe { sqrt_approx_noftz(a) }
+    }
+
+    fn rsqrt(a: f32) -> f32 {
+        unsafe { rsqrt_approx_noftz(a) }
+    }
+
+    fn sin(a: f32) -> f32 {
+        unsafe { sin_approx_noftz(a) }
+    }
+
+    fn cos(a: f32) -> f32 {
+        unsafe { cos_approx_noftz(a) }
+    }
+
+    fn log2(a: f32) -> f32 {
+        unsafe { lg2_approx_noftz(a) }
+    }
+
+    fn exp2(a: f32) -> f32 {
+        unsafe { ex2_approx_noftz(a) }
+    }
+
+    fn ln(a: f32) -> f32 {
+        const RECIP_LOG2_E: f32 = 1f32 / core::f32::consts::LOG2_E;
+        Self::log2(a) * RECIP_LOG2_E
+    }
+
+    fn log10(a: f32) -> f32 {
+        const RECIP_LOG2_10: f32 = 1f32 / core::f32::consts::LOG2_10;
+        Self::log2(a) * RECIP_LOG2_10
+    }
+
+    fn exp(a: f32) -> f32 {
+        Self::exp2(a * core::f32::consts::LOG2_E)
+    }
+
+    fn pow(a: f32, b: f32) -> f32 {
+        Self::exp2(b * Self::log2(a))
+    }
+
+    fn saturate(a: f32) -> f32 {
+        unsafe { saturate_f32(a) }
+    }
+}
+
+impl FastPolicy<f64> for Ieee {
+    fn recip(a: f64) -> f64 {
+        RoundedArith::<Rn>::rounded_recip(a)
+    }
+
+    fn saturate(a: f64) -> f64 {
+        unsafe { saturate_f64(a) }
+    }
+}
+
+/// the half precision approximations are computed in f32, where their subnormals are normal
+impl FastPolicy<Half> for Ieee {}
+
+impl FastPolicy<BHalf> for Ieee {}
+
+macro_rules! saturate_policy {
+    ($($op: ident($($arg: ident),+)),+) => {
+        impl<F: FastNum, P: FastPolicy<F>> FastPolicy<F> for Saturate<P> {
+            const NAN: bool = P::NAN;
+
+            $(fn $op($($arg: F),+) -> F {
+                P::saturate(P::$op($($arg),+))
+            })+
+
+            fn unordered(a: F, b: F) -> Option<core::cmp::Ordering> {
+                P::unordered(a, b)
+            }
+
+            fn saturate(a: F) -> F {
+                P::saturate(a)
+            }
+        }
+    };
+}
+
+saturate_policy! {
+    add(a, b), sub(a, b), mul(a, b), div(a, b), rem(a, b), fma(a, b, c), recip(a), sqrt(a), rsqrt(a),
+    sin(a), cos(a), log2(a), exp2(a), ln(a), log10(a), exp(a), tanh(a), pow(a, b)
+}
+
+macro_rules! nan_policy {
+    ($($Policy: ident),+) => {
+        $(impl<F: FastNum, P: FastPolicy<F>> FastPolicy<F> for $Policy<P> {
+            const NAN: bool = true;
+
+            fn add(a: F, b: F) -> F {
+                a + b
+            }
+
+            fn sub(a: F, b: F) -> F {
+                a - b
+            }
+
+            fn mul(a: F, b: F) -> F {
+                a * b
+            }
+
+            /// `P`'s reciprocal, which NaN passes through
+            fn div(a: F, b: F) -> F {
+                a * P::recip(b)
+            }
+
+            fn rem(a: F, b: F) -> F {
+                a % b
+            }
+
+            fn fma(a: F, b: F, c: F) -> F {
+                P::fma(a, b, c)
+            }
+
+            fn recip(a: F) -> F {
+                P::recip(a)
+            }
+
+            fn sqrt(a: F) -> F {
+                P::sqrt(a)
+            }
+
+            fn rsqrt(a: F) -> F {
+                P::rsqrt(a)
+            }
+
+            fn sin(a: F) -> F {
+                P::sin(a)
+            }
+
+            fn cos(a: F) -> F {
+                P::cos(a)
+            }
+
+            fn log2(a: F) -> F {
+                P::log2(a)
+            }
+
+            fn exp2(a: F) -> F {
+                P::exp2(a)
+            }
+
+            fn ln(a: F) -> F {
+                P::ln(a)
+            }
+
+            fn log10(a: F) -> F {
+                P::log10(a)
+            }
+
+            fn exp(a: F) -> F {
+                P::exp(a)
+            }
+
+            fn tanh(a: F) -> F {
+                P::tanh(a)
+            }
+
+            fn pow(a: F, b: F) -> F {
+                P::pow(a, b)
+            }
+
+            fn unordered(a: F, b: F) -> Option<core::cmp::Ordering> {
+                $Policy::<P>::nan_comparison(a, b)
+            }
+
+            fn saturate(a: F) -> F {
+                P::saturate(a)
+            }
+        })+
+    };
+}
+
+nan_policy! { Checked, PropagateNan }
+
+impl<P> Checked<P> {
+    #[cold]
+    #[inline(never)]
+    fn nan_comparison<F: FastNum>(a: F, b: F) -> Option<core::cmp::Ordering> {
+        let (a, b) = (
+            a.to_f64().unwrap_or(f64::NAN),
+            b.to_f64().unwrap_or(f64::NAN),
+        );
+        #[cfg(target_arch = "nvptx64")]
+        {
+            use crate::{blockIdx, threadIdx};
+            #[repr(C)]
+            struct Args(f64, f64, u32, u32, u32, u32, u32, u32);
+            let mut args = Args(
+                a,
+                b,
+                threadIdx::x(),
+                threadIdx::y(),
+                threadIdx::z(),
+                blockIdx::x(),
+                blockIdx::y(),
+                blockIdx::z(),
+            );
+            let format = "FastFloat comparison of %g and %g in thread (%u, %u, %u) of block (%u, %u, %u)\n\0";
+            unsafe {
+                crate::vprintf(format.as_ptr(), core::ptr::addr_of_mut!(args).cast());
+            }
+        }
+        panic!("NaN in a checked FastFloat comparison of {} and {}", a, b)
+    }
+}
+
+impl<P> PropagateNan<P> {
+    fn nan_comparison<F>(_: F, _: F) -> Option<core::cmp::Ordering> {
+        None
+    }
+}
+
+/// A float whose arithmetic uses the fast approximate instructions, with subnormals, NaN and results
+/// treated according to the policy `P`
+///
+/// Wrap a value with [`FastFloat::new`] or `From`, and unwrap it with [`FastFloat::into_inner`] or `Deref`.
 #[repr(transparent)]
-#[derive(
-    Debug,
-    Display,
-    Binary,
-    Octal,
-    LowerHex,
-    UpperHex,
-    LowerExp,
-    UpperExp,
-    Clone,
-    Copy,
-    From,
-    Deref,
-    DerefMut,
-    Neg,
-)]
-pub struct FastFloat<F>(pub F);
-
-impl<F: FastNum> PartialOrd for FastFloat<F> {
+#[derive(Clone, Copy)]
+pub struct FastFloat<F, P = FlushToZero>(F, PhantomData<P>);
+
+impl<F, P> FastFloat<F, P> {
+    pub const fn new(value: F) -> Self {
+        Self(value, PhantomData)
+    }
+
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
+
+impl<F, P> From<F> for FastFloat<F, P> {
+    fn from(value: F) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<F, P> Deref for FastFloat<F, P> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F, P> DerefMut for FastFloat<F, P> {
+    fn deref_mut(&mut self) -> &mut F {
+        &mut self.0
+    }
+}
+
+impl<F: Neg<Output = F>, P> Neg for FastFloat<F, P> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.0)
+    }
+}
+
+impl<F: fmt::Debug, P> fmt::Debug for FastFloat<F, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FastFloat").field(&self.0).finish()
+    }
+}
+
+macro_rules! impl_fmt {
+    ($($Fmt: ident),+) => {
+        $(impl<F: fmt::$Fmt, P> fmt::$Fmt for FastFloat<F, P> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::$Fmt::fmt(&self.0, f)
+            }
+        })+
+    };
+}
+
+impl_fmt! { Display, Binary, Octal, LowerHex, UpperHex, LowerExp, UpperExp }
+
+impl<F: FastNum, P: FastPolicy<F>> PartialOrd for FastFloat<F, P> {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         if FloatCore::is_nan(*self) || FloatCore::is_nan(*other) {
-            unsafe { core::hint::unreachable_unchecked() }
+            P::unordered(self.0, other.0)
         } else if self.0 < other.0 {
             Some(core::cmp::Ordering::Less)
         } else if self.0 == other.0 {
@@ -275,98 +1086,98 @@ impl<F: FastNum> PartialOrd for FastFloat<F> {
     }
 }
 
-impl<F: FastNum> PartialEq for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> PartialEq for FastFloat<F, P> {
     fn eq(&self, other: &Self) -> bool {
         if FloatCore::is_nan(*self) || FloatCore::is_nan(*other) {
-            unsafe { core::hint::unreachable_unchecked() }
+            return P::unordered(self.0, other.0) == Some(core::cmp::Ordering::Equal);
         }
         self.0 == other.0
     }
 }
 
-impl<F: FastNum> Add for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Add for FastFloat<F, P> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self(self.fast_add(*rhs))
+        Self::new(P::add(self.0, rhs.0))
     }
 }
 
-impl<F: FastNum> AddAssign for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> AddAssign for FastFloat<F, P> {
     fn add_assign(&mut self, rhs: Self) {
         *self = (*self).add(rhs);
     }
 }
 
-impl<F: FastNum> Sub for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Sub for FastFloat<F, P> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Self(self.fast_sub(*rhs))
+        Self::new(P::sub(self.0, rhs.0))
     }
 }
 
-impl<F: FastNum> SubAssign for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> SubAssign for FastFloat<F, P> {
     fn sub_assign(&mut self, rhs: Self) {
         *self = (*self).sub(rhs);
     }
 }
 
-impl<F: FastNum> Mul for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Mul for FastFloat<F, P> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.fast_mul(*rhs))
+        Self::new(P::mul(self.0, rhs.0))
     }
 }
 
-impl<F: FastNum> MulAssign for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> MulAssign for FastFloat<F, P> {
     fn mul_assign(&mut self, rhs: Self) {
         *self = (*self).mul(rhs);
     }
 }
 
-impl<F: FastNum> Div for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Div for FastFloat<F, P> {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        Self(self.fast_div(*rhs))
+        Self::new(P::div(self.0, rhs.0))
     }
 }
 
-impl<F: FastNum> DivAssign for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> DivAssign for FastFloat<F, P> {
     fn div_assign(&mut self, rhs: Self) {
         *self = (*self).div(rhs);
     }
 }
 
-impl<F: FastNum> Rem for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Rem for FastFloat<F, P> {
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        Self(self.fast_rem(*rhs))
+        Self::new(P::rem(self.0, rhs.0))
     }
 }
 
-impl<F: FastNum> RemAssign for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> RemAssign for FastFloat<F, P> {
     fn rem_assign(&mut self, rhs: Self) {
         *self = (*self).rem(rhs);
     }
 }
 
-impl<F: FastNum> FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> FastFloat<F, P> {
     pub fn rsqrt(self) -> Self {
-        Self(self.fast_rsqrt())
+        Self::new(P::rsqrt(self.0))
     }
 
     pub fn copysign(self, other: Self) -> Self {
-        Self(self.fast_copysign(*other))
+        Self::new(self.fast_copysign(*other))
     }
 }
 
-impl<F: FastNum> Zero for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Zero for FastFloat<F, P> {
     fn zero() -> Self {
-        Self(Zero::zero())
+        Self::new(Zero::zero())
     }
 
     fn is_zero(&self) -> bool {
@@ -374,21 +1185,21 @@ impl<F: FastNum> Zero for FastFloat<F> {
     }
 }
 
-impl<F: FastNum> One for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> One for FastFloat<F, P> {
     fn one() -> Self {
-        Self(One::one())
+        Self::new(One::one())
     }
 }
 
-impl<F: FastNum> Num for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Num for FastFloat<F, P> {
     type FromStrRadixErr = <F as Num>::FromStrRadixErr;
 
     fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
-        Num::from_str_radix(str, radix).map(FastFloat)
+        Num::from_str_radix(str, radix).map(FastFloat::new)
     }
 }
 
-impl<F: FastNum> ToPrimitive for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> ToPrimitive for FastFloat<F, P> {
     fn to_isize(&self) -> Option<isize> {
         ToPrimitive::to_isize(&self.0)
     }
@@ -448,13 +1259,15 @@ impl<F: FastNum> ToPrimitive for FastFloat<F> {
     }
 }
 
-impl<F: FastNum> NumCast for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> NumCast for FastFloat<F, P> {
     fn from<T: ToPrimitive>(n: T) -> Option<Self> {
-        NumCast::from(n).map(Self)
+        NumCast::from(n).map(Self::new)
     }
 }
 
-impl<T: 'static + Copy, F: FastNum + AsPrimitive<T>> AsPrimitive<T> for FastFloat<F> {
+impl<T: 'static + Copy, F: FastNum + AsPrimitive<T>, P: FastPolicy<F>> AsPrimitive<T>
+    for FastFloat<F, P>
+{
     fn as_(self) -> T {
         self.0.as_()
     }
@@ -462,9 +1275,9 @@ impl<T: 'static + Copy, F: FastNum + AsPrimitive<T>> AsPrimitive<T> for FastFloa
 
 macro_rules! impl_as_primitive {
     ($($T: ty),+) => {
-        $(impl<F: FastNum> AsPrimitive<FastFloat<F>> for $T where $T: AsPrimitive<F> {
-            fn as_(self) -> FastFloat<F> {
-                FastFloat(self.as_())
+        $(impl<F: FastNum, P: FastPolicy<F>> AsPrimitive<FastFloat<F, P>> for $T where $T: AsPrimitive<F> {
+            fn as_(self) -> FastFloat<F, P> {
+                FastFloat::new(self.as_())
             }
         })+
     };
@@ -472,37 +1285,37 @@ macro_rules! impl_as_primitive {
 
 impl_as_primitive! { u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64 }
 
-impl<F: FastNum> FloatCore for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> FloatCore for FastFloat<F, P> {
     fn infinity() -> Self {
-        Self(FloatCore::infinity())
+        Self::new(FloatCore::infinity())
     }
 
     fn neg_infinity() -> Self {
-        Self(FloatCore::neg_infinity())
+        Self::new(FloatCore::neg_infinity())
     }
 
     fn nan() -> Self {
-        Self(FloatCore::nan())
+        Self::new(FloatCore::nan())
     }
 
     fn neg_zero() -> Self {
-        Self(FloatCore::neg_zero())
+        Self::new(FloatCore::neg_zero())
     }
 
     fn min_value() -> Self {
-        Self(FloatCore::min_value())
+        Self::new(FloatCore::min_value())
     }
 
     fn min_positive_value() -> Self {
-        Self(FloatCore::min_positive_value())
+        Self::new(FloatCore::min_positive_value())
     }
 
     fn epsilon() -> Self {
-        Self(FloatCore::epsilon())
+        Self::new(FloatCore::epsilon())
     }
 
     fn max_value() -> Self {
-        Self(FloatCore::max_value())
+        Self::new(FloatCore::max_value())
     }
 
     fn classify(self) -> core::num::FpCategory {
@@ -510,11 +1323,11 @@ impl<F: FastNum> FloatCore for FastFloat<F> {
     }
 
     fn to_degrees(self) -> Self {
-        Self(FloatCore::to_degrees(*self))
+        Self::new(FloatCore::to_degrees(*self))
     }
 
     fn to_radians(self) -> Self {
-        Self(FloatCore::to_radians(*self))
+        Self::new(FloatCore::to_radians(*self))
     }
 
     fn integer_decode(self) -> (u64, i16, i8) {
@@ -522,43 +1335,44 @@ impl<F: FastNum> FloatCore for FastFloat<F> {
     }
 
     fn abs(self) -> Self {
-        Self(self.fast_abs())
+        Self::new(self.fast_abs())
     }
 
     fn floor(self) -> Self {
-        Self(self.fast_floor())
+        Self::new(self.fast_floor())
     }
 
     fn ceil(self) -> Self {
-        Self(self.fast_ceil())
+        Self::new(self.fast_ceil())
     }
 
     fn trunc(self) -> Self {
-        Self(self.fast_trunc())
+        Self::new(self.fast_trunc())
     }
 
     fn fract(self) -> Self {
-        Self(self.fast_fract())
+        Self::new(self.fast_fract())
     }
 
     fn is_nan(self) -> bool {
-        false
+        P::NAN && FloatCore::is_nan(self.0)
     }
 
+    /// the sign bit, so true for -0 and NaN never compares
     fn is_sign_positive(self) -> bool {
-        self >= Self::zero()
+        FloatCore::is_sign_positive(self.0)
     }
 
     fn is_sign_negative(self) -> bool {
-        self < Self::zero()
+        FloatCore::is_sign_negative(self.0)
     }
 
     fn recip(self) -> Self {
-        Self(self.fast_recip())
+        Self::new(P::recip(self.0))
     }
 }
 
-impl<F: FastNum> Float for FastFloat<F> {
+impl<F: FastNum, P: FastPolicy<F>> Float for FastFloat<F, P> {
     fn nan() -> Self {
         FloatCore::nan()
     }
@@ -620,15 +1434,15 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn trunc(self) -> Self {
-        Self(self.fast_trunc())
+        Self::new(self.fast_trunc())
     }
 
     fn fract(self) -> Self {
-        Self(self.fast_fract())
+        Self::new(self.fast_fract())
     }
 
     fn abs(self) -> Self {
-        Self(self.fast_abs())
+        Self::new(self.fast_abs())
     }
 
     fn signum(self) -> Self {
@@ -644,11 +1458,11 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn mul_add(self, a: Self, b: Self) -> Self {
-        Self(self.fma(*a, *b))
+        Self::new(P::fma(self.0, a.0, b.0))
     }
 
     fn recip(self) -> Self {
-        Self(self.fast_recip())
+        Self::new(P::recip(self.0))
     }
 
     fn powi(self, n: i32) -> Self {
@@ -656,23 +1470,23 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn powf(self, n: Self) -> Self {
-        Self(Float::powf(self.0, n.0))
+        Self::new(P::pow(self.0, n.0))
     }
 
     fn sqrt(self) -> Self {
-        Self(self.fast_sqrt())
+        Self::new(P::sqrt(self.0))
     }
 
     fn exp(self) -> Self {
-        Self(self.fast_exp())
+        Self::new(P::exp(self.0))
     }
 
     fn exp2(self) -> Self {
-        Self(self.fast_exp2())
+        Self::new(P::exp2(self.0))
     }
 
     fn ln(self) -> Self {
-        Self(self.fast_ln())
+        Self::new(P::ln(self.0))
     }
 
     fn log(self, base: Self) -> Self {
@@ -680,11 +1494,11 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn log2(self) -> Self {
-        Self(self.fast_log2())
+        Self::new(P::log2(self.0))
     }
 
     fn log10(self) -> Self {
-        Self(self.fast_log10())
+        Self::new(P::log10(self.0))
     }
 
     fn to_degrees(self) -> Self {
@@ -712,40 +1526,40 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn cbrt(self) -> Self {
-        Self(Float::cbrt(self.0))
+        Self::new(Float::cbrt(self.0))
     }
 
     fn hypot(self, other: Self) -> Self {
-        Self(Float::hypot(self.0, other.0))
+        Self::new(Float::hypot(self.0, other.0))
     }
 
     fn sin(self) -> Self {
-        Self(self.fast_sin())
+        Self::new(P::sin(self.0))
     }
 
     fn cos(self) -> Self {
-        Self(self.fast_cos())
+        Self::new(P::cos(self.0))
     }
 
     fn tan(self) -> Self {
         // <Self as Float>::sin(self) / <Self as Float>::cos(self)
-        Self(Float::tan(self.0))
+        Self::new(Float::tan(self.0))
     }
 
     fn asin(self) -> Self {
-        Self(Float::asin(self.0))
+        Self::new(Float::asin(self.0))
     }
 
     fn acos(self) -> Self {
-        Self(Float::acos(self.0))
+        Self::new(Float::acos(self.0))
     }
 
     fn atan(self) -> Self {
-        Self(Float::atan(self.0))
+        Self::new(Float::atan(self.0))
     }
 
     fn atan2(self, other: Self) -> Self {
-        Self(Float::atan2(self.0, other.0))
+        Self::new(self.0.fast_atan2(other.0))
     }
 
     fn sin_cos(self) -> (Self, Self) {
@@ -753,35 +1567,35 @@ impl<F: FastNum> Float for FastFloat<F> {
     }
 
     fn exp_m1(self) -> Self {
-        Self(Float::exp_m1(self.0))
+        Self::new(Float::exp_m1(self.0))
     }
 
     fn ln_1p(self) -> Self {
-        Self(Float::ln_1p(self.0))
+        Self::new(Float::ln_1p(self.0))
     }
 
     fn sinh(self) -> Self {
-        Self(Float::sinh(self.0))
+        Self::new(Float::sinh(self.0))
     }
 
     fn cosh(self) -> Self {
-        Self(Float::cosh(self.0))
+        Self::new(Float::cosh(self.0))
     }
 
     fn tanh(self) -> Self {
-        Self(Float::tanh(self.0))
+        Self::new(P::tanh(self.0))
     }
 
     fn asinh(self) -> Self {
-        Self(Float::asinh(self.0))
+        Self::new(Float::asinh(self.0))
     }
 
     fn acosh(self) -> Self {
-        Self(Float::acosh(self.0))
+        Self::new(Float::acosh(self.0))
     }
 
     fn atanh(self) -> Self {
-        Self(Float::atanh(self.0))
+        Self::new(Float::atanh(self.0))
     }
 
     fn integer_decode(self) -> (u64, i16, i8) {
@@ -791,25 +1605,25 @@ impl<F: FastNum> Float for FastFloat<F> {
 
 use float_eq::*;
 
-impl<F: FloatEqUlpsTol> FloatEqUlpsTol for FastFloat<F>
+impl<F: FloatEqUlpsTol, P> FloatEqUlpsTol for FastFloat<F, P>
 where
     UlpsTol<F>: Sized,
 {
-    type UlpsTol = FastFloat<UlpsTol<F>>;
+    type UlpsTol = FastFloat<UlpsTol<F>, P>;
 }
 
-impl<F: FloatEqDebugUlpsDiff> FloatEqDebugUlpsDiff for FastFloat<F> {
-    type DebugUlpsDiff = FastFloat<DebugUlpsDiff<F>>;
+impl<F: FloatEqDebugUlpsDiff, P> FloatEqDebugUlpsDiff for FastFloat<F, P> {
+    type DebugUlpsDiff = FastFloat<DebugUlpsDiff<F>, P>;
 }
 
-impl<F> FloatEq for FastFloat<F>
+impl<F, P> FloatEq for FastFloat<F, P>
 where
     F: FloatEq + FloatEqUlpsTol,
     F::Tol: Sized,
     UlpsTol<F>: Sized,
     UlpsTol<F::Tol>: Sized,
 {
-    type Tol = FastFloat<F::Tol>;
+    type Tol = FastFloat<F::Tol, P>;
 
     fn eq_abs(&self, other: &Self, max_diff: &Self::Tol) -> bool {
         self.0.eq_abs(&other.0, &max_diff.0)
@@ -836,7 +1650,7 @@ where
     }
 }
 
-impl<F> AssertFloatEq for FastFloat<F>
+impl<F, P> AssertFloatEq for FastFloat<F, P>
 where
     F: FloatEqUlpsTol + AssertFloatEq + core::fmt::Debug,
     F::Tol: Sized,
@@ -845,42 +1659,42 @@ where
     UlpsTol<F::Tol>: Sized,
     UlpsTol<F::DebugTol>: Sized,
 {
-    type DebugAbsDiff = FastFloat<F::DebugAbsDiff>;
+    type DebugAbsDiff = FastFloat<F::DebugAbsDiff, P>;
 
-    type DebugTol = FastFloat<F::DebugTol>;
+    type DebugTol = FastFloat<F::DebugTol, P>;
 
     fn debug_abs_diff(&self, other: &Self) -> Self::DebugAbsDiff {
-        FastFloat(self.0.debug_abs_diff(&other.0))
+        FastFloat::new(self.0.debug_abs_diff(&other.0))
     }
 
     fn debug_ulps_diff(&self, other: &Self) -> DebugUlpsDiff<Self::DebugAbsDiff> {
-        FastFloat(self.0.debug_ulps_diff(&other.0))
+        FastFloat::new(self.0.debug_ulps_diff(&other.0))
     }
 
     fn debug_abs_tol(&self, other: &Self, max_diff: &Self::Tol) -> Self::DebugTol {
-        FastFloat(self.0.debug_abs_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_abs_tol(&other.0, &max_diff.0))
     }
 
     fn debug_rmax_tol(&self, other: &Self, max_diff: &Self::Tol) -> Self::DebugTol {
-        FastFloat(self.0.debug_rmax_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_rmax_tol(&other.0, &max_diff.0))
     }
 
     fn debug_rmin_tol(&self, other: &Self, max_diff: &Self::Tol) -> Self::DebugTol {
-        FastFloat(self.0.debug_rmin_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_rmin_tol(&other.0, &max_diff.0))
     }
 
     fn debug_r1st_tol(&self, other: &Self, max_diff: &Self::Tol) -> Self::DebugTol {
-        FastFloat(self.0.debug_r1st_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_r1st_tol(&other.0, &max_diff.0))
     }
 
     fn debug_r2nd_tol(&self, other: &Self, max_diff: &Self::Tol) -> Self::DebugTol {
-        FastFloat(self.0.debug_r2nd_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_r2nd_tol(&other.0, &max_diff.0))
     }
 
     fn debug_ulps_tol(&self, other: &Self, max_diff: &UlpsTol<Self::Tol>) -> UlpsTol<Self::DebugTol>
     where
         UlpsTol<Self::DebugTol>: Sized,
     {
-        FastFloat(self.0.debug_ulps_tol(&other.0, &max_diff.0))
+        FastFloat::new(self.0.debug_ulps_tol(&other.0, &max_diff.0))
     }
 }
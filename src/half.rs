@@ -0,0 +1,976 @@
+use crate::FastNum;
+use core::cmp::Ordering;
+use core::fmt;
+use core::num::FpCategory;
+use core::ops::*;
+use num_traits::{float::FloatCore, Float, Num, NumCast, One, ToPrimitive, Zero};
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#half-precision-floating-point-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#data-movement-and-conversion-instructions-cvt
+
+Half precision values are stored as their bit patterns, so they work on any target. On the device the
+conversions and f16 arithmetic use the PTX instructions (add/sub/mul/fma.rn.f16 need sm_53, cvt.rn.bf16.f32 and
+fma.rn.bf16 sm_80, ex2/tanh.approx.f16 sm_75), everything else widens to f32, computes there and rounds back.
+The host uses software implementations that round to nearest even and give the same bits as the hardware,
+double rounding through f32 is innocuous for add, sub, mul, div, sqrt as f32 has more than 2p + 2 bits,
+and fma rounds to odd through f64 first. NaN results are the canonical 0x7fff.
+*/
+
+#[cfg(target_arch = "nvptx64")]
+use self::device as imp;
+#[cfg(not(target_arch = "nvptx64"))]
+use self::soft as imp;
+
+#[cfg(target_arch = "nvptx64")]
+mod device {
+    use core::arch::asm;
+
+    macro_rules! unary {
+        ($name:ident, $op:literal, $Out:ty, $out:ident, $In:ty, $in:ident) => {
+            #[inline]
+            pub fn $name(a: $In) -> $Out {
+                let d: $Out;
+                unsafe {
+                    asm!(concat!($op, " {}, {};"), out($out) d, in($in) a, options(pure, nomem, nostack));
+                }
+                d
+            }
+        };
+    }
+
+    macro_rules! binary {
+        ($name:ident, $op:literal) => {
+            #[inline]
+            pub fn $name(a: u16, b: u16) -> u16 {
+                let d: u16;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {};"),
+                        out(reg16) d, in(reg16) a, in(reg16) b,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    macro_rules! ternary {
+        ($name:ident, $op:literal) => {
+            #[inline]
+            pub fn $name(a: u16, b: u16, c: u16) -> u16 {
+                let d: u16;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {}, {};"),
+                        out(reg16) d, in(reg16) a, in(reg16) b, in(reg16) c,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    unary!(f16_to_f32, "cvt.f32.f16", f32, reg32, u16, reg16);
+    unary!(f32_to_f16, "cvt.rn.f16.f32", u16, reg16, f32, reg32);
+    unary!(f32_to_bf16, "cvt.rn.bf16.f32", u16, reg16, f32, reg32);
+    unary!(f16_exp2_approx, "ex2.approx.f16", u16, reg16, u16, reg16);
+    unary!(f16_tanh_approx, "tanh.approx.f16", u16, reg16, u16, reg16);
+    binary!(f16_add, "add.rn.f16");
+    binary!(f16_sub, "sub.rn.f16");
+    binary!(f16_mul, "mul.rn.f16");
+    ternary!(f16_fma, "fma.rn.f16");
+    ternary!(bf16_fma, "fma.rn.bf16");
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    use super::round_to_odd;
+
+    /// exact widening
+    pub fn f16_to_f32(bits: u16) -> f32 {
+        let bits = bits as u32;
+        let sign = (bits & 0x8000) << 16;
+        let exponent = (bits >> 10) & 0x1f;
+        let mantissa = bits & 0x3ff;
+        let magnitude = match exponent {
+            // subnormal, mantissa * 2^-24
+            0 => (mantissa as f32 * (1.0 / 16777216.0)).to_bits(),
+            0x1f => 0x7f80_0000 | (mantissa << 13),
+            _ => ((exponent + 112) << 23) | (mantissa << 13),
+        };
+        f32::from_bits(sign | magnitude)
+    }
+
+    /// rounds to nearest, ties to even
+    pub fn f32_to_f16(value: f32) -> u16 {
+        let bits = value.to_bits();
+        if value.is_nan() {
+            return 0x7fff;
+        }
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+        let mantissa = bits & 0x7f_ffff;
+        if exponent >= 0x1f {
+            return sign | 0x7c00;
+        }
+        // the result's mantissa before rounding and the number of bits to round away
+        let (truncated, mantissa, shift) = if exponent > 0 {
+            (((exponent as u32) << 10) | (mantissa >> 13), mantissa, 13)
+        } else if exponent >= -10 {
+            let mantissa = mantissa | 0x80_0000;
+            let shift = (14 - exponent) as u32;
+            (mantissa >> shift, mantissa, shift)
+        } else {
+            return sign;
+        };
+        let rest = mantissa & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        let round_up = rest > half || (rest == half && truncated & 1 == 1);
+        // a carry out of the mantissa correctly bumps the exponent, up to infinity
+        sign | (truncated + round_up as u32) as u16
+    }
+
+    /// rounds to nearest, ties to even
+    pub fn f32_to_bf16(value: f32) -> u16 {
+        let bits = value.to_bits();
+        if value.is_nan() {
+            return 0x7fff;
+        }
+        ((bits + 0x7fff + ((bits >> 16) & 1)) >> 16) as u16
+    }
+
+    pub fn f16_exp2_approx(a: u16) -> u16 {
+        f32_to_f16(libm::exp2f(f16_to_f32(a)))
+    }
+
+    pub fn f16_tanh_approx(a: u16) -> u16 {
+        f32_to_f16(libm::tanhf(f16_to_f32(a)))
+    }
+
+    pub fn f16_add(a: u16, b: u16) -> u16 {
+        f32_to_f16(f16_to_f32(a) + f16_to_f32(b))
+    }
+
+    pub fn f16_sub(a: u16, b: u16) -> u16 {
+        f32_to_f16(f16_to_f32(a) - f16_to_f32(b))
+    }
+
+    pub fn f16_mul(a: u16, b: u16) -> u16 {
+        f32_to_f16(f16_to_f32(a) * f16_to_f32(b))
+    }
+
+    pub fn f16_fma(a: u16, b: u16, c: u16) -> u16 {
+        let [a, b, c] = [a, b, c].map(|x| f16_to_f32(x) as f64);
+        f32_to_f16(fma_to_odd(a, b, c))
+    }
+
+    pub fn bf16_fma(a: u16, b: u16, c: u16) -> u16 {
+        let [a, b, c] = [a, b, c].map(|x| f32::from_bits((x as u32) << 16) as f64);
+        f32_to_bf16(fma_to_odd(a, b, c))
+    }
+
+    /// a * b + c rounded to odd f32, for a * b exact in f64
+    fn fma_to_odd(a: f64, b: f64, c: f64) -> f32 {
+        let product = a * b;
+        let sum = product + c;
+        // two-sum, the rounding error of the sum
+        let c_part = sum - product;
+        let error = (product - (sum - c_part)) + (c - c_part);
+        let sum = if sum.is_finite() && error != 0.0 && sum.to_bits() & 1 == 0 {
+            if (error > 0.0) == (sum > 0.0) {
+                f64::from_bits(sum.to_bits() + 1)
+            } else {
+                f64::from_bits(sum.to_bits() - 1)
+            }
+        } else {
+            sum
+        };
+        round_to_odd(sum)
+    }
+}
+
+/// rounds to odd f32, which rounds correctly again to any format with at most 22 bits of precision
+fn round_to_odd(value: f64) -> f32 {
+    let rounded = value as f32;
+    let bits = rounded.to_bits();
+    if rounded.is_infinite() || rounded as f64 == value || bits & 1 == 1 {
+        rounded
+    } else if (value > rounded as f64) == rounded.is_sign_positive() {
+        f32::from_bits(bits + 1)
+    } else {
+        f32::from_bits(bits - 1)
+    }
+}
+
+/// IEEE 754 binary16, stored as its bit pattern, named apart from the primitive `f16` that wmma's registers hold
+#[repr(transparent)]
+#[derive(Clone, Copy, Default)]
+pub struct Half(u16);
+
+impl Half {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(0x3c00);
+    pub const INFINITY: Self = Self(0x7c00);
+    pub const NEG_INFINITY: Self = Self(0xfc00);
+    pub const NAN: Self = Self(0x7fff);
+    pub const MAX: Self = Self(0x7bff);
+    pub const MIN: Self = Self(0xfbff);
+    pub const MIN_POSITIVE: Self = Self(0x0400);
+    pub const EPSILON: Self = Self(0x1400);
+
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// exact widening, `cvt.f32.f16`
+    pub fn to_f32(self) -> f32 {
+        imp::f16_to_f32(self.0)
+    }
+
+    /// rounds to nearest, ties to even, `cvt.rn.f16.f32`
+    pub fn from_f32(value: f32) -> Self {
+        Self(imp::f32_to_f16(value))
+    }
+
+    /// `fma.rn.f16`, self * b + c with a single rounding
+    pub fn fma(self, b: Self, c: Self) -> Self {
+        Self(imp::f16_fma(self.0, b.0, c.0))
+    }
+
+    /// `ex2.approx.f16`
+    pub fn exp2_approx(self) -> Self {
+        Self(imp::f16_exp2_approx(self.0))
+    }
+
+    /// `tanh.approx.f16`
+    pub fn tanh_approx(self) -> Self {
+        Self(imp::f16_tanh_approx(self.0))
+    }
+}
+
+impl Add for Half {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(imp::f16_add(self.0, rhs.0))
+    }
+}
+
+impl Sub for Half {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(imp::f16_sub(self.0, rhs.0))
+    }
+}
+
+impl Mul for Half {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(imp::f16_mul(self.0, rhs.0))
+    }
+}
+
+/// bfloat16, the upper half of an f32, stored as its bit pattern
+#[repr(transparent)]
+#[derive(Clone, Copy, Default)]
+pub struct BHalf(u16);
+
+impl BHalf {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(0x3f80);
+    pub const INFINITY: Self = Self(0x7f80);
+    pub const NEG_INFINITY: Self = Self(0xff80);
+    pub const NAN: Self = Self(0x7fff);
+    pub const MAX: Self = Self(0x7f7f);
+    pub const MIN: Self = Self(0xff7f);
+    pub const MIN_POSITIVE: Self = Self(0x0080);
+    pub const EPSILON: Self = Self(0x3c00);
+
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    /// exact widening
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+
+    /// rounds to nearest, ties to even, `cvt.rn.bf16.f32`
+    pub fn from_f32(value: f32) -> Self {
+        Self(imp::f32_to_bf16(value))
+    }
+
+    /// `fma.rn.bf16`, self * b + c with a single rounding
+    pub fn fma(self, b: Self, c: Self) -> Self {
+        Self(imp::bf16_fma(self.0, b.0, c.0))
+    }
+
+    /// approximated in f32, `ex2.approx.ftz.f`
+    pub fn exp2_approx(self) -> Self {
+        Self::from_f32(self.to_f32().fast_exp2())
+    }
+
+    /// approximated in f32, `tanh.approx.f32`
+    pub fn tanh_approx(self) -> Self {
+        Self::from_f32(self.to_f32().fast_tanh())
+    }
+}
+
+impl Add for BHalf {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_f32(self.to_f32() + rhs.to_f32())
+    }
+}
+
+impl Sub for BHalf {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_f32(self.to_f32() - rhs.to_f32())
+    }
+}
+
+impl Mul for BHalf {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::from_f32(self.to_f32() * rhs.to_f32())
+    }
+}
+
+/// everything both formats compute the same way, `$exponent` and `$mantissa` are the masks of their fields
+macro_rules! half_float {
+    ($F:ident, $exponent:literal, $mantissa:literal) => {
+        impl $F {
+            /// rounds to nearest, ties to even
+            pub fn from_f64(value: f64) -> Self {
+                Self::from_f32(round_to_odd(value))
+            }
+
+            /// exact widening
+            pub fn to_f64(self) -> f64 {
+                self.to_f32() as f64
+            }
+        }
+
+        impl From<$F> for f32 {
+            fn from(value: $F) -> Self {
+                value.to_f32()
+            }
+        }
+
+        impl From<$F> for f64 {
+            fn from(value: $F) -> Self {
+                value.to_f64()
+            }
+        }
+
+        impl fmt::Debug for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&$F::to_f32(*self), f)
+            }
+        }
+
+        impl fmt::Display for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&$F::to_f32(*self), f)
+            }
+        }
+
+        impl fmt::LowerExp for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::LowerExp::fmt(&$F::to_f32(*self), f)
+            }
+        }
+
+        impl fmt::UpperExp for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::UpperExp::fmt(&$F::to_f32(*self), f)
+            }
+        }
+
+        impl PartialEq for $F {
+            fn eq(&self, other: &Self) -> bool {
+                $F::to_f32(*self) == $F::to_f32(*other)
+            }
+        }
+
+        impl PartialOrd for $F {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                $F::to_f32(*self).partial_cmp(&$F::to_f32(*other))
+            }
+        }
+
+        impl Neg for $F {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(self.0 ^ 0x8000)
+            }
+        }
+
+        impl Div for $F {
+            type Output = Self;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                Self::from_f32(self.to_f32() / rhs.to_f32())
+            }
+        }
+
+        impl Rem for $F {
+            type Output = Self;
+
+            /// exact, the remainder is always representable
+            fn rem(self, rhs: Self) -> Self::Output {
+                Self::from_f32(self.to_f32() % rhs.to_f32())
+            }
+        }
+
+        impl AddAssign for $F {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = (*self).add(rhs);
+            }
+        }
+
+        impl SubAssign for $F {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = (*self).sub(rhs);
+            }
+        }
+
+        impl MulAssign for $F {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = (*self).mul(rhs);
+            }
+        }
+
+        impl DivAssign for $F {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = (*self).div(rhs);
+            }
+        }
+
+        impl RemAssign for $F {
+            fn rem_assign(&mut self, rhs: Self) {
+                *self = (*self).rem(rhs);
+            }
+        }
+
+        impl Zero for $F {
+            fn zero() -> Self {
+                Self::ZERO
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 & 0x7fff == 0
+            }
+        }
+
+        impl One for $F {
+            fn one() -> Self {
+                Self::ONE
+            }
+        }
+
+        impl Num for $F {
+            type FromStrRadixErr = <f32 as Num>::FromStrRadixErr;
+
+            fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                <f64 as Num>::from_str_radix(str, radix).map(Self::from_f64)
+            }
+        }
+
+        impl ToPrimitive for $F {
+            fn to_i64(&self) -> Option<i64> {
+                ToPrimitive::to_i64(&$F::to_f32(*self))
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                ToPrimitive::to_u64(&$F::to_f32(*self))
+            }
+
+            fn to_f32(&self) -> Option<f32> {
+                Some($F::to_f32(*self))
+            }
+
+            fn to_f64(&self) -> Option<f64> {
+                Some($F::to_f64(*self))
+            }
+        }
+
+        impl NumCast for $F {
+            fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+                n.to_f64().map(Self::from_f64)
+            }
+        }
+
+        impl FloatCore for $F {
+            fn infinity() -> Self {
+                Self::INFINITY
+            }
+
+            fn neg_infinity() -> Self {
+                Self::NEG_INFINITY
+            }
+
+            fn nan() -> Self {
+                Self::NAN
+            }
+
+            fn neg_zero() -> Self {
+                Self(0x8000)
+            }
+
+            fn min_value() -> Self {
+                Self::MIN
+            }
+
+            fn min_positive_value() -> Self {
+                Self::MIN_POSITIVE
+            }
+
+            fn epsilon() -> Self {
+                Self::EPSILON
+            }
+
+            fn max_value() -> Self {
+                Self::MAX
+            }
+
+            fn classify(self) -> FpCategory {
+                match (self.0 & $exponent, self.0 & $mantissa) {
+                    (0, 0) => FpCategory::Zero,
+                    (0, _) => FpCategory::Subnormal,
+                    ($exponent, 0) => FpCategory::Infinite,
+                    ($exponent, _) => FpCategory::Nan,
+                    _ => FpCategory::Normal,
+                }
+            }
+
+            fn to_degrees(self) -> Self {
+                Self::from_f32(FloatCore::to_degrees(self.to_f32()))
+            }
+
+            fn to_radians(self) -> Self {
+                Self::from_f32(FloatCore::to_radians(self.to_f32()))
+            }
+
+            fn integer_decode(self) -> (u64, i16, i8) {
+                FloatCore::integer_decode(self.to_f32())
+            }
+
+            fn is_nan(self) -> bool {
+                self.0 & 0x7fff > $exponent
+            }
+
+            fn is_sign_positive(self) -> bool {
+                self.0 & 0x8000 == 0
+            }
+
+            fn is_sign_negative(self) -> bool {
+                self.0 & 0x8000 != 0
+            }
+
+            fn abs(self) -> Self {
+                Self(self.0 & 0x7fff)
+            }
+
+            fn floor(self) -> Self {
+                Self::from_f32(FloatCore::floor(self.to_f32()))
+            }
+
+            fn ceil(self) -> Self {
+                Self::from_f32(FloatCore::ceil(self.to_f32()))
+            }
+
+            fn round(self) -> Self {
+                Self::from_f32(FloatCore::round(self.to_f32()))
+            }
+
+            fn trunc(self) -> Self {
+                Self::from_f32(FloatCore::trunc(self.to_f32()))
+            }
+        }
+
+        impl Float for $F {
+            fn nan() -> Self {
+                FloatCore::nan()
+            }
+
+            fn infinity() -> Self {
+                FloatCore::infinity()
+            }
+
+            fn neg_infinity() -> Self {
+                FloatCore::neg_infinity()
+            }
+
+            fn neg_zero() -> Self {
+                FloatCore::neg_zero()
+            }
+
+            fn min_value() -> Self {
+                FloatCore::min_value()
+            }
+
+            fn min_positive_value() -> Self {
+                FloatCore::min_positive_value()
+            }
+
+            fn epsilon() -> Self {
+                FloatCore::epsilon()
+            }
+
+            fn max_value() -> Self {
+                FloatCore::max_value()
+            }
+
+            fn is_nan(self) -> bool {
+                FloatCore::is_nan(self)
+            }
+
+            fn is_infinite(self) -> bool {
+                FloatCore::is_infinite(self)
+            }
+
+            fn is_finite(self) -> bool {
+                FloatCore::is_finite(self)
+            }
+
+            fn is_normal(self) -> bool {
+                FloatCore::is_normal(self)
+            }
+
+            fn classify(self) -> FpCategory {
+                FloatCore::classify(self)
+            }
+
+            fn floor(self) -> Self {
+                FloatCore::floor(self)
+            }
+
+            fn ceil(self) -> Self {
+                FloatCore::ceil(self)
+            }
+
+            fn round(self) -> Self {
+                FloatCore::round(self)
+            }
+
+            fn trunc(self) -> Self {
+                FloatCore::trunc(self)
+            }
+
+            fn fract(self) -> Self {
+                FloatCore::fract(self)
+            }
+
+            fn abs(self) -> Self {
+                FloatCore::abs(self)
+            }
+
+            fn signum(self) -> Self {
+                FloatCore::signum(self)
+            }
+
+            fn is_sign_positive(self) -> bool {
+                FloatCore::is_sign_positive(self)
+            }
+
+            fn is_sign_negative(self) -> bool {
+                FloatCore::is_sign_negative(self)
+            }
+
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self.fma(a, b)
+            }
+
+            fn recip(self) -> Self {
+                Self::ONE / self
+            }
+
+            fn powi(self, n: i32) -> Self {
+                Self::from_f32(Float::powi(self.to_f32(), n))
+            }
+
+            fn powf(self, n: Self) -> Self {
+                Self::from_f32(Float::powf(self.to_f32(), n.to_f32()))
+            }
+
+            fn sqrt(self) -> Self {
+                Self::from_f32(Float::sqrt(self.to_f32()))
+            }
+
+            fn exp(self) -> Self {
+                Self::from_f32(Float::exp(self.to_f32()))
+            }
+
+            fn exp2(self) -> Self {
+                Self::from_f32(Float::exp2(self.to_f32()))
+            }
+
+            fn ln(self) -> Self {
+                Self::from_f32(Float::ln(self.to_f32()))
+            }
+
+            fn log(self, base: Self) -> Self {
+                Self::from_f32(Float::log(self.to_f32(), base.to_f32()))
+            }
+
+            fn log2(self) -> Self {
+                Self::from_f32(Float::log2(self.to_f32()))
+            }
+
+            fn log10(self) -> Self {
+                Self::from_f32(Float::log10(self.to_f32()))
+            }
+
+            fn to_degrees(self) -> Self {
+                FloatCore::to_degrees(self)
+            }
+
+            fn to_radians(self) -> Self {
+                FloatCore::to_radians(self)
+            }
+
+            fn max(self, other: Self) -> Self {
+                FloatCore::max(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                FloatCore::min(self, other)
+            }
+
+            fn abs_sub(self, other: Self) -> Self {
+                if self <= other {
+                    Self::ZERO
+                } else {
+                    self - other
+                }
+            }
+
+            fn cbrt(self) -> Self {
+                Self::from_f32(Float::cbrt(self.to_f32()))
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                Self::from_f32(Float::hypot(self.to_f32(), other.to_f32()))
+            }
+
+            fn sin(self) -> Self {
+                Self::from_f32(Float::sin(self.to_f32()))
+            }
+
+            fn cos(self) -> Self {
+                Self::from_f32(Float::cos(self.to_f32()))
+            }
+
+            fn tan(self) -> Self {
+                Self::from_f32(Float::tan(self.to_f32()))
+            }
+
+            fn asin(self) -> Self {
+                Self::from_f32(Float::asin(self.to_f32()))
+            }
+
+            fn acos(self) -> Self {
+                Self::from_f32(Float::acos(self.to_f32()))
+            }
+
+            fn atan(self) -> Self {
+                Self::from_f32(Float::atan(self.to_f32()))
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                Self::from_f32(Float::atan2(self.to_f32(), other.to_f32()))
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                let (sin, cos) = Float::sin_cos(self.to_f32());
+                (Self::from_f32(sin), Self::from_f32(cos))
+            }
+
+            fn exp_m1(self) -> Self {
+                Self::from_f32(Float::exp_m1(self.to_f32()))
+            }
+
+            fn ln_1p(self) -> Self {
+                Self::from_f32(Float::ln_1p(self.to_f32()))
+            }
+
+            fn sinh(self) -> Self {
+                Self::from_f32(Float::sinh(self.to_f32()))
+            }
+
+            fn cosh(self) -> Self {
+                Self::from_f32(Float::cosh(self.to_f32()))
+            }
+
+            fn tanh(self) -> Self {
+                Self::from_f32(Float::tanh(self.to_f32()))
+            }
+
+            fn asinh(self) -> Self {
+                Self::from_f32(Float::asinh(self.to_f32()))
+            }
+
+            fn acosh(self) -> Self {
+                Self::from_f32(Float::acosh(self.to_f32()))
+            }
+
+            fn atanh(self) -> Self {
+                Self::from_f32(Float::atanh(self.to_f32()))
+            }
+
+            fn integer_decode(self) -> (u64, i16, i8) {
+                FloatCore::integer_decode(self)
+            }
+
+            fn copysign(self, sign: Self) -> Self {
+                Self((self.0 & 0x7fff) | (sign.0 & 0x8000))
+            }
+        }
+
+        /// the approximations are the f32 ones, the result rounded back
+        impl FastNum for $F {
+            fn fma(self, b: Self, c: Self) -> Self {
+                $F::fma(self, b, c)
+            }
+
+            fn fast_add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+
+            fn fast_sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+
+            fn fast_mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+
+            fn fast_div(self, rhs: Self) -> Self {
+                Self::from_f32(self.to_f32().fast_div(rhs.to_f32()))
+            }
+
+            fn fast_rem(self, rhs: Self) -> Self {
+                self % rhs
+            }
+
+            fn fast_recip(self) -> Self {
+                Self::from_f32(self.to_f32().fast_recip())
+            }
+
+            fn fast_sqrt(self) -> Self {
+                Self::from_f32(self.to_f32().fast_sqrt())
+            }
+
+            fn fast_sin(self) -> Self {
+                Self::from_f32(self.to_f32().fast_sin())
+            }
+
+            fn fast_cos(self) -> Self {
+                Self::from_f32(self.to_f32().fast_cos())
+            }
+
+            fn fast_rsqrt(self) -> Self {
+                Self::from_f32(self.to_f32().fast_rsqrt())
+            }
+
+            fn fast_log2(self) -> Self {
+                Self::from_f32(self.to_f32().fast_log2())
+            }
+
+            fn fast_exp2(self) -> Self {
+                self.exp2_approx()
+            }
+
+            fn fast_log10(self) -> Self {
+                Self::from_f32(self.to_f32().fast_log10())
+            }
+
+            fn fast_ln(self) -> Self {
+                Self::from_f32(self.to_f32().fast_ln())
+            }
+
+            fn fast_exp(self) -> Self {
+                Self::from_f32(self.to_f32().fast_exp())
+            }
+
+            fn fast_sin_reduced(self) -> Self {
+                Self::from_f32(self.to_f32().fast_sin_reduced())
+            }
+
+            fn fast_cos_reduced(self) -> Self {
+                Self::from_f32(self.to_f32().fast_cos_reduced())
+            }
+
+            fn fast_sincos(self) -> (Self, Self) {
+                let (sin, cos) = self.to_f32().fast_sincos();
+                (Self::from_f32(sin), Self::from_f32(cos))
+            }
+
+            fn fast_tanh(self) -> Self {
+                self.tanh_approx()
+            }
+
+            fn fast_sigmoid(self) -> Self {
+                Self::from_f32(self.to_f32().fast_sigmoid())
+            }
+
+            fn fast_gelu(self) -> Self {
+                Self::from_f32(self.to_f32().fast_gelu())
+            }
+
+            fn fast_softplus(self) -> Self {
+                Self::from_f32(self.to_f32().fast_softplus())
+            }
+
+            fn fast_pow(self, y: Self) -> Self {
+                Self::from_f32(self.to_f32().fast_pow(y.to_f32()))
+            }
+
+            fn fast_atan2(self, x: Self) -> Self {
+                Self::from_f32(self.to_f32().fast_atan2(x.to_f32()))
+            }
+
+            fn fast_abs(self) -> Self {
+                FloatCore::abs(self)
+            }
+
+            fn fast_copysign(self, other: Self) -> Self {
+                Float::copysign(self, other)
+            }
+
+            fn fast_trunc(self) -> Self {
+                FloatCore::trunc(self)
+            }
+
+            fn fast_ceil(self) -> Self {
+                FloatCore::ceil(self)
+            }
+
+            fn fast_floor(self) -> Self {
+                FloatCore::floor(self)
+            }
+        }
+    };
+}
+
+half_float!(Half, 0x7c00, 0x03ff);
+half_float!(BHalf, 0x7f80, 0x007f);
@@ -0,0 +1,693 @@
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#integer-arithmetic-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#extended-precision-integer-arithmetic-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#data-movement-and-conversion-instructions-prmt
+
+popc, clz, brev, ffs, mul.hi and mul.wide are what LLVM selects for the core methods, the rest is inline PTX.
+bmsk and szext need sm_70, dp4a and dp2a sm_61. On the host every instruction is the pseudocode of the
+PTX ISA, the reference for what the device computes.
+*/
+
+#[cfg(target_arch = "nvptx64")]
+use self::device as imp;
+#[cfg(not(target_arch = "nvptx64"))]
+use self::soft as imp;
+
+#[cfg(target_arch = "nvptx64")]
+mod device {
+    use core::arch::asm;
+
+    macro_rules! instruction {
+        ($($name:ident($($arg:ident: $T:ty = $reg:ident),+) -> $R:ty = $dreg:ident, $op:literal;)+) => {
+            $(#[inline]
+            pub fn $name($($arg: $T),+) -> $R {
+                let d: $R;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}", $(instruction!(@comma $arg)),+, ";"),
+                        out($dreg) d, $(in($reg) $arg),+,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            })+
+        };
+        (@comma $arg:ident) => {
+            ", {}"
+        };
+    }
+
+    instruction! {
+        bfe_u32(a: u32 = reg32, pos: u32 = reg32, len: u32 = reg32) -> u32 = reg32, "bfe.u32";
+        bfe_s32(a: i32 = reg32, pos: u32 = reg32, len: u32 = reg32) -> i32 = reg32, "bfe.s32";
+        bfe_u64(a: u64 = reg64, pos: u32 = reg32, len: u32 = reg32) -> u64 = reg64, "bfe.u64";
+        bfe_s64(a: i64 = reg64, pos: u32 = reg32, len: u32 = reg32) -> i64 = reg64, "bfe.s64";
+        bfi_b32(a: u32 = reg32, b: u32 = reg32, pos: u32 = reg32, len: u32 = reg32) -> u32 = reg32, "bfi.b32";
+        bfi_b64(a: u64 = reg64, b: u64 = reg64, pos: u32 = reg32, len: u32 = reg32) -> u64 = reg64, "bfi.b64";
+        mul_hi_u32(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "mul.hi.u32";
+        mul_hi_s32(a: i32 = reg32, b: i32 = reg32) -> i32 = reg32, "mul.hi.s32";
+        mul_hi_u64(a: u64 = reg64, b: u64 = reg64) -> u64 = reg64, "mul.hi.u64";
+        mul_hi_s64(a: i64 = reg64, b: i64 = reg64) -> i64 = reg64, "mul.hi.s64";
+        prmt(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32";
+        prmt_f4e(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.f4e";
+        prmt_b4e(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.b4e";
+        prmt_rc8(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.rc8";
+        prmt_ecl(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.ecl";
+        prmt_ecr(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.ecr";
+        prmt_rc16(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "prmt.b32.rc16";
+        shf_l_wrap(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "shf.l.wrap.b32";
+        shf_l_clamp(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "shf.l.clamp.b32";
+        shf_r_wrap(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "shf.r.wrap.b32";
+        shf_r_clamp(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "shf.r.clamp.b32";
+        mul24_lo_u32(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "mul24.lo.u32";
+        mul24_lo_s32(a: i32 = reg32, b: i32 = reg32) -> i32 = reg32, "mul24.lo.s32";
+        mul24_hi_u32(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "mul24.hi.u32";
+        mul24_hi_s32(a: i32 = reg32, b: i32 = reg32) -> i32 = reg32, "mul24.hi.s32";
+        mad24_lo_u32(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "mad24.lo.u32";
+        mad24_lo_s32(a: i32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "mad24.lo.s32";
+        sad_u32(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "sad.u32";
+        sad_s32(a: i32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "sad.s32";
+        dp4a_u32_u32(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "dp4a.u32.u32";
+        dp4a_u32_s32(a: u32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp4a.u32.s32";
+        dp4a_s32_u32(a: i32 = reg32, b: u32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp4a.s32.u32";
+        dp4a_s32_s32(a: i32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp4a.s32.s32";
+        dp2a_lo_u32_u32(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "dp2a.lo.u32.u32";
+        dp2a_lo_u32_s32(a: u32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.lo.u32.s32";
+        dp2a_lo_s32_u32(a: i32 = reg32, b: u32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.lo.s32.u32";
+        dp2a_lo_s32_s32(a: i32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.lo.s32.s32";
+        dp2a_hi_u32_u32(a: u32 = reg32, b: u32 = reg32, c: u32 = reg32) -> u32 = reg32, "dp2a.hi.u32.u32";
+        dp2a_hi_u32_s32(a: u32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.hi.u32.s32";
+        dp2a_hi_s32_u32(a: i32 = reg32, b: u32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.hi.s32.u32";
+        dp2a_hi_s32_s32(a: i32 = reg32, b: i32 = reg32, c: i32 = reg32) -> i32 = reg32, "dp2a.hi.s32.s32";
+        bmsk_clamp(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "bmsk.clamp.b32";
+        bmsk_wrap(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "bmsk.wrap.b32";
+        szext_clamp_u32(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "szext.clamp.u32";
+        szext_clamp_s32(a: i32 = reg32, b: u32 = reg32) -> i32 = reg32, "szext.clamp.s32";
+        szext_wrap_u32(a: u32 = reg32, b: u32 = reg32) -> u32 = reg32, "szext.wrap.u32";
+        szext_wrap_s32(a: i32 = reg32, b: u32 = reg32) -> i32 = reg32, "szext.wrap.s32";
+        add_sat_s32(a: i32 = reg32, b: i32 = reg32) -> i32 = reg32, "add.sat.s32";
+    }
+
+    macro_rules! carry {
+        ($($name:ident, $T:ty, $reg:ident, $add:literal, $mov:literal, $op_cc:literal, $op:literal;)+) => {
+            // CC.CF is set from the carry in and read back in the same asm block, it doesn't
+            // survive between blocks
+            $(#[inline]
+            pub fn $name(a: $T, b: $T, carry: bool) -> ($T, bool) {
+                let d: $T;
+                let mut c = carry as $T;
+                unsafe {
+                    asm!(
+                        concat!($add, " {c}, {c}, -1;"),
+                        concat!($op_cc, " {d}, {a}, {b};"),
+                        concat!($mov, " {c}, 0;"),
+                        concat!($op, " {c}, {c}, 0;"),
+                        d = out($reg) d, a = in($reg) a, b = in($reg) b, c = inout($reg) c,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                (d, c != 0)
+            })+
+        };
+    }
+
+    carry! {
+        addc_u32, u32, reg32, "add.cc.u32", "mov.u32", "addc.cc.u32", "addc.u32";
+        addc_u64, u64, reg64, "add.cc.u64", "mov.u64", "addc.cc.u64", "addc.u64";
+        subc_u32, u32, reg32, "add.cc.u32", "mov.u32", "subc.cc.u32", "subc.u32";
+        subc_u64, u64, reg64, "add.cc.u64", "mov.u64", "subc.cc.u64", "subc.u64";
+    }
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    macro_rules! bit_field {
+        ($bfe_u:ident, $bfe_s:ident, $bfi:ident, $U:ty, $S:ty) => {
+            /// d[i] = i < len && pos + i <= msb ? a[pos + i] : sbit
+            fn bfe(a: $U, pos: u32, len: u32, signed: bool) -> $U {
+                let msb = <$U>::BITS - 1;
+                let (pos, len) = (pos & 0xff, len & 0xff);
+                let sbit = signed && len != 0 && a >> (pos + len - 1).min(msb) & 1 == 1;
+                (0..=msb).fold(0, |d, i| {
+                    let bit = if i < len && pos + i <= msb {
+                        a >> (pos + i) & 1 == 1
+                    } else {
+                        sbit
+                    };
+                    d | (bit as $U) << i
+                })
+            }
+
+            pub fn $bfe_u(a: $U, pos: u32, len: u32) -> $U {
+                bfe(a, pos, len, false)
+            }
+
+            pub fn $bfe_s(a: $S, pos: u32, len: u32) -> $S {
+                bfe(a as $U, pos, len, true) as $S
+            }
+
+            /// f[pos + i] = a[i] for i < len && pos + i <= msb
+            pub fn $bfi(a: $U, b: $U, pos: u32, len: u32) -> $U {
+                let (pos, len) = (pos & 0xff, len & 0xff);
+                (0..len)
+                    .take_while(|i| pos + i < <$U>::BITS)
+                    .fold(b, |f, i| f & !(1 << (pos + i)) | (a >> i & 1) << (pos + i))
+            }
+        };
+    }
+
+    mod b32 {
+        bit_field!(bfe_u32, bfe_s32, bfi_b32, u32, i32);
+    }
+
+    mod b64 {
+        bit_field!(bfe_u64, bfe_s64, bfi_b64, u64, i64);
+    }
+
+    pub use self::b32::*;
+    pub use self::b64::*;
+
+    pub fn mul_hi_u32(a: u32, b: u32) -> u32 {
+        ((a as u64 * b as u64) >> 32) as u32
+    }
+
+    pub fn mul_hi_s32(a: i32, b: i32) -> i32 {
+        ((a as i64 * b as i64) >> 32) as i32
+    }
+
+    pub fn mul_hi_u64(a: u64, b: u64) -> u64 {
+        ((a as u128 * b as u128) >> 64) as u64
+    }
+
+    pub fn mul_hi_s64(a: i64, b: i64) -> i64 {
+        ((a as i128 * b as i128) >> 64) as i64
+    }
+
+    /// the bytes of b:a, a in the low 4
+    fn bytes(a: u32, b: u32) -> [u8; 8] {
+        ((b as u64) << 32 | a as u64).to_le_bytes()
+    }
+
+    /// every nibble of c selects a byte, its msb replicates the sign of the byte
+    pub fn prmt(a: u32, b: u32, c: u32) -> u32 {
+        let bytes = bytes(a, b);
+        (0..4).fold(0, |d, i| {
+            let select = c >> (4 * i) & 0xf;
+            let byte = bytes[select as usize & 7];
+            let byte = if select & 8 != 0 {
+                ((byte as i8) >> 7) as u8
+            } else {
+                byte
+            };
+            d | (byte as u32) << (8 * i)
+        })
+    }
+
+    macro_rules! prmt_mode {
+        ($($name:ident: $table:expr;)+) => {
+            $(/// c[1:0] selects which bytes go to d.b0, d.b1, d.b2, d.b3
+            pub fn $name(a: u32, b: u32, c: u32) -> u32 {
+                const TABLE: [[usize; 4]; 4] = $table;
+                let bytes = bytes(a, b);
+                u32::from_le_bytes(TABLE[c as usize & 3].map(|i| bytes[i]))
+            })+
+        };
+    }
+
+    prmt_mode! {
+        prmt_f4e: [[0, 1, 2, 3], [1, 2, 3, 4], [2, 3, 4, 5], [3, 4, 5, 6]];
+        prmt_b4e: [[0, 7, 6, 5], [1, 0, 7, 6], [2, 1, 0, 7], [3, 2, 1, 0]];
+        prmt_rc8: [[0, 0, 0, 0], [1, 1, 1, 1], [2, 2, 2, 2], [3, 3, 3, 3]];
+        prmt_ecl: [[0, 1, 2, 3], [1, 1, 2, 3], [2, 2, 2, 3], [3, 3, 3, 3]];
+        prmt_ecr: [[0, 0, 0, 0], [0, 1, 1, 1], [0, 1, 2, 2], [0, 1, 2, 3]];
+        prmt_rc16: [[0, 1, 0, 1], [2, 3, 2, 3], [0, 1, 0, 1], [2, 3, 2, 3]];
+    }
+
+    /// the high 32 bits of b:a << n
+    fn shf_l(a: u32, b: u32, n: u32) -> u32 {
+        (((b as u64) << 32 | a as u64) << n >> 32) as u32
+    }
+
+    /// the low 32 bits of b:a >> n
+    fn shf_r(a: u32, b: u32, n: u32) -> u32 {
+        (((b as u64) << 32 | a as u64) >> n) as u32
+    }
+
+    pub fn shf_l_wrap(a: u32, b: u32, c: u32) -> u32 {
+        shf_l(a, b, c & 0x1f)
+    }
+
+    pub fn shf_l_clamp(a: u32, b: u32, c: u32) -> u32 {
+        shf_l(a, b, c.min(32))
+    }
+
+    pub fn shf_r_wrap(a: u32, b: u32, c: u32) -> u32 {
+        shf_r(a, b, c & 0x1f)
+    }
+
+    pub fn shf_r_clamp(a: u32, b: u32, c: u32) -> u32 {
+        shf_r(a, b, c.min(32))
+    }
+
+    /// the 48 bit product of the low 24 bits
+    fn mul24_u32(a: u32, b: u32) -> u64 {
+        (a & 0xff_ffff) as u64 * (b & 0xff_ffff) as u64
+    }
+
+    fn mul24_s32(a: i32, b: i32) -> i64 {
+        ((a << 8) >> 8) as i64 * ((b << 8) >> 8) as i64
+    }
+
+    pub fn mul24_lo_u32(a: u32, b: u32) -> u32 {
+        mul24_u32(a, b) as u32
+    }
+
+    pub fn mul24_lo_s32(a: i32, b: i32) -> i32 {
+        mul24_s32(a, b) as i32
+    }
+
+    /// bits 16 to 47 of the product
+    pub fn mul24_hi_u32(a: u32, b: u32) -> u32 {
+        (mul24_u32(a, b) >> 16) as u32
+    }
+
+    pub fn mul24_hi_s32(a: i32, b: i32) -> i32 {
+        (mul24_s32(a, b) >> 16) as i32
+    }
+
+    pub fn mad24_lo_u32(a: u32, b: u32, c: u32) -> u32 {
+        mul24_lo_u32(a, b).wrapping_add(c)
+    }
+
+    pub fn mad24_lo_s32(a: i32, b: i32, c: i32) -> i32 {
+        mul24_lo_s32(a, b).wrapping_add(c)
+    }
+
+    pub fn sad_u32(a: u32, b: u32, c: u32) -> u32 {
+        c.wrapping_add(a.abs_diff(b))
+    }
+
+    pub fn sad_s32(a: i32, b: i32, c: i32) -> i32 {
+        c.wrapping_add(a.abs_diff(b) as i32)
+    }
+
+    macro_rules! dot {
+        ($($dp4a:ident, $dp2a_lo:ident, $dp2a_hi:ident, $A:ty, $A8:ty, $A16:ty, $B:ty, $B8:ty, $D:ty;)+) => {
+            $(/// c + the sum of the products of the bytes
+            pub fn $dp4a(a: $A, b: $B, c: $D) -> $D {
+                let (a, b) = (a.to_le_bytes(), b.to_le_bytes());
+                (0..4).fold(c, |d, i| {
+                    d.wrapping_add((a[i] as $A8 as $D).wrapping_mul(b[i] as $B8 as $D))
+                })
+            }
+
+            /// c + the sum of the products of the halves of a with bytes k and k + 1 of b
+            fn $dp2a_lo(a: $A, b: $B, c: $D, k: usize) -> $D {
+                let b = b.to_le_bytes();
+                (0..2).fold(c, |d, i| {
+                    let a = (a >> (16 * i)) as $A16 as $D;
+                    d.wrapping_add(a.wrapping_mul(b[k + i] as $B8 as $D))
+                })
+            }
+
+            pub fn $dp2a_hi(a: $A, b: $B, c: $D) -> $D {
+                $dp2a_lo(a, b, c, 2)
+            })+
+        };
+    }
+
+    mod dot {
+        dot! {
+            dp4a_u32_u32, dp2a_u32_u32, dp2a_hi_u32_u32, u32, u8, u16, u32, u8, u32;
+            dp4a_u32_s32, dp2a_u32_s32, dp2a_hi_u32_s32, u32, u8, u16, i32, i8, i32;
+            dp4a_s32_u32, dp2a_s32_u32, dp2a_hi_s32_u32, i32, i8, i16, u32, u8, i32;
+            dp4a_s32_s32, dp2a_s32_s32, dp2a_hi_s32_s32, i32, i8, i16, i32, i8, i32;
+        }
+
+        pub fn dp2a_lo_u32_u32(a: u32, b: u32, c: u32) -> u32 {
+            dp2a_u32_u32(a, b, c, 0)
+        }
+
+        pub fn dp2a_lo_u32_s32(a: u32, b: i32, c: i32) -> i32 {
+            dp2a_u32_s32(a, b, c, 0)
+        }
+
+        pub fn dp2a_lo_s32_u32(a: i32, b: u32, c: i32) -> i32 {
+            dp2a_s32_u32(a, b, c, 0)
+        }
+
+        pub fn dp2a_lo_s32_s32(a: i32, b: i32, c: i32) -> i32 {
+            dp2a_s32_s32(a, b, c, 0)
+        }
+    }
+
+    pub use self::dot::*;
+
+    /// the low n bits set
+    fn ones(n: u32) -> u32 {
+        if n >= 32 {
+            u32::MAX
+        } else {
+            (1 << n) - 1
+        }
+    }
+
+    /// bits a to a + b - 1
+    fn bmsk(a: u32, b: u32) -> u32 {
+        ones((a + b).min(32)) & !ones(a)
+    }
+
+    pub fn bmsk_clamp(a: u32, b: u32) -> u32 {
+        bmsk(a.min(32), b.min(32))
+    }
+
+    pub fn bmsk_wrap(a: u32, b: u32) -> u32 {
+        bmsk(a & 0x1f, b & 0x1f)
+    }
+
+    fn szext_u32(a: u32, n: u32) -> u32 {
+        a & ones(n)
+    }
+
+    fn szext_s32(a: i32, n: u32) -> i32 {
+        match n {
+            0 => 0,
+            32 => a,
+            n => (a << (32 - n)) >> (32 - n),
+        }
+    }
+
+    pub fn szext_clamp_u32(a: u32, b: u32) -> u32 {
+        szext_u32(a, b.min(32))
+    }
+
+    pub fn szext_clamp_s32(a: i32, b: u32) -> i32 {
+        szext_s32(a, b.min(32))
+    }
+
+    pub fn szext_wrap_u32(a: u32, b: u32) -> u32 {
+        szext_u32(a, b & 0x1f)
+    }
+
+    pub fn szext_wrap_s32(a: i32, b: u32) -> i32 {
+        szext_s32(a, b & 0x1f)
+    }
+
+    pub fn add_sat_s32(a: i32, b: i32) -> i32 {
+        a.saturating_add(b)
+    }
+
+    macro_rules! carry {
+        ($($addc:ident, $subc:ident, $T:ty;)+) => {
+            $(pub fn $addc(a: $T, b: $T, carry: bool) -> ($T, bool) {
+                let (d, c0) = a.overflowing_add(b);
+                let (d, c1) = d.overflowing_add(carry as $T);
+                (d, c0 | c1)
+            }
+
+            pub fn $subc(a: $T, b: $T, borrow: bool) -> ($T, bool) {
+                let (d, b0) = a.overflowing_sub(b);
+                let (d, b1) = d.overflowing_sub(borrow as $T);
+                (d, b0 | b1)
+            })+
+        };
+    }
+
+    carry! {
+        addc_u32, subc_u32, u32;
+        addc_u64, subc_u64, u64;
+    }
+}
+
+/// Bit manipulation and the high half of products of the 32 & 64 bit integers, the signed types
+/// sign extend
+pub trait IntBits: Copy {
+    /// the number of set bits, `popc`
+    fn popc(self) -> u32;
+    /// the number of leading zeros, `clz`
+    fn clz(self) -> u32;
+    /// 1 + the index of the least significant set bit, 0 if there is none, like CUDA's `__ffs`
+    fn ffs(self) -> u32;
+    /// the bits reversed, `brev`
+    fn brev(self) -> Self;
+    /// bits `pos` to `pos + len - 1` in the low bits, extended from the last bit of the field,
+    /// `pos` & `len` mod 256, `bfe`
+    fn bfe(self, pos: u32, len: u32) -> Self;
+    /// `base` with bits `pos` to `pos + len - 1` replaced by the low bits of `self`,
+    /// `pos` & `len` mod 256, `bfi`
+    fn bfi(self, base: Self, pos: u32, len: u32) -> Self;
+    /// the high half of the full product, `mul.hi`
+    fn mul_hi(self, rhs: Self) -> Self;
+}
+
+macro_rules! int_bits {
+    ($($T:ty, $U:ty, $bfe:ident, $bfi:ident, $mul_hi:ident);+) => {
+        $(impl IntBits for $T {
+            fn popc(self) -> u32 {
+                self.count_ones()
+            }
+
+            fn clz(self) -> u32 {
+                self.leading_zeros()
+            }
+
+            fn ffs(self) -> u32 {
+                if self == 0 {
+                    0
+                } else {
+                    self.trailing_zeros() + 1
+                }
+            }
+
+            fn brev(self) -> Self {
+                self.reverse_bits()
+            }
+
+            fn bfe(self, pos: u32, len: u32) -> Self {
+                imp::$bfe(self, pos, len)
+            }
+
+            fn bfi(self, base: Self, pos: u32, len: u32) -> Self {
+                imp::$bfi(self as $U, base as $U, pos, len) as $T
+            }
+
+            fn mul_hi(self, rhs: Self) -> Self {
+                imp::$mul_hi(self, rhs)
+            }
+        })+
+    };
+}
+
+int_bits! {
+    u32, u32, bfe_u32, bfi_b32, mul_hi_u32;
+    i32, u32, bfe_s32, bfi_b32, mul_hi_s32;
+    u64, u64, bfe_u64, bfi_b64, mul_hi_u64;
+    i64, u64, bfe_s64, bfi_b64, mul_hi_s64
+}
+
+/// The full product, `mul.wide`
+pub trait MulWide {
+    type Wide;
+    fn mul_wide(self, rhs: Self) -> Self::Wide;
+}
+
+macro_rules! mul_wide {
+    ($($T:ty => $W:ty),+) => {
+        $(impl MulWide for $T {
+            type Wide = $W;
+
+            fn mul_wide(self, rhs: Self) -> $W {
+                self as $W * rhs as $W
+            }
+        })+
+    };
+}
+
+mul_wide! { u16 => u32, i16 => i32, u32 => u64, i32 => i64 }
+
+/// The 32 bit integer instructions that depend on the signedness
+pub trait Int32: Copy {
+    /// the low 32 bits of the product of the low 24 bits, `mul24.lo`
+    fn mul24(self, rhs: Self) -> Self;
+    /// bits 16 to 47 of the product of the low 24 bits, `mul24.hi`
+    fn mul24_hi(self, rhs: Self) -> Self;
+    /// `mul24` + c, `mad24.lo`
+    fn mad24(self, b: Self, c: Self) -> Self;
+    /// c + |self - b|, `sad`
+    fn sad(self, b: Self, c: Self) -> Self;
+    /// the low `min(n, 32)` bits, extended from the last, `szext.clamp`
+    fn szext(self, n: u32) -> Self;
+    /// the low `n mod 32` bits, extended from the last, `szext.wrap`
+    fn szext_wrap(self, n: u32) -> Self;
+}
+
+macro_rules! int32 {
+    ($($T:ty, $mul24:ident, $mul24_hi:ident, $mad24:ident, $sad:ident, $szext:ident, $szext_wrap:ident);+) => {
+        $(impl Int32 for $T {
+            fn mul24(self, rhs: Self) -> Self {
+                imp::$mul24(self, rhs)
+            }
+
+            fn mul24_hi(self, rhs: Self) -> Self {
+                imp::$mul24_hi(self, rhs)
+            }
+
+            fn mad24(self, b: Self, c: Self) -> Self {
+                imp::$mad24(self, b, c)
+            }
+
+            fn sad(self, b: Self, c: Self) -> Self {
+                imp::$sad(self, b, c)
+            }
+
+            fn szext(self, n: u32) -> Self {
+                imp::$szext(self, n)
+            }
+
+            fn szext_wrap(self, n: u32) -> Self {
+                imp::$szext_wrap(self, n)
+            }
+        })+
+    };
+}
+
+int32! {
+    u32, mul24_lo_u32, mul24_hi_u32, mad24_lo_u32, sad_u32, szext_clamp_u32, szext_wrap_u32;
+    i32, mul24_lo_s32, mul24_hi_s32, mad24_lo_s32, sad_s32, szext_clamp_s32, szext_wrap_s32
+}
+
+/// Dot products of packed bytes and halves, signed unless both are u32
+pub trait PackedDot<B> {
+    type Output;
+    /// c + the sum of the products of the 4 bytes of self & b, `dp4a`
+    fn dp4a(self, b: B, c: Self::Output) -> Self::Output;
+    /// c + the sum of the products of the 2 halves of self & bytes 0 & 1 of b, `dp2a.lo`
+    fn dp2a_lo(self, b: B, c: Self::Output) -> Self::Output;
+    /// c + the sum of the products of the 2 halves of self & bytes 2 & 3 of b, `dp2a.hi`
+    fn dp2a_hi(self, b: B, c: Self::Output) -> Self::Output;
+}
+
+macro_rules! packed_dot {
+    ($($A:ty, $B:ty => $D:ty, $dp4a:ident, $dp2a_lo:ident, $dp2a_hi:ident);+) => {
+        $(impl PackedDot<$B> for $A {
+            type Output = $D;
+
+            fn dp4a(self, b: $B, c: $D) -> $D {
+                imp::$dp4a(self, b, c)
+            }
+
+            fn dp2a_lo(self, b: $B, c: $D) -> $D {
+                imp::$dp2a_lo(self, b, c)
+            }
+
+            fn dp2a_hi(self, b: $B, c: $D) -> $D {
+                imp::$dp2a_hi(self, b, c)
+            }
+        })+
+    };
+}
+
+packed_dot! {
+    u32, u32 => u32, dp4a_u32_u32, dp2a_lo_u32_u32, dp2a_hi_u32_u32;
+    u32, i32 => i32, dp4a_u32_s32, dp2a_lo_u32_s32, dp2a_hi_u32_s32;
+    i32, u32 => i32, dp4a_s32_u32, dp2a_lo_s32_u32, dp2a_hi_s32_u32;
+    i32, i32 => i32, dp4a_s32_s32, dp2a_lo_s32_s32, dp2a_hi_s32_s32
+}
+
+/// Additions and subtractions chained through the carry flag, `add.cc`/`addc` & `sub.cc`/`subc`
+pub trait CarryArith: Sized {
+    /// self + rhs + carry and the carry out
+    fn addc(self, rhs: Self, carry: bool) -> (Self, bool);
+    /// self - rhs - borrow and the borrow out
+    fn subc(self, rhs: Self, borrow: bool) -> (Self, bool);
+}
+
+macro_rules! carry_arith {
+    ($($T:ty, $addc:ident, $subc:ident);+) => {
+        $(impl CarryArith for $T {
+            fn addc(self, rhs: Self, carry: bool) -> (Self, bool) {
+                imp::$addc(self, rhs, carry)
+            }
+
+            fn subc(self, rhs: Self, borrow: bool) -> (Self, bool) {
+                imp::$subc(self, rhs, borrow)
+            }
+        })+
+    };
+}
+
+carry_arith! {
+    u32, addc_u32, subc_u32;
+    u64, addc_u64, subc_u64
+}
+
+/// bytes of b:a, a in the low 4, selected by the nibbles of `selector` for d.b0 to d.b3, a set msb
+/// replicates the sign of the byte, `prmt.b32`
+pub fn prmt(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt(a, b, selector)
+}
+
+/// forward 4 extract, bytes `selector` to `selector + 3` of b:a, `prmt.b32.f4e`
+pub fn prmt_f4e(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_f4e(a, b, selector)
+}
+
+/// backward 4 extract, bytes `selector` down to `selector - 3` mod 8 of b:a, `prmt.b32.b4e`
+pub fn prmt_b4e(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_b4e(a, b, selector)
+}
+
+/// byte `selector` of a in every byte, `prmt.b32.rc8`
+pub fn prmt_rc8(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_rc8(a, b, selector)
+}
+
+/// a with the bytes below `selector` replaced by byte `selector`, `prmt.b32.ecl`
+pub fn prmt_ecl(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_ecl(a, b, selector)
+}
+
+/// a with the bytes above `selector` replaced by byte `selector`, `prmt.b32.ecr`
+pub fn prmt_ecr(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_ecr(a, b, selector)
+}
+
+/// the half of a with bit 1 of `selector` in both halves, `prmt.b32.rc16`
+pub fn prmt_rc16(a: u32, b: u32, selector: u32) -> u32 {
+    imp::prmt_rc16(a, b, selector)
+}
+
+/// the high 32 bits of hi:lo << (shift mod 32), `shf.l.wrap`
+pub fn funnel_shift_l(lo: u32, hi: u32, shift: u32) -> u32 {
+    imp::shf_l_wrap(lo, hi, shift)
+}
+
+/// the high 32 bits of hi:lo << min(shift, 32), `shf.l.clamp`
+pub fn funnel_shift_l_clamp(lo: u32, hi: u32, shift: u32) -> u32 {
+    imp::shf_l_clamp(lo, hi, shift)
+}
+
+/// the low 32 bits of hi:lo >> (shift mod 32), `shf.r.wrap`
+pub fn funnel_shift_r(lo: u32, hi: u32, shift: u32) -> u32 {
+    imp::shf_r_wrap(lo, hi, shift)
+}
+
+/// the low 32 bits of hi:lo >> min(shift, 32), `shf.r.clamp`
+pub fn funnel_shift_r_clamp(lo: u32, hi: u32, shift: u32) -> u32 {
+    imp::shf_r_clamp(lo, hi, shift)
+}
+
+/// bits `start` to `start + width - 1` set, both clamped to 32, `bmsk.clamp`
+pub fn bmsk(start: u32, width: u32) -> u32 {
+    imp::bmsk_clamp(start, width)
+}
+
+/// bits `start` to `start + width - 1` set, both mod 32, `bmsk.wrap`
+pub fn bmsk_wrap(start: u32, width: u32) -> u32 {
+    imp::bmsk_wrap(start, width)
+}
+
+/// a + b saturated to the i32 range, `add.sat.s32`
+pub fn add_sat(a: i32, b: i32) -> i32 {
+    imp::add_sat_s32(a, b)
+}
@@ -1,5 +1,16 @@
 #![no_std]
-#![feature(core_intrinsics, link_llvm_intrinsics, ffi_const)]
+#![feature(
+    core_intrinsics,
+    link_llvm_intrinsics,
+    ffi_const,
+    repr_simd,
+    f16,
+    simd_ffi
+)]
+#![cfg_attr(
+    target_arch = "nvptx64",
+    feature(abi_unadjusted, asm_experimental_arch)
+)]
 #![cfg_attr(feature = "panic", feature(panic_info_message))]
 #![cfg_attr(feature = "unstable-allocator-api", feature(allocator_api))]
 #![allow(non_camel_case_types)]
@@ -7,12 +18,10 @@
 
 #[cfg(feature = "global-allocator")]
 extern crate alloc;
-#[macro_use]
-extern crate derive_more;
 
-// TODO: Documentation, active-mask, warp matrix ops, memory barriers, asynchronous copy,
+// TODO: Documentation, active-mask, memory barriers, asynchronous copy,
 // builtin-redux, nanosleep, cfg guards for ptx isa version & sm version,
-// clock sreg?, math rounding modes?, cooperative groups?, unstable-allocator-api?
+// clock sreg?, cooperative groups?, unstable-allocator-api?
 
 /*
 https://docs.nvidia.com/cuda/parallel-thread-execution/index.htm
@@ -20,23 +29,75 @@ https://docs.nvidia.com/cuda/ptx-writers-guide-to-interoperability/index.html
 */
 
 mod allocator;
+mod approx_f64;
 mod barrier;
+mod bitonic;
+mod block_histogram;
+mod block_io;
+mod block_radix_sort;
+mod block_reduce;
+mod block_scan;
+mod complex;
+mod device_scan;
+mod device_select;
+mod divisor;
 mod float;
+mod half;
+mod integer;
+mod linalg;
+mod minifloat;
+mod mma;
+mod packed_half;
 #[cfg(feature = "panic")]
 mod panic;
+mod radix;
+mod rounding;
+mod scan;
 mod shuffle;
+mod special;
 mod sreg;
 mod syscall;
+mod uint;
+mod warp;
+mod warp_atomic;
+mod warp_sort;
+mod wmma;
 pub use crate::allocator::CudaSysAllocator;
 pub use crate::barrier::*;
+pub use crate::bitonic::*;
+pub use crate::block_histogram::*;
+pub use crate::block_io::*;
+pub use crate::block_radix_sort::*;
+pub use crate::block_reduce::*;
+pub use crate::block_scan::*;
+pub use crate::complex::*;
+pub use crate::device_scan::*;
+pub use crate::device_select::*;
+pub use crate::divisor::*;
 pub use crate::float::*;
+pub use crate::half::*;
+pub use crate::integer::*;
+pub use crate::linalg::*;
+pub use crate::minifloat::*;
+pub use crate::mma::*;
+pub use crate::packed_half::*;
+pub use crate::radix::*;
+pub use crate::rounding::*;
+pub use crate::scan::*;
 pub use crate::shuffle::*;
+pub use crate::special::*;
 pub use crate::sreg::*;
 pub use crate::syscall::*;
+pub use crate::uint::*;
+pub use crate::warp::*;
+pub use crate::warp_atomic::*;
+pub use crate::warp_sort::*;
+pub use crate::wmma::*;
 use core::mem::transmute;
 pub use nvptx_vprintf::printf;
 
 pub const ALL_MEMBER_MASK: u32 = 0xffffffff;
+pub const WARP_SIZE: u32 = 32;
 
 extern "C" {
     #[link_name = "llvm.nvvm.atomic.load.add.f32.p0f32"]
@@ -49,6 +110,9 @@ extern "C" {
     #[link_name = "llvm.nvvm.atomic.load.dec.32.p0i32"]
     pub fn atomic_load_dec_32(address: *mut u32, val: u32) -> u32;
 
+    #[link_name = "llvm.nvvm.activemask"]
+    fn __activemask() -> u32;
+
     #[ffi_const]
     #[link_name = "llvm.nvvm.vote.all.sync"]
     fn vote_all_sync(membermask: u32, pred: bool) -> bool;
@@ -75,6 +139,11 @@ extern "C" {
     // fn match_all_i64_sync(membermask: u32, value: u64) -> (u32, bool);
 }
 
+/// mask of the lanes of the warp that are currently active, i.e. executing this call in convergence
+pub fn activemask() -> u32 {
+    unsafe { __activemask() }
+}
+
 /// true if the source predicates is true for all thread in %membermask, false otherwise
 pub fn vote_all(membermask: u32, pred: bool) -> bool {
     unsafe { vote_all_sync(membermask, pred) }
@@ -127,6 +196,18 @@ impl Match for f32 {
     // }
 }
 
+impl Match for Half {
+    fn match_any(self, membermask: u32) -> u32 {
+        unsafe { match_any_i32_sync(membermask, self.to_bits() as u32) }
+    }
+}
+
+impl Match for BHalf {
+    fn match_any(self, membermask: u32) -> u32 {
+        unsafe { match_any_i32_sync(membermask, self.to_bits() as u32) }
+    }
+}
+
 impl Match for u64 {
     fn match_any(self, membermask: u32) -> u32 {
         unsafe { match_any_i64_sync(membermask, self) }
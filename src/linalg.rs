@@ -0,0 +1,462 @@
+use crate::FastNum;
+use core::ops::*;
+
+/*
+Plain `#[repr(C)]` structs of their components, so they pass straight into kernels, a `Vec3<f32>` is a
+CUDA float3 but a `Vec4<f32>` only has the alignment of f32, not float4's 16. Matrices are column major,
+like GLSL's, and every product sums with `fma`.
+*/
+
+macro_rules! vector {
+    ($($(#[$attr:meta])* $V:ident, $N:literal { $($f:ident),+ })+) => {
+        $($(#[$attr])*
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, Default, PartialEq)]
+        pub struct $V<F> {
+            $(pub $f: F),+
+        }
+
+        impl<F> $V<F> {
+            pub const fn new($($f: F),+) -> Self {
+                Self { $($f),+ }
+            }
+
+            pub fn to_array(self) -> [F; $N] {
+                [$(self.$f),+]
+            }
+        }
+
+        impl<F: FastNum> $V<F> {
+            pub fn splat(value: F) -> Self {
+                Self { $($f: value),+ }
+            }
+
+            pub fn scale(self, k: F) -> Self {
+                Self { $($f: self.$f.fast_mul(k)),+ }
+            }
+
+            /// self * k + add in each component with a single rounding
+            pub fn mul_add(self, k: F, add: Self) -> Self {
+                Self { $($f: FastNum::fma(self.$f, k, add.$f)),+ }
+            }
+
+            pub fn mul_elementwise(self, rhs: Self) -> Self {
+                Self { $($f: self.$f.fast_mul(rhs.$f)),+ }
+            }
+
+            pub fn dot(self, rhs: Self) -> F {
+                let mut sum = F::zero();
+                $(sum = FastNum::fma(self.$f, rhs.$f, sum);)+
+                sum
+            }
+
+            pub fn length_sqr(self) -> F {
+                self.dot(self)
+            }
+
+            pub fn length(self) -> F {
+                self.dot(self).fast_sqrt()
+            }
+
+            /// scaled by `fast_rsqrt` of the squared length
+            pub fn normalize(self) -> Self {
+                self.scale(self.dot(self).fast_rsqrt())
+            }
+
+            /// self + (rhs - self) t
+            pub fn lerp(self, rhs: Self, t: F) -> Self {
+                (rhs - self).mul_add(t, self)
+            }
+        }
+
+        impl<F> From<[F; $N]> for $V<F> {
+            fn from([$($f),+]: [F; $N]) -> Self {
+                Self { $($f),+ }
+            }
+        }
+
+        impl<F> From<$V<F>> for [F; $N] {
+            fn from(value: $V<F>) -> Self {
+                value.to_array()
+            }
+        }
+
+        impl<F: FastNum> Add for $V<F> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($f: self.$f.fast_add(rhs.$f)),+ }
+            }
+        }
+
+        impl<F: FastNum> Sub for $V<F> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($f: self.$f.fast_sub(rhs.$f)),+ }
+            }
+        }
+
+        impl<F: FastNum> Neg for $V<F> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($f: -self.$f),+ }
+            }
+        }
+
+        impl<F: FastNum> Mul<F> for $V<F> {
+            type Output = Self;
+
+            fn mul(self, rhs: F) -> Self {
+                self.scale(rhs)
+            }
+        }
+
+        impl<F: FastNum> Div<F> for $V<F> {
+            type Output = Self;
+
+            fn div(self, rhs: F) -> Self {
+                self.scale(rhs.fast_recip())
+            }
+        }
+
+        impl<F: FastNum> AddAssign for $V<F> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs
+            }
+        }
+
+        impl<F: FastNum> SubAssign for $V<F> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs
+            }
+        }
+
+        impl<F: FastNum> MulAssign<F> for $V<F> {
+            fn mul_assign(&mut self, rhs: F) {
+                *self = *self * rhs
+            }
+        })+
+    };
+}
+
+vector! {
+    Vec2, 2 { x, y }
+    Vec3, 3 { x, y, z }
+    Vec4, 4 { x, y, z, w }
+    /// x i + y j + z k + w, rotations are unit quaternions
+    Quat, 4 { x, y, z, w }
+}
+
+impl<F: FastNum> Vec2<F> {
+    /// the z component of the cross product of (x, y, 0) & (rhs.x, rhs.y, 0)
+    pub fn perp_dot(self, rhs: Self) -> F {
+        FastNum::fma(self.x, rhs.y, -self.y.fast_mul(rhs.x))
+    }
+}
+
+impl<F: FastNum> Vec3<F> {
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            FastNum::fma(self.y, rhs.z, -self.z.fast_mul(rhs.y)),
+            FastNum::fma(self.z, rhs.x, -self.x.fast_mul(rhs.z)),
+            FastNum::fma(self.x, rhs.y, -self.y.fast_mul(rhs.x)),
+        )
+    }
+
+    pub fn extend(self, w: F) -> Vec4<F> {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    /// the reflection off a plane with unit normal `normal`, self - 2 (self . normal) normal
+    pub fn reflect(self, normal: Self) -> Self {
+        let d = self.dot(normal);
+        normal.mul_add(-(d + d), self)
+    }
+}
+
+impl<F: FastNum> Vec4<F> {
+    pub fn truncate(self) -> Vec3<F> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl<F: FastNum> Quat<F> {
+    pub fn identity() -> Self {
+        Self::new(F::zero(), F::zero(), F::zero(), F::one())
+    }
+
+    fn vector(self) -> Vec3<F> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    fn from_parts(v: Vec3<F>, w: F) -> Self {
+        Self::new(v.x, v.y, v.z, w)
+    }
+
+    /// the rotation by `angle` about the unit `axis`, with a single `fast_sincos`
+    pub fn from_axis_angle(axis: Vec3<F>, angle: F) -> Self {
+        let half = F::one() / (F::one() + F::one());
+        let (sin, cos) = angle.fast_mul(half).fast_sincos();
+        Self::from_parts(axis.scale(sin), cos)
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(self) -> Self {
+        self.conj().scale(self.length_sqr().fast_recip())
+    }
+
+    /// rotates `v` by the unit quaternion, v + w t + q x t with t = 2 q x v
+    pub fn rotate(self, v: Vec3<F>) -> Vec3<F> {
+        let q = self.vector();
+        let t = q.cross(v);
+        let t = t + t;
+        t.mul_add(self.w, v) + q.cross(t)
+    }
+}
+
+/// the Hamilton product, `self * rhs` rotates by `rhs` then by `self`
+impl<F: FastNum> Mul for Quat<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self.vector(), rhs.vector());
+        let v = b.mul_add(self.w, a.mul_add(rhs.w, a.cross(b)));
+        Self::from_parts(v, FastNum::fma(self.w, rhs.w, -a.dot(b)))
+    }
+}
+
+impl<F: FastNum> MulAssign for Quat<F> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+macro_rules! matrix {
+    ($($M:ident, $V:ident, $N:literal);+) => {
+        $(#[repr(C)]
+        #[derive(Debug, Clone, Copy, Default, PartialEq)]
+        pub struct $M<F> {
+            pub cols: [$V<F>; $N],
+        }
+
+        impl<F> $M<F> {
+            pub const fn from_cols(cols: [$V<F>; $N]) -> Self {
+                Self { cols }
+            }
+        }
+
+        impl<F: FastNum> $M<F> {
+            pub fn identity() -> Self {
+                Self::from_diagonal($V::splat(F::one()))
+            }
+
+            pub fn from_diagonal(diagonal: $V<F>) -> Self {
+                let diagonal = diagonal.to_array();
+                Self::from_cols(core::array::from_fn(|i| {
+                    $V::from(core::array::from_fn(|j| if i == j { diagonal[i] } else { F::zero() }))
+                }))
+            }
+
+            pub fn from_rows(rows: [$V<F>; $N]) -> Self {
+                Self::from_cols(rows).transpose()
+            }
+
+            pub fn row(&self, i: usize) -> $V<F> {
+                $V::from(self.cols.map(|col| col.to_array()[i]))
+            }
+
+            pub fn transpose(&self) -> Self {
+                Self::from_cols(core::array::from_fn(|i| self.row(i)))
+            }
+
+            pub fn scale(&self, k: F) -> Self {
+                Self::from_cols(self.cols.map(|col| col.scale(k)))
+            }
+        }
+
+        /// the columns summed with `fma`
+        impl<F: FastNum> Mul<$V<F>> for $M<F> {
+            type Output = $V<F>;
+
+            fn mul(self, rhs: $V<F>) -> $V<F> {
+                let rhs = rhs.to_array();
+                let first = self.cols[0].scale(rhs[0]);
+                self.cols.iter().zip(rhs).skip(1).fold(first, |sum, (col, k)| col.mul_add(k, sum))
+            }
+        }
+
+        impl<F: FastNum> Mul for $M<F> {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                Self::from_cols(rhs.cols.map(|col| self * col))
+            }
+        }
+
+        impl<F: FastNum> MulAssign for $M<F> {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs
+            }
+        }
+
+        impl<F: FastNum> Add for $M<F> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self::from_cols(core::array::from_fn(|i| self.cols[i] + rhs.cols[i]))
+            }
+        }
+
+        impl<F: FastNum> Sub for $M<F> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self::from_cols(core::array::from_fn(|i| self.cols[i] - rhs.cols[i]))
+            }
+        })+
+    };
+}
+
+matrix! {
+    Mat2, Vec2, 2;
+    Mat3, Vec3, 3;
+    Mat4, Vec4, 4
+}
+
+impl<F: FastNum> Mat2<F> {
+    /// the counterclockwise rotation by `angle`
+    pub fn from_angle(angle: F) -> Self {
+        let (sin, cos) = angle.fast_sincos();
+        Self::from_cols([Vec2::new(cos, sin), Vec2::new(-sin, cos)])
+    }
+
+    pub fn determinant(&self) -> F {
+        self.cols[0].perp_dot(self.cols[1])
+    }
+
+    /// the adjugate over the determinant, not finite if singular
+    pub fn inverse(&self) -> Self {
+        let [a, b] = self.cols;
+        let adjugate = Self::from_cols([Vec2::new(b.y, -a.y), Vec2::new(-b.x, a.x)]);
+        adjugate.scale(self.determinant().fast_recip())
+    }
+}
+
+impl<F: FastNum> Mat3<F> {
+    /// the rotation of a unit quaternion
+    pub fn from_quat(q: Quat<F>) -> Self {
+        let two = F::one() + F::one();
+        let (x2, y2, z2) = (q.x.fast_mul(two), q.y.fast_mul(two), q.z.fast_mul(two));
+        let (xx, yy, zz) = (q.x.fast_mul(x2), q.y.fast_mul(y2), q.z.fast_mul(z2));
+        let (xy, xz, yz) = (q.x.fast_mul(y2), q.x.fast_mul(z2), q.y.fast_mul(z2));
+        let (wx, wy, wz) = (q.w.fast_mul(x2), q.w.fast_mul(y2), q.w.fast_mul(z2));
+        let one = F::one();
+        Self::from_cols([
+            Vec3::new(one - (yy + zz), xy + wz, xz - wy),
+            Vec3::new(xy - wz, one - (xx + zz), yz + wx),
+            Vec3::new(xz + wy, yz - wx, one - (xx + yy)),
+        ])
+    }
+
+    /// the triple product of the columns
+    pub fn determinant(&self) -> F {
+        let [a, b, c] = self.cols;
+        a.dot(b.cross(c))
+    }
+
+    /// the rows of the inverse are the cross products of the columns over the determinant,
+    /// not finite if singular
+    pub fn inverse(&self) -> Self {
+        let [a, b, c] = self.cols;
+        let (bc, ca, ab) = (b.cross(c), c.cross(a), a.cross(b));
+        Self::from_rows([bc, ca, ab]).scale(a.dot(bc).fast_recip())
+    }
+}
+
+impl<F: FastNum> Mat4<F> {
+    pub fn from_mat3(m: Mat3<F>) -> Self {
+        let [a, b, c] = m.cols;
+        let (zero, one) = (F::zero(), F::one());
+        Self::from_cols([
+            a.extend(zero),
+            b.extend(zero),
+            c.extend(zero),
+            Vec4::new(zero, zero, zero, one),
+        ])
+    }
+
+    /// the rotation of a unit quaternion
+    pub fn from_quat(q: Quat<F>) -> Self {
+        Self::from_mat3(Mat3::from_quat(q))
+    }
+
+    pub fn from_translation(t: Vec3<F>) -> Self {
+        let mut m = Self::identity();
+        m.cols[3] = t.extend(F::one());
+        m
+    }
+
+    pub fn from_scale(s: Vec3<F>) -> Self {
+        Self::from_diagonal(s.extend(F::one()))
+    }
+
+    /// scales by `s`, rotates by `q`, then translates by `t`
+    pub fn from_scale_rotation_translation(s: Vec3<F>, q: Quat<F>, t: Vec3<F>) -> Self {
+        let [a, b, c] = Mat3::from_quat(q).cols;
+        Self::from_cols([
+            a.scale(s.x).extend(F::zero()),
+            b.scale(s.y).extend(F::zero()),
+            c.scale(s.z).extend(F::zero()),
+            t.extend(F::one()),
+        ])
+    }
+
+    /// the affine transform of a point, w = 1 without the projective divide
+    pub fn transform_point(&self, p: Vec3<F>) -> Vec3<F> {
+        (*self * p.extend(F::one())).truncate()
+    }
+
+    /// the linear part of the transform, w = 0, no translation
+    pub fn transform_vector(&self, v: Vec3<F>) -> Vec3<F> {
+        (*self * v.extend(F::zero())).truncate()
+    }
+
+    /// Lengyel's, from the cross products of the upper 3 rows of the column pairs
+    pub fn determinant(&self) -> F {
+        let (s, t, u, v) = self.cross_terms();
+        s.dot(v) + t.dot(u)
+    }
+
+    fn cross_terms(&self) -> (Vec3<F>, Vec3<F>, Vec3<F>, Vec3<F>) {
+        let [a, b, c, d] = self.cols.map(Vec4::truncate);
+        let [x, y, z, w] = self.row(3).to_array();
+        let u = a.scale(y) - b.scale(x);
+        let v = c.scale(w) - d.scale(z);
+        (a.cross(b), c.cross(d), u, v)
+    }
+
+    /// Lengyel's, not finite if singular
+    pub fn inverse(&self) -> Self {
+        let [a, b, c, d] = self.cols.map(Vec4::truncate);
+        let [x, y, z, w] = self.row(3).to_array();
+        let (s, t, u, v) = self.cross_terms();
+        let recip = (s.dot(v) + t.dot(u)).fast_recip();
+        let (s, t, u, v) = (
+            s.scale(recip),
+            t.scale(recip),
+            u.scale(recip),
+            v.scale(recip),
+        );
+        Self::from_rows([
+            t.mul_add(y, b.cross(v)).extend(-b.dot(t)),
+            t.mul_add(-x, v.cross(a)).extend(a.dot(t)),
+            s.mul_add(w, d.cross(u)).extend(-d.dot(s)),
+            s.mul_add(-z, u.cross(c)).extend(c.dot(s)),
+        ])
+    }
+}
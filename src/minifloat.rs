@@ -0,0 +1,344 @@
+use crate::{f16x2, Half};
+use core::fmt;
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#alternate-floating-point-data-formats
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#data-movement-and-conversion-instructions-cvt
+
+The fp8 formats only have packed conversions, `cvt.rn.satfinite.{e4m3x2,e5m2x2}.{f32,f16x2}` and back to f16x2,
+all sm_89. They round to nearest even and saturate, anything beyond the largest finite value including
+infinity becomes it while NaN stays NaN. tf32 is rounded with `cvt.rna.tf32.f32`, sm_80, to nearest with
+ties away from zero. The host implementations round the same way from the f32 bits.
+*/
+
+#[cfg(target_arch = "nvptx64")]
+use self::device as imp;
+#[cfg(not(target_arch = "nvptx64"))]
+use self::soft as imp;
+
+#[cfg(target_arch = "nvptx64")]
+mod device {
+    use core::arch::asm;
+
+    macro_rules! from_f32x2 {
+        ($name:ident, $op:literal) => {
+            /// the first operand of the cvt goes to the upper byte
+            #[inline]
+            pub fn $name(lo: f32, hi: f32) -> u16 {
+                let d: u16;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {};"),
+                        out(reg16) d, in(reg32) hi, in(reg32) lo,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    macro_rules! convert {
+        ($name:ident, $op:literal, $out:ident $Out:ty, $in:ident $In:ty) => {
+            #[inline]
+            pub fn $name(a: $In) -> $Out {
+                let d: $Out;
+                unsafe {
+                    asm!(concat!($op, " {}, {};"), out($out) d, in($in) a, options(pure, nomem, nostack));
+                }
+                d
+            }
+        };
+    }
+
+    from_f32x2!(f32x2_to_e4m3x2, "cvt.rn.satfinite.e4m3x2.f32");
+    from_f32x2!(f32x2_to_e5m2x2, "cvt.rn.satfinite.e5m2x2.f32");
+    convert!(f16x2_to_e4m3x2, "cvt.rn.satfinite.e4m3x2.f16x2", reg16 u16, reg32 u32);
+    convert!(f16x2_to_e5m2x2, "cvt.rn.satfinite.e5m2x2.f16x2", reg16 u16, reg32 u32);
+    convert!(e4m3x2_to_f16x2, "cvt.rn.f16x2.e4m3x2", reg32 u32, reg16 u16);
+    convert!(e5m2x2_to_f16x2, "cvt.rn.f16x2.e5m2x2", reg32 u32, reg16 u16);
+    convert!(f32_to_tf32, "cvt.rna.tf32.f32", reg32 u32, reg32 f32);
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    use crate::Half;
+
+    /// an fp8 format with `M` mantissa bits, biased by `BIAS`, whose largest finite magnitude is `MAX`
+    struct Format<const M: u32, const BIAS: i32, const MAX: u8>;
+
+    type E4M3 = Format<3, 7, 0x7e>;
+    type E5M2 = Format<2, 15, 0x7b>;
+
+    impl<const M: u32, const BIAS: i32, const MAX: u8> Format<M, BIAS, MAX> {
+        /// rounds to nearest, ties to even, and saturates to the largest finite value
+        fn from_f32(value: f32) -> u8 {
+            let bits = value.to_bits();
+            if value.is_nan() {
+                return 0x7f;
+            }
+            let sign = ((bits >> 24) & 0x80) as u8;
+            let exponent = ((bits >> 23) & 0xff) as i32 - 127 + BIAS;
+            let mantissa = bits & 0x7f_ffff;
+            // beyond every finite exponent, or infinity
+            if exponent > (MAX >> M) as i32 {
+                return sign | MAX;
+            }
+            // the result's magnitude before rounding and the number of bits to round away
+            let (truncated, mantissa, shift) = if exponent > 0 {
+                (
+                    ((exponent as u32) << M) | (mantissa >> (23 - M)),
+                    mantissa,
+                    23 - M,
+                )
+            } else if exponent >= -(M as i32) {
+                let mantissa = mantissa | 0x80_0000;
+                let shift = (24 - M as i32 - exponent) as u32;
+                (mantissa >> shift, mantissa, shift)
+            } else {
+                return sign;
+            };
+            let rest = mantissa & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            let round_up = rest > half || (rest == half && truncated & 1 == 1);
+            // a carry out of the mantissa bumps the exponent, past the largest finite value it saturates
+            let magnitude = truncated + round_up as u32;
+            sign | magnitude.min(MAX as u32) as u8
+        }
+
+        /// exact widening
+        fn to_f32(bits: u8) -> f32 {
+            let sign = ((bits & 0x80) as u32) << 24;
+            let exponent = ((bits & 0x7f) >> M) as i32;
+            let mantissa = (bits & ((1 << M) - 1)) as u32;
+            if bits & 0x7f > MAX {
+                // e5m2 has infinities, e4m3 only the NaN above its largest finite value
+                let special = if mantissa != 0 {
+                    0x7fff_ffff
+                } else {
+                    0x7f80_0000
+                };
+                return f32::from_bits(sign | special);
+            }
+            let magnitude = match exponent {
+                0 => mantissa as f32 * f32::from_bits(((127 + 1 - BIAS - M as i32) as u32) << 23),
+                _ => f32::from_bits(
+                    (((exponent - BIAS + 127) as u32) << 23) | (mantissa << (23 - M)),
+                ),
+            };
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+    }
+
+    pub fn f32x2_to_e4m3x2(lo: f32, hi: f32) -> u16 {
+        E4M3::from_f32(lo) as u16 | (E4M3::from_f32(hi) as u16) << 8
+    }
+
+    pub fn f32x2_to_e5m2x2(lo: f32, hi: f32) -> u16 {
+        E5M2::from_f32(lo) as u16 | (E5M2::from_f32(hi) as u16) << 8
+    }
+
+    pub fn f16x2_to_e4m3x2(a: u32) -> u16 {
+        let [lo, hi] = [a as u16, (a >> 16) as u16].map(|x| Half::from_bits(x).to_f32());
+        f32x2_to_e4m3x2(lo, hi)
+    }
+
+    pub fn f16x2_to_e5m2x2(a: u32) -> u16 {
+        let [lo, hi] = [a as u16, (a >> 16) as u16].map(|x| Half::from_bits(x).to_f32());
+        f32x2_to_e5m2x2(lo, hi)
+    }
+
+    pub fn e4m3x2_to_f16x2(a: u16) -> u32 {
+        let [lo, hi] = [a as u8, (a >> 8) as u8].map(|x| Half::from_f32(E4M3::to_f32(x)).to_bits());
+        lo as u32 | (hi as u32) << 16
+    }
+
+    pub fn e5m2x2_to_f16x2(a: u16) -> u32 {
+        let [lo, hi] = [a as u8, (a >> 8) as u8].map(|x| Half::from_f32(E5M2::to_f32(x)).to_bits());
+        lo as u32 | (hi as u32) << 16
+    }
+
+    /// rounds to nearest, ties away from zero
+    pub fn f32_to_tf32(value: f32) -> u32 {
+        let bits = value.to_bits();
+        if value.is_nan() {
+            return 0x7fff_ffff;
+        }
+        // a carry out of the mantissa correctly bumps the exponent, up to infinity
+        (bits + 0x1000) & !0x1fff
+    }
+}
+
+macro_rules! fp8 {
+    ($(#[$attr:meta])* $F:ident, $max:literal, $from_f32x2:ident, $from_f16x2:ident, $to_f16x2:ident) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Default)]
+        pub struct $F(u8);
+
+        impl $F {
+            pub const ZERO: Self = Self(0);
+            pub const NAN: Self = Self(0x7f);
+            pub const MAX: Self = Self($max);
+            pub const MIN: Self = Self(0x80 | $max);
+
+            pub const fn from_bits(bits: u8) -> Self {
+                Self(bits)
+            }
+
+            pub const fn to_bits(self) -> u8 {
+                self.0
+            }
+
+            /// rounds both to nearest, ties to even, saturating, with a single cvt
+            pub fn from_f32x2(lo: f32, hi: f32) -> [Self; 2] {
+                let bits = imp::$from_f32x2(lo, hi);
+                [Self(bits as u8), Self((bits >> 8) as u8)]
+            }
+
+            /// rounds both lanes to nearest, ties to even, saturating, with a single cvt
+            pub fn from_f16x2(value: f16x2) -> [Self; 2] {
+                let bits = imp::$from_f16x2(value.to_bits());
+                [Self(bits as u8), Self((bits >> 8) as u8)]
+            }
+
+            /// exact widening of both, with a single cvt
+            pub fn to_f16x2(pair: [Self; 2]) -> f16x2 {
+                f16x2::from_bits(imp::$to_f16x2(pair[0].0 as u16 | (pair[1].0 as u16) << 8))
+            }
+
+            /// rounds to nearest, ties to even, saturating
+            pub fn from_f32(value: f32) -> Self {
+                Self::from_f32x2(value, 0.0)[0]
+            }
+
+            /// exact widening
+            pub fn to_f16(self) -> Half {
+                Self::to_f16x2([self, Self::ZERO]).lo()
+            }
+
+            /// exact widening
+            pub fn to_f32(self) -> f32 {
+                self.to_f16().to_f32()
+            }
+
+            pub fn is_nan(self) -> bool {
+                self.to_f16().to_bits() & 0x7fff > 0x7c00
+            }
+
+            /// the scale that maps `amax`, the largest magnitude of a tensor, onto the largest finite value,
+            /// or 1 if `amax` is zero or not finite
+            pub fn scale_for(amax: f32) -> f32 {
+                if amax > 0.0 && amax.is_finite() {
+                    Self::MAX.to_f32() / amax
+                } else {
+                    1.0
+                }
+            }
+
+            /// quantizes `value * scale`
+            pub fn from_f32_scaled(value: f32, scale: f32) -> Self {
+                Self::from_f32(value * scale)
+            }
+
+            /// quantizes `lo * scale` and `hi * scale` with a single cvt
+            pub fn from_f32x2_scaled(lo: f32, hi: f32, scale: f32) -> [Self; 2] {
+                Self::from_f32x2(lo * scale, hi * scale)
+            }
+
+            /// dequantizes a value quantized with `scale`
+            pub fn to_f32_scaled(self, scale: f32) -> f32 {
+                self.to_f32() / scale
+            }
+        }
+
+        impl From<$F> for f32 {
+            fn from(value: $F) -> Self {
+                value.to_f32()
+            }
+        }
+
+        impl PartialEq for $F {
+            fn eq(&self, other: &Self) -> bool {
+                self.to_f16() == other.to_f16()
+            }
+        }
+
+        impl fmt::Debug for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.to_f32(), f)
+            }
+        }
+
+        impl fmt::Display for $F {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.to_f32(), f)
+            }
+        }
+    };
+}
+
+fp8!(
+    /// fp8 with 4 exponent and 3 mantissa bits, no infinities, a single NaN and a largest finite value of 448
+    Fp8E4M3, 0x7e, f32x2_to_e4m3x2, f16x2_to_e4m3x2, e4m3x2_to_f16x2
+);
+fp8!(
+    /// fp8 with 5 exponent and 2 mantissa bits, the upper half of an f16, a largest finite value of 57344
+    Fp8E5M2, 0x7b, f32x2_to_e5m2x2, f16x2_to_e5m2x2, e5m2x2_to_f16x2
+);
+
+/// TensorFloat-32, an f32 with the 13 low mantissa bits zero.
+/// It is also the precision marker of tf32 fragments, which load from f32 matrices and whose multiply
+/// only uses the upper 19 bits of every element.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default)]
+pub struct Tf32(u32);
+
+impl Tf32 {
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    /// rounds to nearest, ties away from zero, `cvt.rna.tf32.f32`
+    pub fn from_f32(value: f32) -> Self {
+        Self(imp::f32_to_tf32(value))
+    }
+
+    /// exact widening
+    pub const fn to_f32(self) -> f32 {
+        f32::from_bits(self.0)
+    }
+
+    /// `value` rounded to tf32 precision, the f32 a tf32 fragment should be loaded from
+    pub fn rounded(value: f32) -> f32 {
+        Self::from_f32(value).to_f32()
+    }
+}
+
+impl From<Tf32> for f32 {
+    fn from(value: Tf32) -> Self {
+        value.to_f32()
+    }
+}
+
+impl PartialEq for Tf32 {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_f32() == other.to_f32()
+    }
+}
+
+impl fmt::Debug for Tf32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_f32(), f)
+    }
+}
+
+impl fmt::Display for Tf32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f32(), f)
+    }
+}
@@ -0,0 +1,875 @@
+#[cfg(target_arch = "nvptx64")]
+use crate::wmma::{Regs2, Regs4};
+#[cfg(target_arch = "nvptx64")]
+use crate::Mma;
+use crate::{
+    half2, Accumulator, ColMajor, FillFragment, Fragment, FragmentElement, FragmentUse, MatrixA,
+    MatrixB, RowMajor, Tf32, WARP_SIZE,
+};
+#[cfg(target_arch = "nvptx64")]
+use core::arch::asm;
+use core::mem::transmute;
+#[cfg(target_arch = "nvptx64")]
+use core::mem::transmute_copy;
+
+/*
+Warp-level `mma.sync`, `mma.sp.sync`, `ldmatrix` and `stmatrix`
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#warp-level-matrix-instructions-for-mma
+
+Unlike wmma, the element held by every register of every lane is specified, see [`LaneLayout`].
+Multiplicands are always `RowMajor` A and `ColMajor` B, the layout of the operands in registers.
+Accumulators reuse the wmma [`Fragment`] types with the m16n8k* and m8n8k* shapes.
+
+`ldmatrix` and `stmatrix` only address shared memory and `mma.sp` has no LLVM intrinsic, so those are
+inline assembly. The instructions are device code, the layouts and references also build on the host.
+*/
+
+/// The `M x K/2` compressed left-hand multiplicand of a sparse `mma.sp`, in which two of every four
+/// consecutive elements of a row are zero
+pub struct SparseMatrixA;
+
+/// Signed 4-bit integer precision marker, eight elements per register
+pub struct S4;
+/// Unsigned 4-bit integer precision marker, eight elements per register
+pub struct U4;
+/// Single-bit precision marker, 32 elements per register
+pub struct B1;
+
+impl FragmentElement for S4 {
+    type Value = i8;
+    type Reg = u32;
+    fn splat(value: i8) -> u32 {
+        (value as u32 & 0xf) * 0x1111_1111
+    }
+}
+
+impl FragmentElement for U4 {
+    type Value = u8;
+    type Reg = u32;
+    fn splat(value: u8) -> u32 {
+        (value as u32 & 0xf) * 0x1111_1111
+    }
+}
+
+impl FragmentElement for B1 {
+    type Value = bool;
+    type Reg = u32;
+    fn splat(value: bool) -> u32 {
+        if value {
+            !0
+        } else {
+            0
+        }
+    }
+}
+
+/// Element types the host reference implementations compute with, every element is widened to f64
+pub trait ReferenceElement: FragmentElement {
+    /// elements packed in one register, starting from the low bits
+    const PER_REG: usize;
+    fn unpack(regs: &[Self::Reg], element: usize) -> f64;
+    /// stores `value` rounded or wrapped to the element type
+    fn pack(regs: &mut [Self::Reg], element: usize, value: f64);
+}
+
+/// replaces the `bits`-wide field `index` of `reg`
+fn insert_bits(reg: &mut u32, index: usize, bits: u32, value: u32) {
+    let mask = (u32::MAX >> (32 - bits)) << (index as u32 * bits);
+    *reg = (*reg & !mask) | ((value << (index as u32 * bits)) & mask);
+}
+
+fn extract_bits(reg: u32, index: usize, bits: u32) -> u32 {
+    (reg >> (index as u32 * bits)) & (u32::MAX >> (32 - bits))
+}
+
+impl ReferenceElement for crate::Half {
+    const PER_REG: usize = 2;
+    fn unpack(regs: &[half2], element: usize) -> f64 {
+        let reg: u32 = unsafe { transmute(regs[element / 2]) };
+        crate::Half::from_bits(extract_bits(reg, element % 2, 16) as u16).to_f64()
+    }
+    fn pack(regs: &mut [half2], element: usize, value: f64) {
+        let mut reg: u32 = unsafe { transmute(regs[element / 2]) };
+        let bits = crate::Half::from_f64(value).to_bits();
+        insert_bits(&mut reg, element % 2, 16, bits as u32);
+        regs[element / 2] = unsafe { transmute::<u32, half2>(reg) };
+    }
+}
+
+impl ReferenceElement for crate::BHalf {
+    const PER_REG: usize = 2;
+    fn unpack(regs: &[u32], element: usize) -> f64 {
+        let bits = extract_bits(regs[element / 2], element % 2, 16);
+        crate::BHalf::from_bits(bits as u16).to_f64()
+    }
+    fn pack(regs: &mut [u32], element: usize, value: f64) {
+        let bits = crate::BHalf::from_f64(value).to_bits();
+        insert_bits(&mut regs[element / 2], element % 2, 16, bits as u32);
+    }
+}
+
+impl ReferenceElement for Tf32 {
+    const PER_REG: usize = 1;
+    /// the multiply ignores the 13 low mantissa bits
+    fn unpack(regs: &[u32], element: usize) -> f64 {
+        f32::from_bits(regs[element] & !0x1fff) as f64
+    }
+    fn pack(regs: &mut [u32], element: usize, value: f64) {
+        regs[element] = (value as f32).to_bits();
+    }
+}
+
+macro_rules! impl_reference_int {
+    ($($T: ty => $bits: literal, $signed: literal);+) => {
+        $(impl ReferenceElement for $T {
+            const PER_REG: usize = 32 / $bits;
+            fn unpack(regs: &[u32], element: usize) -> f64 {
+                let bits = extract_bits(regs[element / Self::PER_REG], element % Self::PER_REG, $bits);
+                if $signed {
+                    ((bits << (32 - $bits)) as i32 >> (32 - $bits)) as f64
+                } else {
+                    bits as f64
+                }
+            }
+            fn pack(regs: &mut [u32], element: usize, value: f64) {
+                let bits = value as i64 as u32;
+                insert_bits(&mut regs[element / Self::PER_REG], element % Self::PER_REG, $bits, bits);
+            }
+        })+
+    };
+}
+
+impl_reference_int! { i8 => 8, true; u8 => 8, false; S4 => 4, true; U4 => 4, false; B1 => 1, false }
+
+impl ReferenceElement for f32 {
+    const PER_REG: usize = 1;
+    fn unpack(regs: &[f32], element: usize) -> f64 {
+        regs[element] as f64
+    }
+    fn pack(regs: &mut [f32], element: usize, value: f64) {
+        regs[element] = value as f32;
+    }
+}
+
+impl ReferenceElement for i32 {
+    const PER_REG: usize = 1;
+    fn unpack(regs: &[i32], element: usize) -> f64 {
+        regs[element] as f64
+    }
+    /// wraps around like the non-saturating integer `mma`
+    fn pack(regs: &mut [i32], element: usize, value: f64) {
+        regs[element] = value as i64 as i32;
+    }
+}
+
+/// Fragments whose elements are spread over the lanes of the warp as the PTX ISA specifies.
+///
+/// Elements of a lane are numbered from the low bits of its first register up, see
+/// [`LaneLayout::coords`]. With `group = lane / 4` and `t = lane % 4`, and `e` elements per register:
+/// - A with 4 registers: element `i` of register `r` is `(group + 8 * (r % 2), t * e + i + 4 * e * (r / 2))`
+/// - A with 1 or 2 registers: element `i` of register `r` is `(group + 8 * r, t * e + i)`
+/// - B: element `i` of register `r` is `(t * e + i + 4 * e * r, group)`
+/// - accumulators: element `i` is `(group + 8 * (i / 2), t * 2 + i % 2)`
+pub trait LaneLayout: Copy + Default {
+    type Element: ReferenceElement;
+    const ROWS: usize;
+    const COLS: usize;
+    /// elements held by each lane
+    const ELEMENTS: usize;
+    /// the `(row, column)` of the matrix holding `element` of `lane`
+    fn coords(lane: u32, element: usize) -> (usize, usize);
+    fn regs(&self) -> &[<Self::Element as FragmentElement>::Reg];
+    fn regs_mut(&mut self) -> &mut [<Self::Element as FragmentElement>::Reg];
+
+    fn get(&self, element: usize) -> f64 {
+        Self::Element::unpack(self.regs(), element)
+    }
+
+    fn set(&mut self, element: usize, value: f64) {
+        Self::Element::pack(self.regs_mut(), element, value)
+    }
+
+    /// the fragment of `lane` of the matrix whose elements are given by `matrix(row, column)`
+    fn from_matrix<F: Fn(usize, usize) -> f64>(lane: u32, matrix: F) -> Self {
+        let mut fragment = Self::default();
+        for element in 0..Self::ELEMENTS {
+            let (row, col) = Self::coords(lane, element);
+            fragment.set(element, matrix(row, col));
+        }
+        fragment
+    }
+
+    /// calls `matrix(row, column, value)` for every element held by the warp's fragments
+    fn to_matrix<F: FnMut(usize, usize, f64)>(
+        fragments: &[Self; WARP_SIZE as usize],
+        mut matrix: F,
+    ) {
+        for (lane, fragment) in fragments.iter().enumerate() {
+            for element in 0..Self::ELEMENTS {
+                let (row, col) = Self::coords(lane as u32, element);
+                matrix(row, col, fragment.get(element));
+            }
+        }
+    }
+}
+
+fn coords_a(lane: u32, element: usize, per_reg: usize, regs: usize) -> (usize, usize) {
+    let (group, t) = ((lane / 4) as usize, (lane % 4) as usize);
+    let (reg, i) = (element / per_reg, element % per_reg);
+    if regs == 4 {
+        (
+            group + 8 * (reg % 2),
+            t * per_reg + i + 4 * per_reg * (reg / 2),
+        )
+    } else {
+        (group + 8 * reg, t * per_reg + i)
+    }
+}
+
+fn coords_b(lane: u32, element: usize, per_reg: usize, _regs: usize) -> (usize, usize) {
+    let (group, t) = ((lane / 4) as usize, (lane % 4) as usize);
+    let (reg, i) = (element / per_reg, element % per_reg);
+    (t * per_reg + i + 4 * per_reg * reg, group)
+}
+
+fn coords_c(lane: u32, element: usize, _per_reg: usize, _regs: usize) -> (usize, usize) {
+    let (group, t) = ((lane / 4) as usize, (lane % 4) as usize);
+    (group + 8 * (element / 2), t * 2 + element % 2)
+}
+
+/// Host reference of `mma.sync`, computing in f64 and rounding once to the accumulator type, so
+/// floating point results may differ from the hardware's by its accumulation rounding
+pub trait MmaReference<B, C, D>: Sized {
+    fn reference(
+        a: &[Self; WARP_SIZE as usize],
+        b: &[B; WARP_SIZE as usize],
+        c: &[C; WARP_SIZE as usize],
+    ) -> [D; WARP_SIZE as usize];
+}
+
+/// Integer `mma.sync` clamping the result to the i32 range instead of wrapping around
+pub trait MmaSatfinite<B, C, D>: Sized {
+    #[cfg(target_arch = "nvptx64")]
+    fn mma_satfinite(&self, b: &B, c: &C) -> D;
+    fn reference_satfinite(
+        a: &[Self; WARP_SIZE as usize],
+        b: &[B; WARP_SIZE as usize],
+        c: &[C; WARP_SIZE as usize],
+    ) -> [D; WARP_SIZE as usize];
+}
+
+/// Single-bit `mma.sync`, `D = popc(A op B) + C` where the multiply is a bitwise and or xor
+pub trait MmaPopc<B, C, D>: Sized {
+    #[cfg(target_arch = "nvptx64")]
+    fn mma_and_popc(&self, b: &B, c: &C) -> D;
+    #[cfg(target_arch = "nvptx64")]
+    fn mma_xor_popc(&self, b: &B, c: &C) -> D;
+    fn reference_and_popc(
+        a: &[Self; WARP_SIZE as usize],
+        b: &[B; WARP_SIZE as usize],
+        c: &[C; WARP_SIZE as usize],
+    ) -> [D; WARP_SIZE as usize];
+    fn reference_xor_popc(
+        a: &[Self; WARP_SIZE as usize],
+        b: &[B; WARP_SIZE as usize],
+        c: &[C; WARP_SIZE as usize],
+    ) -> [D; WARP_SIZE as usize];
+}
+
+/// Sparse `mma.sp.sync`, implemented by [`SparseMatrixA`] fragments.
+///
+/// A row of A is split in units, the elements of f16 and bf16, bytes of the integer types and
+/// halves of tf32, two of every four consecutive units are stored. `metadata` holds the 2-bit index
+/// within its group of four of every stored unit, rows `group` and `group + 8` are described by the
+/// lanes `4 * group..4 * group + 4`. A word holds 8 consecutive stored units of both rows, row
+/// `group`'s in its low half, a shape with `n` words per row pair takes them from lanes
+/// `SELECTOR * n..SELECTOR * n + n` of the four.
+/// The result equals the dense [`MmaReference::reference`] on the decompressed A.
+pub trait MmaSparse<B, C, D>: Sized {
+    #[cfg(target_arch = "nvptx64")]
+    fn mma_sparse<const SELECTOR: u32>(&self, b: &B, c: &C, metadata: u32) -> D;
+    /// the host reference of `mma_sparse`, given the `metadata` of every lane
+    fn reference_sparse<const SELECTOR: u32>(
+        a: &[Self; WARP_SIZE as usize],
+        b: &[B; WARP_SIZE as usize],
+        c: &[C; WARP_SIZE as usize],
+        metadata: &[u32; WARP_SIZE as usize],
+    ) -> [D; WARP_SIZE as usize];
+}
+
+// the largest multiplicands are the 16 x 256 and 256 x 8 single-bit ones
+const MAX_A: usize = 16 * 256;
+const MAX_B: usize = 256 * 8;
+
+fn reference<A: LaneLayout, B: LaneLayout, C: LaneLayout, D: LaneLayout>(
+    a: &[A; WARP_SIZE as usize],
+    b: &[B; WARP_SIZE as usize],
+    c: &[C; WARP_SIZE as usize],
+    product: impl Fn(f64, f64) -> f64,
+    round: impl Fn(f64) -> f64,
+) -> [D; WARP_SIZE as usize] {
+    let mut matrix_a = [0f64; MAX_A];
+    A::to_matrix(a, |row, col, value| matrix_a[row * A::COLS + col] = value);
+    multiply(&matrix_a, A::COLS, b, c, product, round)
+}
+
+/// [`reference`] of the A decompressed from its stored elements and `metadata`, see [`MmaSparse`]
+fn reference_sparse<A: LaneLayout, B: LaneLayout, C: LaneLayout, D: LaneLayout>(
+    a: &[A; WARP_SIZE as usize],
+    b: &[B; WARP_SIZE as usize],
+    c: &[C; WARP_SIZE as usize],
+    metadata: &[u32; WARP_SIZE as usize],
+    selector: u32,
+) -> [D; WARP_SIZE as usize] {
+    let k = 2 * A::COLS;
+    let element_bits = 32 / A::Element::PER_REG;
+    let unit_bits = element_bits.clamp(8, 16);
+    let words = k * element_bits / unit_bits / 16;
+    let mut matrix_a = [0f64; MAX_A];
+    A::to_matrix(a, |row, col, value| {
+        // a tf32 element is indexed by its first unit, a 4-bit one shares its unit with a neighbour
+        let unit = col * element_bits / unit_bits;
+        let lane = 4 * (row % 8) + selector as usize * words + unit / 8;
+        let index = (metadata[lane] >> (16 * (row / 8) + 2 * (unit % 8))) & 3;
+        let dense_unit = 4 * (unit / 2) + index as usize;
+        let dense = (dense_unit * unit_bits + col * element_bits % unit_bits) / element_bits;
+        matrix_a[row * k + dense] = value;
+    });
+    multiply(&matrix_a, k, b, c, |a, b| a * b, |d| d)
+}
+
+/// `round(product(A, B) + C)` of the row-major `M x k` A
+fn multiply<B: LaneLayout, C: LaneLayout, D: LaneLayout>(
+    matrix_a: &[f64],
+    k: usize,
+    b: &[B; WARP_SIZE as usize],
+    c: &[C; WARP_SIZE as usize],
+    product: impl Fn(f64, f64) -> f64,
+    round: impl Fn(f64) -> f64,
+) -> [D; WARP_SIZE as usize] {
+    let mut matrix_b = [0f64; MAX_B];
+    B::to_matrix(b, |row, col, value| matrix_b[row * B::COLS + col] = value);
+    debug_assert_eq!((B::ROWS, C::ELEMENTS), (k, D::ELEMENTS));
+    core::array::from_fn(|lane| {
+        let mut d = D::default();
+        for element in 0..D::ELEMENTS {
+            let (row, col) = D::coords(lane as u32, element);
+            let mut acc = c[lane].get(element);
+            for i in 0..k {
+                acc += product(matrix_a[row * k + i], matrix_b[i * B::COLS + col]);
+            }
+            d.set(element, round(acc));
+        }
+        d
+    })
+}
+
+fn saturate(value: f64) -> f64 {
+    value.clamp(i32::MIN as f64, i32::MAX as f64)
+}
+
+macro_rules! operand {
+    ($Use: ident, $M: literal, $N: literal, $K: literal, $T: ty, $L: ty, $regs: literal, $coords: ident, $rows: expr, $cols: expr) => {
+        impl FragmentUse<$M, $N, $K, $T, $L> for $Use {
+            type Regs = [<$T as FragmentElement>::Reg; $regs];
+        }
+
+        impl FillFragment for Fragment<$Use, $M, $N, $K, $T, $L> {
+            type Value = <$T as FragmentElement>::Value;
+            fn fill(value: Self::Value) -> Self {
+                Self::from_regs([<$T as FragmentElement>::splat(value); $regs])
+            }
+        }
+
+        impl Default for Fragment<$Use, $M, $N, $K, $T, $L> {
+            fn default() -> Self {
+                Self::fill(Default::default())
+            }
+        }
+
+        impl LaneLayout for Fragment<$Use, $M, $N, $K, $T, $L> {
+            type Element = $T;
+            const ROWS: usize = $rows;
+            const COLS: usize = $cols;
+            const ELEMENTS: usize = $regs * <$T as ReferenceElement>::PER_REG;
+            fn coords(lane: u32, element: usize) -> (usize, usize) {
+                $coords(lane, element, <$T as ReferenceElement>::PER_REG, $regs)
+            }
+            fn regs(&self) -> &[<$T as FragmentElement>::Reg] {
+                &self.x
+            }
+            fn regs_mut(&mut self) -> &mut [<$T as FragmentElement>::Reg] {
+                &mut self.x
+            }
+        }
+    };
+}
+
+/// declares the multiplicands of the given shapes, `[M, N, K, A registers, B registers]`
+macro_rules! multiplicands {
+    ($($T: ty),+ => $shapes: tt) => {
+        $(multiplicands!(@type $T, $shapes);)+
+    };
+    (@type $T: ty, [$([$M: literal, $N: literal, $K: literal, $a: literal, $b: literal]),+]) => {
+        $(operand!(MatrixA, $M, $N, $K, $T, RowMajor, $a, coords_a, $M, $K);
+        operand!(MatrixB, $M, $N, $K, $T, ColMajor, $b, coords_b, $K, $N);)+
+    };
+}
+
+/// declares the compressed A operands of sparse shapes, `[M, N, K, A registers]`
+macro_rules! sparse_multiplicands {
+    ($($T: ty),+ => $shapes: tt) => {
+        $(sparse_multiplicands!(@type $T, $shapes);)+
+    };
+    (@type $T: ty, [$([$M: literal, $N: literal, $K: literal, $a: literal]),+]) => {
+        $(operand!(SparseMatrixA, $M, $N, $K, $T, RowMajor, $a, coords_a, $M, $K / 2);)+
+    };
+}
+
+/// declares B operands only used by sparse shapes, `[M, N, K, B registers]`
+macro_rules! sparse_b {
+    ($($T: ty),+ => $shapes: tt) => {
+        $(sparse_b!(@type $T, $shapes);)+
+    };
+    (@type $T: ty, [$([$M: literal, $N: literal, $K: literal, $b: literal]),+]) => {
+        $(operand!(MatrixB, $M, $N, $K, $T, ColMajor, $b, coords_b, $K, $N);)+
+    };
+}
+
+macro_rules! accumulators {
+    ($T: ty => [$([$M: literal, $N: literal, $K: literal, $regs: literal]),+]) => {
+        $(operand!(Accumulator, $M, $N, $K, $T, (), $regs, coords_c, $M, $N);)+
+    };
+}
+
+/// calls the `mma.sync` intrinsic `$link` on fragments `$a`, `$b` and `$c`, the registers of each are
+/// bound to the identifiers of the lists
+#[cfg(target_arch = "nvptx64")]
+macro_rules! mma_call {
+    ($link: expr, $M: literal, $N: literal, $K: literal, $TA: ty, $TB: ty, $TC: ty, $Ret: ty, $a: expr, $b: expr, $c: expr, [$($ra: ident)+] [$($rb: ident)+] [$($rc: ident)+]) => {{
+        #[allow(improper_ctypes)]
+        extern "unadjusted" {
+            #[link_name = $link]
+            fn mma(
+                $($ra: <$TA as FragmentElement>::Reg,)+
+                $($rb: <$TB as FragmentElement>::Reg,)+
+                $($rc: <$TC as FragmentElement>::Reg,)+
+            ) -> $Ret;
+        }
+        let [$($ra),+] = $a.x;
+        let [$($rb),+] = $b.x;
+        let [$($rc),+] = $c.x;
+        let regs = unsafe { mma($($ra,)+ $($rb,)+ $($rc,)+) };
+        Fragment::from_regs(unsafe {
+            transmute::<$Ret, <Accumulator as FragmentUse<$M, $N, $K, $TC, ()>>::Regs>(regs)
+        })
+    }};
+}
+
+/// `mma.sync` of `A x B + C` for the given shapes, `$regs` lists the registers of A, B and C
+macro_rules! mma {
+    (@dense $M: literal, $N: literal, $K: literal, $TA: ty, $TB: ty, $TC: ty, $types: literal, $Ret: ty, $($regs: tt)+) => {
+        #[cfg(target_arch = "nvptx64")]
+        impl Mma<
+                Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+            > for Fragment<MatrixA, $M, $N, $K, $TA, RowMajor>
+        {
+            fn mma(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+                c: &Fragment<Accumulator, $M, $N, $K, $TC>,
+            ) -> Fragment<Accumulator, $M, $N, $K, $TC> {
+                mma_call!(
+                    concat!("llvm.nvvm.mma.m", $M, "n", $N, "k", $K, ".row.col", $types),
+                    $M, $N, $K, $TA, $TB, $TC, $Ret, self, b, c, $($regs)+
+                )
+            }
+        }
+
+        impl MmaReference<
+                Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+            > for Fragment<MatrixA, $M, $N, $K, $TA, RowMajor>
+        {
+            fn reference(
+                a: &[Self; WARP_SIZE as usize],
+                b: &[Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>; WARP_SIZE as usize],
+                c: &[Fragment<Accumulator, $M, $N, $K, $TC>; WARP_SIZE as usize],
+            ) -> [Fragment<Accumulator, $M, $N, $K, $TC>; WARP_SIZE as usize] {
+                reference(a, b, c, |a, b| a * b, |d| d)
+            }
+        }
+    };
+    (@integer $M: literal, $N: literal, $K: literal, $TA: ty, $TB: ty, $types: literal, $Ret: ty, $($regs: tt)+) => {
+        mma!(@dense $M, $N, $K, $TA, $TB, i32, $types, $Ret, $($regs)+);
+
+        impl MmaSatfinite<
+                Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+                Fragment<Accumulator, $M, $N, $K, i32>,
+                Fragment<Accumulator, $M, $N, $K, i32>,
+            > for Fragment<MatrixA, $M, $N, $K, $TA, RowMajor>
+        {
+            #[cfg(target_arch = "nvptx64")]
+            fn mma_satfinite(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+                c: &Fragment<Accumulator, $M, $N, $K, i32>,
+            ) -> Fragment<Accumulator, $M, $N, $K, i32> {
+                mma_call!(
+                    concat!("llvm.nvvm.mma.m", $M, "n", $N, "k", $K, ".row.col.satfinite", $types),
+                    $M, $N, $K, $TA, $TB, i32, $Ret, self, b, c, $($regs)+
+                )
+            }
+
+            fn reference_satfinite(
+                a: &[Self; WARP_SIZE as usize],
+                b: &[Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>; WARP_SIZE as usize],
+                c: &[Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize],
+            ) -> [Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize] {
+                reference(a, b, c, |a, b| a * b, saturate)
+            }
+        }
+    };
+    (@popc $M: literal, $N: literal, $K: literal, $Ret: ty, $($regs: tt)+) => {
+        impl MmaPopc<
+                Fragment<MatrixB, $M, $N, $K, B1, ColMajor>,
+                Fragment<Accumulator, $M, $N, $K, i32>,
+                Fragment<Accumulator, $M, $N, $K, i32>,
+            > for Fragment<MatrixA, $M, $N, $K, B1, RowMajor>
+        {
+            #[cfg(target_arch = "nvptx64")]
+            fn mma_and_popc(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, B1, ColMajor>,
+                c: &Fragment<Accumulator, $M, $N, $K, i32>,
+            ) -> Fragment<Accumulator, $M, $N, $K, i32> {
+                mma_call!(
+                    concat!("llvm.nvvm.mma.and.popc.m", $M, "n", $N, "k", $K, ".row.col.b1"),
+                    $M, $N, $K, B1, B1, i32, $Ret, self, b, c, $($regs)+
+                )
+            }
+
+            #[cfg(target_arch = "nvptx64")]
+            fn mma_xor_popc(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, B1, ColMajor>,
+                c: &Fragment<Accumulator, $M, $N, $K, i32>,
+            ) -> Fragment<Accumulator, $M, $N, $K, i32> {
+                mma_call!(
+                    concat!("llvm.nvvm.mma.xor.popc.m", $M, "n", $N, "k", $K, ".row.col.b1"),
+                    $M, $N, $K, B1, B1, i32, $Ret, self, b, c, $($regs)+
+                )
+            }
+
+            fn reference_and_popc(
+                a: &[Self; WARP_SIZE as usize],
+                b: &[Fragment<MatrixB, $M, $N, $K, B1, ColMajor>; WARP_SIZE as usize],
+                c: &[Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize],
+            ) -> [Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize] {
+                reference(a, b, c, |a, b| a * b, |d| d)
+            }
+
+            fn reference_xor_popc(
+                a: &[Self; WARP_SIZE as usize],
+                b: &[Fragment<MatrixB, $M, $N, $K, B1, ColMajor>; WARP_SIZE as usize],
+                c: &[Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize],
+            ) -> [Fragment<Accumulator, $M, $N, $K, i32>; WARP_SIZE as usize] {
+                reference(a, b, c, |a, b| if a != b { 1.0 } else { 0.0 }, |d| d)
+            }
+        }
+    };
+}
+
+/// integer `mma.sync` of every type combination over the given shapes,
+/// `[M, N, K], C registers, [A registers] [B registers] [C registers]`
+macro_rules! mma_integer {
+    ($($TA: ty, $TB: ty, $types: literal);+ => $shapes: tt) => {
+        $(mma_integer!(@type $TA, $TB, $types, $shapes);)+
+    };
+    (@type $TA: ty, $TB: ty, $types: literal, [$([$M: literal, $N: literal, $K: literal], $Ret: ty, $a: tt $b: tt $c: tt);+]) => {
+        $(mma!(@integer $M, $N, $K, $TA, $TB, $types, $Ret, $a $b $c);)+
+    };
+}
+
+#[cfg(target_arch = "nvptx64")]
+fn to_b32<R: Copy>(reg: R) -> u32 {
+    const { assert!(size_of::<R>() == 4) };
+    unsafe { transmute_copy(&reg) }
+}
+
+#[cfg(target_arch = "nvptx64")]
+fn from_b32<R: Copy>(reg: u32) -> R {
+    const { assert!(size_of::<R>() == 4) };
+    unsafe { transmute_copy(&reg) }
+}
+
+/// `mma.sp.sync` of a compressed A, selectors lists the valid sparsity selectors of the type
+macro_rules! mma_sparse {
+    ($M: literal, $N: literal, $K: literal, $T: ty, $TC: ty, $types: literal, [$($selector: literal)+], $a: tt $b: tt $c: tt) => {
+        impl MmaSparse<
+                Fragment<MatrixB, $M, $N, $K, $T, ColMajor>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+            > for Fragment<SparseMatrixA, $M, $N, $K, $T, RowMajor>
+        {
+            #[cfg(target_arch = "nvptx64")]
+            fn mma_sparse<const SELECTOR: u32>(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, $T, ColMajor>,
+                c: &Fragment<Accumulator, $M, $N, $K, $TC>,
+                metadata: u32,
+            ) -> Fragment<Accumulator, $M, $N, $K, $TC> {
+                const { assert!(matches!(SELECTOR, $($selector)|+), "invalid sparsity selector") };
+                let a = self.x.map(to_b32);
+                let b = b.x.map(to_b32);
+                let mut d = c.x.map(to_b32);
+                match SELECTOR {
+                    $($selector => mma_sparse!(@asm $M, $N, $K, $types, $selector, a, b, d, metadata, $a $b $c),)+
+                    _ => unreachable!(),
+                }
+                Fragment::from_regs(d.map(from_b32))
+            }
+
+            fn reference_sparse<const SELECTOR: u32>(
+                a: &[Self; WARP_SIZE as usize],
+                b: &[Fragment<MatrixB, $M, $N, $K, $T, ColMajor>; WARP_SIZE as usize],
+                c: &[Fragment<Accumulator, $M, $N, $K, $TC>; WARP_SIZE as usize],
+                metadata: &[u32; WARP_SIZE as usize],
+            ) -> [Fragment<Accumulator, $M, $N, $K, $TC>; WARP_SIZE as usize] {
+                const { assert!(matches!(SELECTOR, $($selector)|+), "invalid sparsity selector") };
+                reference_sparse(a, b, c, metadata, SELECTOR)
+            }
+        }
+    };
+    (@asm $M: literal, $N: literal, $K: literal, $types: literal, $selector: literal, $a: ident, $b: ident, $d: ident, $metadata: ident,
+        [$a0: ident $($ra: ident)*] [$b0: ident $($rb: ident)*] [$c0: ident $($rc: ident)*]) => {{
+        let [$a0, $($ra),*] = $a;
+        let [$b0, $($rb),*] = $b;
+        let [$c0, $($rc),*] = &mut $d;
+        unsafe {
+            asm!(
+                concat!(
+                    "mma.sp.sync.aligned.m", $M, "n", $N, "k", $K, ".row.col", $types, " ",
+                    "{{{", stringify!($c0), "}", $(", {", stringify!($rc), "}",)* "}}, ",
+                    "{{{", stringify!($a0), "}", $(", {", stringify!($ra), "}",)* "}}, ",
+                    "{{{", stringify!($b0), "}", $(", {", stringify!($rb), "}",)* "}}, ",
+                    "{{{", stringify!($c0), "}", $(", {", stringify!($rc), "}",)* "}}, ",
+                    "{metadata}, ", $selector, ";"
+                ),
+                $a0 = in(reg32) $a0,
+                $($ra = in(reg32) $ra,)*
+                $b0 = in(reg32) $b0,
+                $($rb = in(reg32) $rb,)*
+                $c0 = inout(reg32) *$c0,
+                $($rc = inout(reg32) *$rc,)*
+                metadata = in(reg32) $metadata,
+                options(pure, nomem, nostack),
+            )
+        }
+    }};
+}
+
+macro_rules! ldmatrix {
+    ($($(#[$attr: meta])* $name: ident, $num: literal, $trans: literal, $n: literal, [$r0: ident $($r: ident)*]);+) => {
+        $($(#[$attr])*
+        #[cfg(target_arch = "nvptx64")]
+        pub unsafe fn $name<T>(row: *const T) -> [u32; $n] {
+            let $r0: u32;
+            $(let $r: u32;)*
+            unsafe {
+                asm!(
+                    "{{",
+                    ".reg .u64 s;",
+                    "cvta.to.shared.u64 s, {row};",
+                    concat!(
+                        "ldmatrix.sync.aligned.m8n8", $num, $trans, ".shared.b16 ",
+                        "{{{", stringify!($r0), "}", $(", {", stringify!($r), "}",)* "}}, [s];"
+                    ),
+                    "}}",
+                    row = in(reg64) row,
+                    $r0 = out(reg32) $r0,
+                    $($r = out(reg32) $r,)*
+                    options(readonly, nostack),
+                )
+            }
+            [$r0, $($r),*]
+        })+
+    };
+}
+
+macro_rules! stmatrix {
+    ($($(#[$attr: meta])* $name: ident, $num: literal, $trans: literal, $n: literal, [$r0: ident $($r: ident)*]);+) => {
+        $($(#[$attr])*
+        #[cfg(target_arch = "nvptx64")]
+        pub unsafe fn $name<T>(row: *mut T, regs: [u32; $n]) {
+            let [$r0, $($r),*] = regs;
+            unsafe {
+                asm!(
+                    "{{",
+                    ".reg .u64 s;",
+                    "cvta.to.shared.u64 s, {row};",
+                    concat!(
+                        "stmatrix.sync.aligned.m8n8", $num, $trans, ".shared.b16 [s], ",
+                        "{{{", stringify!($r0), "}", $(", {", stringify!($r), "}",)* "}};"
+                    ),
+                    "}}",
+                    row = in(reg64) row,
+                    $r0 = in(reg32) $r0,
+                    $($r = in(reg32) $r,)*
+                    options(nostack),
+                )
+            }
+        })+
+    };
+}
+
+ldmatrix! {
+    /// Loads one 8x8 matrix of 16-bit elements from shared memory, sm_75.
+    ///
+    /// Lanes `0..8` give the address of a 16-byte aligned row each, the register of lane `l` receives
+    /// elements `2 * (l % 4)` and `2 * (l % 4) + 1` of row `l / 4`, the layout of a 16-bit B or
+    /// accumulator register.
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x1, ".x1", "", 1, [r0];
+    /// Loads two 8x8 matrices, lanes `0..16` give row addresses, see [`ldmatrix_x1`].
+    /// The result is a B register pair of `m16n8k16`.
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x2, ".x2", "", 2, [r0 r1];
+    /// Loads four 8x8 matrices, every lane gives a row address, see [`ldmatrix_x1`].
+    /// The result is an A fragment of `m16n8k16` when the matrices are its four 8x8 quadrants in
+    /// column-major order.
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x4, ".x4", "", 4, [r0 r1 r2 r3];
+    /// [`ldmatrix_x1`] of the transposed matrix
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x1_trans, ".x1", ".trans", 1, [r0];
+    /// [`ldmatrix_x2`] of the transposed matrices, which loads B from a row-major K x N matrix
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x2_trans, ".x2", ".trans", 2, [r0 r1];
+    /// [`ldmatrix_x4`] of the transposed matrices
+    ///
+    /// # Safety
+    /// `row` must point to 16 readable bytes of shared memory
+    ldmatrix_x4_trans, ".x4", ".trans", 4, [r0 r1 r2 r3]
+}
+
+stmatrix! {
+    /// Stores one 8x8 matrix of 16-bit elements to shared memory, the inverse of [`ldmatrix_x1`], sm_90
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x1, ".x1", "", 1, [r0];
+    /// Stores two 8x8 matrices, the inverse of [`ldmatrix_x2`], sm_90
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x2, ".x2", "", 2, [r0 r1];
+    /// Stores four 8x8 matrices, the inverse of [`ldmatrix_x4`], sm_90
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x4, ".x4", "", 4, [r0 r1 r2 r3];
+    /// [`stmatrix_x1`] of the transposed matrix
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x1_trans, ".x1", ".trans", 1, [r0];
+    /// [`stmatrix_x2`] of the transposed matrices
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x2_trans, ".x2", ".trans", 2, [r0 r1];
+    /// [`stmatrix_x4`] of the transposed matrices
+    ///
+    /// # Safety
+    /// `row` must point to 16 writable bytes of shared memory
+    stmatrix_x4_trans, ".x4", ".trans", 4, [r0 r1 r2 r3]
+}
+
+multiplicands!(crate::Half, crate::BHalf => [[16, 8, 8, 2, 1], [16, 8, 16, 4, 2]]);
+multiplicands!(Tf32 => [[16, 8, 4, 2, 1], [16, 8, 8, 4, 2]]);
+multiplicands!(i8, u8 => [[8, 8, 16, 1, 1], [16, 8, 16, 2, 1], [16, 8, 32, 4, 2]]);
+multiplicands!(S4, U4 => [[8, 8, 32, 1, 1], [16, 8, 32, 2, 1], [16, 8, 64, 4, 2]]);
+multiplicands!(B1 => [[8, 8, 128, 1, 1], [16, 8, 128, 2, 1], [16, 8, 256, 4, 2]]);
+
+sparse_multiplicands!(crate::Half, crate::BHalf => [[16, 8, 16, 2], [16, 8, 32, 4]]);
+sparse_multiplicands!(Tf32 => [[16, 8, 8, 2], [16, 8, 16, 4]]);
+sparse_multiplicands!(i8, u8 => [[16, 8, 32, 2], [16, 8, 64, 4]]);
+sparse_multiplicands!(S4, U4 => [[16, 8, 64, 2], [16, 8, 128, 4]]);
+sparse_b!(crate::Half, crate::BHalf => [[16, 8, 32, 4]]);
+sparse_b!(Tf32 => [[16, 8, 16, 4]]);
+sparse_b!(i8, u8 => [[16, 8, 64, 4]]);
+sparse_b!(S4, U4 => [[16, 8, 128, 4]]);
+
+accumulators!(crate::Half => [[16, 8, 8, 2], [16, 8, 16, 2], [16, 8, 32, 2]]);
+accumulators!(f32 => [[16, 8, 4, 4], [16, 8, 8, 4], [16, 8, 16, 4], [16, 8, 32, 4]]);
+accumulators!(i32 => [
+    [8, 8, 16, 2], [16, 8, 16, 4], [16, 8, 32, 4], [8, 8, 32, 2], [16, 8, 64, 4], [8, 8, 128, 2],
+    [16, 8, 128, 4], [16, 8, 256, 4]
+]);
+
+// f16, sm_80
+mma!(@dense 16, 8, 8, crate::Half, crate::Half, crate::Half, ".f16.f16", Regs2<half2>, [a0 a1] [b0] [c0 c1]);
+mma!(@dense 16, 8, 8, crate::Half, crate::Half, f32, ".f32.f32", Regs4<f32>, [a0 a1] [b0] [c0 c1 c2 c3]);
+mma!(@dense 16, 8, 16, crate::Half, crate::Half, crate::Half, ".f16.f16", Regs2<half2>, [a0 a1 a2 a3] [b0 b1] [c0 c1]);
+mma!(@dense 16, 8, 16, crate::Half, crate::Half, f32, ".f32.f32", Regs4<f32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]);
+
+// bf16 and tf32, sm_80
+mma!(@dense 16, 8, 8, crate::BHalf, crate::BHalf, f32, ".bf16", Regs4<f32>, [a0 a1] [b0] [c0 c1 c2 c3]);
+mma!(@dense 16, 8, 16, crate::BHalf, crate::BHalf, f32, ".bf16", Regs4<f32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]);
+mma!(@dense 16, 8, 4, Tf32, Tf32, f32, ".tf32", Regs4<f32>, [a0 a1] [b0] [c0 c1 c2 c3]);
+mma!(@dense 16, 8, 8, Tf32, Tf32, f32, ".tf32", Regs4<f32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]);
+
+// 8-bit integers, m8n8k16 sm_75 and the others sm_80
+mma_integer!(
+    i8, i8, ".s8"; u8, u8, ".u8"; i8, u8, ".s8.u8"; u8, i8, ".u8.s8" => [
+        [8, 8, 16], Regs2<i32>, [a0] [b0] [c0 c1];
+        [16, 8, 16], Regs4<i32>, [a0 a1] [b0] [c0 c1 c2 c3];
+        [16, 8, 32], Regs4<i32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]
+    ]
+);
+
+// 4-bit integers, m8n8k32 sm_75 and the others sm_80
+mma_integer!(
+    S4, S4, ".s4"; U4, U4, ".u4"; S4, U4, ".s4.u4"; U4, S4, ".u4.s4" => [
+        [8, 8, 32], Regs2<i32>, [a0] [b0] [c0 c1];
+        [16, 8, 32], Regs4<i32>, [a0 a1] [b0] [c0 c1 c2 c3];
+        [16, 8, 64], Regs4<i32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]
+    ]
+);
+
+// single bits, m8n8k128 sm_75 and the others sm_80
+mma!(@popc 8, 8, 128, Regs2<i32>, [a0] [b0] [c0 c1]);
+mma!(@popc 16, 8, 128, Regs4<i32>, [a0 a1] [b0] [c0 c1 c2 c3]);
+mma!(@popc 16, 8, 256, Regs4<i32>, [a0 a1 a2 a3] [b0 b1] [c0 c1 c2 c3]);
+
+// sparse, sm_80
+mma_sparse!(16, 8, 16, crate::Half, crate::Half, ".f16.f16.f16.f16", [0 1], [a0 a1] [b0 b1] [c0 c1]);
+mma_sparse!(16, 8, 16, crate::Half, f32, ".f32.f16.f16.f32", [0 1], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 32, crate::Half, crate::Half, ".f16.f16.f16.f16", [0 1], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1]);
+mma_sparse!(16, 8, 32, crate::Half, f32, ".f32.f16.f16.f32", [0 1], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 16, crate::BHalf, f32, ".f32.bf16.bf16.f32", [0 1], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 32, crate::BHalf, f32, ".f32.bf16.bf16.f32", [0 1], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 8, Tf32, f32, ".f32.tf32.tf32.f32", [0 1], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 16, Tf32, f32, ".f32.tf32.tf32.f32", [0 1], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 32, i8, i32, ".s32.s8.s8.s32", [0], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 64, i8, i32, ".s32.s8.s8.s32", [0], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 32, u8, i32, ".s32.u8.u8.s32", [0], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 64, u8, i32, ".s32.u8.u8.s32", [0], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 64, S4, i32, ".s32.s4.s4.s32", [0], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 128, S4, i32, ".s32.s4.s4.s32", [0], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 64, U4, i32, ".s32.u4.u4.s32", [0], [a0 a1] [b0 b1] [c0 c1 c2 c3]);
+mma_sparse!(16, 8, 128, U4, i32, ".s32.u4.u4.s32", [0], [a0 a1 a2 a3] [b0 b1 b2 b3] [c0 c1 c2 c3]);
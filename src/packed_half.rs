@@ -0,0 +1,502 @@
+use crate::{BHalf, Half};
+use core::fmt;
+use core::ops::*;
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#half-precision-floating-point-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#parallel-synchronization-and-communication-instructions-atom
+
+Two halves packed in a 32 bit register, the first lane in the low 16 bits like CUDA's __half2, so every
+instruction processes both lanes. f16x2 arithmetic needs sm_53, min/max and the packed cvt sm_80, atom/red.add
+sm_60, ex2/tanh.approx.f16x2 sm_75. bf16x2 has only fma before sm_90, so add, sub and mul are fmas with 1, -1 and -0 and need sm_80, as
+does its min/max and cvt, its atom/red.add needs sm_90. On the host each lane is computed with the scalar
+types, which give the same bits.
+*/
+
+#[cfg(target_arch = "nvptx64")]
+use self::device as imp;
+#[cfg(not(target_arch = "nvptx64"))]
+use self::soft as imp;
+
+#[cfg(target_arch = "nvptx64")]
+mod device {
+    use core::arch::asm;
+
+    const BF16X2_ONE: u32 = 0x3f80_3f80;
+    const BF16X2_NEG_ONE: u32 = 0xbf80_bf80;
+    const BF16X2_NEG_ZERO: u32 = 0x8000_8000;
+
+    macro_rules! unary {
+        ($name:ident, $op:literal) => {
+            #[inline]
+            pub fn $name(a: u32) -> u32 {
+                let d: u32;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {};"),
+                        out(reg32) d, in(reg32) a,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    unary!(f16x2_exp2_approx, "ex2.approx.f16x2");
+    unary!(f16x2_tanh_approx, "tanh.approx.f16x2");
+
+    macro_rules! binary {
+        ($name:ident, $op:literal) => {
+            #[inline]
+            pub fn $name(a: u32, b: u32) -> u32 {
+                let d: u32;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {};"),
+                        out(reg32) d, in(reg32) a, in(reg32) b,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    binary!(f16x2_add, "add.rn.f16x2");
+    binary!(f16x2_sub, "sub.rn.f16x2");
+    binary!(f16x2_mul, "mul.rn.f16x2");
+    binary!(f16x2_min, "min.f16x2");
+    binary!(f16x2_max, "max.f16x2");
+    binary!(f16x2_min_nan, "min.NaN.f16x2");
+    binary!(f16x2_max_nan, "max.NaN.f16x2");
+    binary!(bf16x2_min, "min.bf16x2");
+    binary!(bf16x2_max, "max.bf16x2");
+    binary!(bf16x2_min_nan, "min.NaN.bf16x2");
+    binary!(bf16x2_max_nan, "max.NaN.bf16x2");
+
+    macro_rules! ternary {
+        ($name:ident, $op:literal) => {
+            #[inline]
+            pub fn $name(a: u32, b: u32, c: u32) -> u32 {
+                let d: u32;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {}, {};"),
+                        out(reg32) d, in(reg32) a, in(reg32) b, in(reg32) c,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    ternary!(f16x2_fma, "fma.rn.f16x2");
+    ternary!(bf16x2_fma, "fma.rn.bf16x2");
+
+    pub fn bf16x2_add(a: u32, b: u32) -> u32 {
+        bf16x2_fma(a, BF16X2_ONE, b)
+    }
+
+    pub fn bf16x2_sub(a: u32, b: u32) -> u32 {
+        bf16x2_fma(b, BF16X2_NEG_ONE, a)
+    }
+
+    pub fn bf16x2_mul(a: u32, b: u32) -> u32 {
+        bf16x2_fma(a, b, BF16X2_NEG_ZERO)
+    }
+
+    macro_rules! convert {
+        ($name:ident, $op:literal) => {
+            /// the first operand of the cvt goes to the upper half
+            #[inline]
+            pub fn $name(lo: f32, hi: f32) -> u32 {
+                let d: u32;
+                unsafe {
+                    asm!(
+                        concat!($op, " {}, {}, {};"),
+                        out(reg32) d, in(reg32) hi, in(reg32) lo,
+                        options(pure, nomem, nostack)
+                    );
+                }
+                d
+            }
+        };
+    }
+
+    convert!(f32_to_f16x2, "cvt.rn.f16x2.f32");
+    convert!(f32_to_bf16x2, "cvt.rn.bf16x2.f32");
+
+    macro_rules! atomic {
+        ($atom:ident, $red:ident, $ty:literal) => {
+            #[inline]
+            pub unsafe fn $atom(ptr: *mut u32, value: u32) -> u32 {
+                let old: u32;
+                unsafe {
+                    asm!(
+                        concat!("atom.add.noftz.", $ty, " {}, [{}], {};"),
+                        out(reg32) old, in(reg64) ptr, in(reg32) value,
+                        options(nostack)
+                    );
+                }
+                old
+            }
+
+            #[inline]
+            pub unsafe fn $red(ptr: *mut u32, value: u32) {
+                unsafe {
+                    asm!(
+                        concat!("red.add.noftz.", $ty, " [{}], {};"),
+                        in(reg64) ptr, in(reg32) value,
+                        options(nostack)
+                    );
+                }
+            }
+        };
+    }
+
+    atomic!(f16x2_atom_add, f16x2_red_add, "f16x2");
+    atomic!(bf16x2_atom_add, bf16x2_red_add, "bf16x2");
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    use crate::{BHalf, Half};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    fn lanes(a: u32, b: u32, op: impl Fn(u16, u16) -> u16) -> u32 {
+        op(a as u16, b as u16) as u32 | (op((a >> 16) as u16, (b >> 16) as u16) as u32) << 16
+    }
+
+    pub fn f16x2_exp2_approx(a: u32) -> u32 {
+        lanes(a, 0, |a, _| Half::from_bits(a).exp2_approx().to_bits())
+    }
+
+    pub fn f16x2_tanh_approx(a: u32) -> u32 {
+        lanes(a, 0, |a, _| Half::from_bits(a).tanh_approx().to_bits())
+    }
+
+    /// IEEE minNum, -0 is less than +0 and a NaN only if both are
+    fn min(a: f32, b: f32, bits: (u16, u16)) -> u16 {
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => 0x7fff,
+            (true, false) => bits.1,
+            (false, true) => bits.0,
+            _ if a < b || (a == b && a.is_sign_negative()) => bits.0,
+            _ => bits.1,
+        }
+    }
+
+    /// IEEE maxNum, +0 is greater than -0 and a NaN only if both are
+    fn max(a: f32, b: f32, bits: (u16, u16)) -> u16 {
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => 0x7fff,
+            (true, false) => bits.1,
+            (false, true) => bits.0,
+            _ if a > b || (a == b && a.is_sign_positive()) => bits.0,
+            _ => bits.1,
+        }
+    }
+
+    macro_rules! lanewise {
+        ($F:ident, $add:ident, $sub:ident, $mul:ident, $fma:ident, $min:ident, $max:ident,
+         $min_nan:ident, $max_nan:ident, $convert:ident, $atom:ident, $red:ident) => {
+            pub fn $add(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| ($F::from_bits(a) + $F::from_bits(b)).to_bits())
+            }
+
+            pub fn $sub(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| ($F::from_bits(a) - $F::from_bits(b)).to_bits())
+            }
+
+            pub fn $mul(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| ($F::from_bits(a) * $F::from_bits(b)).to_bits())
+            }
+
+            pub fn $fma(a: u32, b: u32, c: u32) -> u32 {
+                let fma = |shift: u32| {
+                    let [a, b, c] = [a, b, c].map(|x| $F::from_bits((x >> shift) as u16));
+                    a.fma(b, c).to_bits() as u32
+                };
+                fma(0) | fma(16) << 16
+            }
+
+            pub fn $min(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| {
+                    min($F::from_bits(a).to_f32(), $F::from_bits(b).to_f32(), (a, b))
+                })
+            }
+
+            pub fn $max(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| {
+                    max($F::from_bits(a).to_f32(), $F::from_bits(b).to_f32(), (a, b))
+                })
+            }
+
+            pub fn $min_nan(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| {
+                    let (x, y) = ($F::from_bits(a).to_f32(), $F::from_bits(b).to_f32());
+                    if x.is_nan() || y.is_nan() {
+                        0x7fff
+                    } else {
+                        min(x, y, (a, b))
+                    }
+                })
+            }
+
+            pub fn $max_nan(a: u32, b: u32) -> u32 {
+                lanes(a, b, |a, b| {
+                    let (x, y) = ($F::from_bits(a).to_f32(), $F::from_bits(b).to_f32());
+                    if x.is_nan() || y.is_nan() {
+                        0x7fff
+                    } else {
+                        max(x, y, (a, b))
+                    }
+                })
+            }
+
+            pub fn $convert(lo: f32, hi: f32) -> u32 {
+                $F::from_f32(lo).to_bits() as u32 | ($F::from_f32(hi).to_bits() as u32) << 16
+            }
+
+            pub unsafe fn $atom(ptr: *mut u32, value: u32) -> u32 {
+                let atomic = unsafe { AtomicU32::from_ptr(ptr) };
+                let mut old = atomic.load(Ordering::Relaxed);
+                while let Err(current) = atomic.compare_exchange_weak(
+                    old,
+                    $add(old, value),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    old = current;
+                }
+                old
+            }
+
+            pub unsafe fn $red(ptr: *mut u32, value: u32) {
+                unsafe { $atom(ptr, value) };
+            }
+        };
+    }
+
+    lanewise!(
+        Half,
+        f16x2_add,
+        f16x2_sub,
+        f16x2_mul,
+        f16x2_fma,
+        f16x2_min,
+        f16x2_max,
+        f16x2_min_nan,
+        f16x2_max_nan,
+        f32_to_f16x2,
+        f16x2_atom_add,
+        f16x2_red_add
+    );
+    lanewise!(
+        BHalf,
+        bf16x2_add,
+        bf16x2_sub,
+        bf16x2_mul,
+        bf16x2_fma,
+        bf16x2_min,
+        bf16x2_max,
+        bf16x2_min_nan,
+        bf16x2_max_nan,
+        f32_to_bf16x2,
+        bf16x2_atom_add,
+        bf16x2_red_add
+    );
+}
+
+macro_rules! packed_half {
+    ($(#[$attr:meta])* $F2:ident, $F:ident, $add:ident, $sub:ident, $mul:ident, $fma:ident, $min:ident,
+     $max:ident, $min_nan:ident, $max_nan:ident, $convert:ident, $atom:ident, $red:ident) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Default)]
+        pub struct $F2(u32);
+
+        impl $F2 {
+            pub const fn from_bits(bits: u32) -> Self {
+                Self(bits)
+            }
+
+            pub const fn to_bits(self) -> u32 {
+                self.0
+            }
+
+            pub const fn new(lo: $F, hi: $F) -> Self {
+                Self(lo.to_bits() as u32 | (hi.to_bits() as u32) << 16)
+            }
+
+            pub const fn splat(value: $F) -> Self {
+                Self::new(value, value)
+            }
+
+            /// the first lane, in the low 16 bits
+            pub const fn lo(self) -> $F {
+                $F::from_bits(self.0 as u16)
+            }
+
+            /// the second lane, in the high 16 bits
+            pub const fn hi(self) -> $F {
+                $F::from_bits((self.0 >> 16) as u16)
+            }
+
+            /// rounds both to nearest, ties to even, with a single cvt
+            pub fn from_f32(lo: f32, hi: f32) -> Self {
+                Self(imp::$convert(lo, hi))
+            }
+
+            /// exact widening of both lanes
+            pub fn to_f32(self) -> (f32, f32) {
+                (self.lo().to_f32(), self.hi().to_f32())
+            }
+
+            /// self * b + c in both lanes with a single rounding
+            pub fn fma(self, b: Self, c: Self) -> Self {
+                Self(imp::$fma(self.0, b.0, c.0))
+            }
+
+            /// lanewise minimum, a NaN lane only if both are NaN
+            pub fn min(self, other: Self) -> Self {
+                Self(imp::$min(self.0, other.0))
+            }
+
+            /// lanewise maximum, a NaN lane only if both are NaN
+            pub fn max(self, other: Self) -> Self {
+                Self(imp::$max(self.0, other.0))
+            }
+
+            /// lanewise minimum, NaN if either is NaN
+            pub fn min_nan(self, other: Self) -> Self {
+                Self(imp::$min_nan(self.0, other.0))
+            }
+
+            /// lanewise maximum, NaN if either is NaN
+            pub fn max_nan(self, other: Self) -> Self {
+                Self(imp::$max_nan(self.0, other.0))
+            }
+
+            pub fn abs(self) -> Self {
+                Self(self.0 & 0x7fff_7fff)
+            }
+
+            /// atomically adds `value` to `*ptr` and returns the previous value, `atom.add.noftz`
+            ///
+            /// # Safety
+            /// `ptr` must be valid and aligned for atomic reads and writes
+            pub unsafe fn atom_add(ptr: *mut Self, value: Self) -> Self {
+                Self(unsafe { imp::$atom(ptr as *mut u32, value.0) })
+            }
+
+            /// atomically adds `value` to `*ptr`, `red.add.noftz`
+            ///
+            /// # Safety
+            /// `ptr` must be valid and aligned for atomic reads and writes
+            pub unsafe fn red_add(ptr: *mut Self, value: Self) {
+                unsafe { imp::$red(ptr as *mut u32, value.0) }
+            }
+        }
+
+        impl From<($F, $F)> for $F2 {
+            fn from((lo, hi): ($F, $F)) -> Self {
+                Self::new(lo, hi)
+            }
+        }
+
+        impl From<$F2> for ($F, $F) {
+            fn from(value: $F2) -> Self {
+                (value.lo(), value.hi())
+            }
+        }
+
+        impl fmt::Debug for $F2 {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($F2)).field(&self.lo()).field(&self.hi()).finish()
+            }
+        }
+
+        /// both lanes compare equal
+        impl PartialEq for $F2 {
+            fn eq(&self, other: &Self) -> bool {
+                self.lo() == other.lo() && self.hi() == other.hi()
+            }
+        }
+
+        impl Neg for $F2 {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(self.0 ^ 0x8000_8000)
+            }
+        }
+
+        impl Add for $F2 {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(imp::$add(self.0, rhs.0))
+            }
+        }
+
+        impl Sub for $F2 {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(imp::$sub(self.0, rhs.0))
+            }
+        }
+
+        impl Mul for $F2 {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                Self(imp::$mul(self.0, rhs.0))
+            }
+        }
+
+        impl AddAssign for $F2 {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = (*self).add(rhs);
+            }
+        }
+
+        impl SubAssign for $F2 {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = (*self).sub(rhs);
+            }
+        }
+
+        impl MulAssign for $F2 {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = (*self).mul(rhs);
+            }
+        }
+    };
+}
+
+packed_half!(
+    /// two f16 lanes, CUDA's __half2
+    f16x2, Half, f16x2_add, f16x2_sub, f16x2_mul, f16x2_fma, f16x2_min, f16x2_max, f16x2_min_nan,
+    f16x2_max_nan, f32_to_f16x2, f16x2_atom_add, f16x2_red_add
+);
+packed_half!(
+    /// two bf16 lanes, CUDA's __nv_bfloat162
+    bf16x2, BHalf, bf16x2_add, bf16x2_sub, bf16x2_mul, bf16x2_fma, bf16x2_min, bf16x2_max,
+    bf16x2_min_nan, bf16x2_max_nan, f32_to_bf16x2, bf16x2_atom_add, bf16x2_red_add
+);
+
+impl f16x2 {
+    /// `ex2.approx.f16x2`
+    pub fn exp2_approx(self) -> Self {
+        Self(imp::f16x2_exp2_approx(self.0))
+    }
+
+    /// `tanh.approx.f16x2`
+    pub fn tanh_approx(self) -> Self {
+        Self(imp::f16x2_tanh_approx(self.0))
+    }
+}
@@ -0,0 +1,143 @@
+/*
+The digits & ranks of `BlockRadixSort`, which need no device, so they can be checked on the host.
+Each pass ranks `RADIX_BITS` of the keys' order-preserving radix: a thread counts the digits of its
+items, numbering equal digits in item order, the counters of every thread are scanned in digit-major
+order, and a key's rank is its digit's offset for its thread plus its number, which keeps the sort
+stable. Per-thread counters rather than match_any & ballot: the digit-major scan over the whole block
+is needed anyway for the offsets across warps, and it ranks a thread's items in the same pass.
+*/
+
+/// number of key bits ranked per pass
+pub const RADIX_BITS: u32 = 4;
+pub(crate) const RADIX_DIGITS: usize = 1 << RADIX_BITS;
+
+/// Keys with an order-preserving mapping onto unsigned integers
+pub trait RadixKey: Copy {
+    const BITS: u32;
+    /// unsigned image of the key whose unsigned order matches the key order
+    fn to_radix(self) -> u64;
+    fn from_radix(radix: u64) -> Self;
+}
+
+impl RadixKey for u32 {
+    const BITS: u32 = 32;
+
+    fn to_radix(self) -> u64 {
+        self as u64
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        radix as u32
+    }
+}
+
+impl RadixKey for u64 {
+    const BITS: u32 = 64;
+
+    fn to_radix(self) -> u64 {
+        self
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        radix
+    }
+}
+
+/// flips the sign bit so negative values order below positive ones
+impl RadixKey for i32 {
+    const BITS: u32 = 32;
+
+    fn to_radix(self) -> u64 {
+        (self as u32 ^ 0x8000_0000) as u64
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        (radix as u32 ^ 0x8000_0000) as i32
+    }
+}
+
+impl RadixKey for i64 {
+    const BITS: u32 = 64;
+
+    fn to_radix(self) -> u64 {
+        self as u64 ^ 0x8000_0000_0000_0000
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        (radix ^ 0x8000_0000_0000_0000) as i64
+    }
+}
+
+/// flips every bit of negative values and only the sign bit of positive ones,
+/// so `-0.0` orders below `0.0` and NaNs order by their sign to either end
+impl RadixKey for f32 {
+    const BITS: u32 = 32;
+
+    fn to_radix(self) -> u64 {
+        let bits = self.to_bits();
+        let flipped = if bits & 0x8000_0000 != 0 {
+            !bits
+        } else {
+            bits | 0x8000_0000
+        };
+        flipped as u64
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        let radix = radix as u32;
+        f32::from_bits(if radix & 0x8000_0000 != 0 {
+            radix & !0x8000_0000
+        } else {
+            !radix
+        })
+    }
+}
+
+impl RadixKey for f64 {
+    const BITS: u32 = 64;
+
+    fn to_radix(self) -> u64 {
+        let bits = self.to_bits();
+        if bits & 0x8000_0000_0000_0000 != 0 {
+            !bits
+        } else {
+            bits | 0x8000_0000_0000_0000
+        }
+    }
+
+    fn from_radix(radix: u64) -> Self {
+        f64::from_bits(if radix & 0x8000_0000_0000_0000 != 0 {
+            radix & !0x8000_0000_0000_0000
+        } else {
+            !radix
+        })
+    }
+}
+
+/// the `(bit, pass_bits)` of each pass over the radix bits `begin_bit..end_bit`
+pub fn radix_passes(begin_bit: u32, end_bit: u32) -> impl Iterator<Item = (u32, u32)> {
+    let mut bit = begin_bit;
+    core::iter::from_fn(move || {
+        let pass_bits = (end_bit - bit).min(RADIX_BITS);
+        let pass = (bit, pass_bits);
+        bit += pass_bits;
+        (pass_bits > 0).then_some(pass)
+    })
+}
+
+/// the digit of `key` in the pass over its radix bits `bit..bit + pass_bits`
+pub fn radix_digit<K: RadixKey>(key: K, bit: u32, pass_bits: u32) -> usize {
+    ((key.to_radix() >> bit) & ((1u64 << pass_bits) - 1)) as usize
+}
+
+/// counts a thread's digits into `counters`, which start at 0, and returns each item's number among
+/// the thread's items of its digit
+pub fn radix_thread_ranks<const ITEMS: usize>(
+    digits: &[usize; ITEMS],
+    counters: &mut [u32; RADIX_DIGITS],
+) -> [u32; ITEMS] {
+    digits.map(|d| {
+        counters[d] += 1;
+        counters[d] - 1
+    })
+}
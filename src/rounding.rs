@@ -0,0 +1,639 @@
+use core::ops::*;
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#floating-point-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#rounding-modifiers
+
+IEEE 754 arithmetic with the rounding mode chosen per instruction: `.rn` to nearest even, `.rz` toward zero,
+`.rm` toward negative infinity and `.rp` toward positive infinity. Every result is the exact result rounded
+once in that mode, so rounding down and up brackets it, as interval arithmetic needs, and sums are
+reproducible regardless of how the compiler would otherwise contract or reorder them.
+The f32 instructions can also flush subnormal inputs and results to a zero of the same sign, `.ftz`, there
+is no f64 flavour of it. There is no sub instruction with a rounding mode, negation is exact so it adds -rhs.
+The host rounds to nearest and moves the result one step when the exact result is past it in the mode's
+direction, which side it is on comes from the sign of the exactly summed residual, e.g. `a * b - nearest`.
+*/
+
+/// A rounding mode of the IEEE instructions
+pub trait RoundingMode: Copy + Default {}
+
+/// round to nearest, ties to even, `.rn`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rn;
+/// round toward zero, `.rz`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rz;
+/// round toward negative infinity, `.rm`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rm;
+/// round toward positive infinity, `.rp`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rp;
+/// rounds with `M`, flushing subnormal inputs and results to zero, `.ftz`, only for f32
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ftz<M>(pub M);
+
+impl RoundingMode for Rn {}
+impl RoundingMode for Rz {}
+impl RoundingMode for Rm {}
+impl RoundingMode for Rp {}
+impl<M: RoundingMode> RoundingMode for Ftz<M> {}
+
+/// Arithmetic rounded once with the rounding mode `M`,
+/// e.g. `RoundedArith::<Rm>::rounded_add(a, b)` is a lower bound on `a + b`
+pub trait RoundedArith<M: RoundingMode>: Copy + Neg<Output = Self> {
+    fn rounded_add(self, rhs: Self) -> Self;
+    fn rounded_sub(self, rhs: Self) -> Self {
+        self.rounded_add(-rhs)
+    }
+    fn rounded_mul(self, rhs: Self) -> Self;
+    fn rounded_div(self, rhs: Self) -> Self;
+    fn rounded_sqrt(self) -> Self;
+    /// self * b + c
+    fn rounded_fma(self, b: Self, c: Self) -> Self;
+    fn rounded_recip(self) -> Self;
+}
+
+#[cfg(target_arch = "nvptx64")]
+macro_rules! rounded_arith {
+    ($($F:ty, $M:ty => $suffix:literal);+ $(;)?) => {
+        $(impl RoundedArith<$M> for $F {
+            fn rounded_add(self, rhs: Self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.add.", $suffix)]
+                    fn add(a: $F, b: $F) -> $F;
+                }
+                unsafe { add(self, rhs) }
+            }
+
+            fn rounded_mul(self, rhs: Self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.mul.", $suffix)]
+                    fn mul(a: $F, b: $F) -> $F;
+                }
+                unsafe { mul(self, rhs) }
+            }
+
+            fn rounded_div(self, rhs: Self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.div.", $suffix)]
+                    fn div(a: $F, b: $F) -> $F;
+                }
+                unsafe { div(self, rhs) }
+            }
+
+            fn rounded_sqrt(self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.sqrt.", $suffix)]
+                    fn sqrt(a: $F) -> $F;
+                }
+                unsafe { sqrt(self) }
+            }
+
+            fn rounded_fma(self, b: Self, c: Self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.fma.", $suffix)]
+                    fn fma(a: $F, b: $F, c: $F) -> $F;
+                }
+                unsafe { fma(self, b, c) }
+            }
+
+            fn rounded_recip(self) -> Self {
+                extern "C" {
+                    #[ffi_const]
+                    #[link_name = concat!("llvm.nvvm.rcp.", $suffix)]
+                    fn rcp(a: $F) -> $F;
+                }
+                unsafe { rcp(self) }
+            }
+        })+
+    };
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+macro_rules! rounded_arith {
+    ($($F:ty, $M:ty => $suffix:literal);+ $(;)?) => {
+        $(impl RoundedArith<$M> for $F {
+            fn rounded_add(self, rhs: Self) -> Self {
+                soft::add::<$M, $F>(self, rhs)
+            }
+
+            fn rounded_mul(self, rhs: Self) -> Self {
+                soft::mul::<$M, $F>(self, rhs)
+            }
+
+            fn rounded_div(self, rhs: Self) -> Self {
+                soft::div::<$M, $F>(self, rhs)
+            }
+
+            fn rounded_sqrt(self) -> Self {
+                soft::sqrt::<$M, $F>(self)
+            }
+
+            fn rounded_fma(self, b: Self, c: Self) -> Self {
+                soft::fma::<$M, $F>(self, b, c)
+            }
+
+            fn rounded_recip(self) -> Self {
+                soft::div::<$M, $F>(1.0, self)
+            }
+        })+
+    };
+}
+
+rounded_arith! {
+    f32, Rn => "rn.f";
+    f32, Rz => "rz.f";
+    f32, Rm => "rm.f";
+    f32, Rp => "rp.f";
+    f32, Ftz<Rn> => "rn.ftz.f";
+    f32, Ftz<Rz> => "rz.ftz.f";
+    f32, Ftz<Rm> => "rm.ftz.f";
+    f32, Ftz<Rp> => "rp.ftz.f";
+    f64, Rn => "rn.d";
+    f64, Rz => "rz.d";
+    f64, Rm => "rm.d";
+    f64, Rp => "rp.d";
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    use super::{Ftz, Rm, Rn, Rp, Rz};
+    use core::cmp::Ordering::{self, *};
+    use core::ops::{Add, Div, Mul, Neg};
+
+    /// How a mode rounds, from the nearest result and the side of it the exact result is on
+    pub trait Mode {
+        /// flushes subnormal inputs and results, `.ftz`
+        const FTZ: bool = false;
+        /// rounds toward negative infinity, where an exact zero sum is -0
+        const DOWN: bool = false;
+        fn round<F: Host>(nearest: F, exact: Ordering) -> F;
+    }
+
+    impl Mode for Rn {
+        fn round<F: Host>(nearest: F, _: Ordering) -> F {
+            nearest
+        }
+    }
+
+    impl Mode for Rz {
+        fn round<F: Host>(nearest: F, exact: Ordering) -> F {
+            match exact {
+                Less if nearest.is_sign_positive() => nearest.next_down(),
+                Greater if !nearest.is_sign_positive() => nearest.next_up(),
+                _ => nearest,
+            }
+        }
+    }
+
+    impl Mode for Rm {
+        const DOWN: bool = true;
+
+        fn round<F: Host>(nearest: F, exact: Ordering) -> F {
+            if exact == Less {
+                nearest.next_down()
+            } else {
+                nearest
+            }
+        }
+    }
+
+    impl Mode for Rp {
+        fn round<F: Host>(nearest: F, exact: Ordering) -> F {
+            if exact == Greater {
+                nearest.next_up()
+            } else {
+                nearest
+            }
+        }
+    }
+
+    impl<M: Mode> Mode for Ftz<M> {
+        const FTZ: bool = true;
+        const DOWN: bool = M::DOWN;
+
+        fn round<F: Host>(nearest: F, exact: Ordering) -> F {
+            M::round(nearest, exact)
+        }
+    }
+
+    /// The float's operations rounded to nearest, and the signs of their exact residuals for finite operands
+    pub trait Host:
+        Copy
+        + PartialOrd
+        + Add<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + Neg<Output = Self>
+    {
+        const ZERO: Self;
+        fn is_nan(self) -> bool;
+        fn is_finite(self) -> bool;
+        fn is_sign_positive(self) -> bool;
+        fn next_up(self) -> Self;
+        fn next_down(self) -> Self;
+        fn flush(self) -> Self;
+        fn sqrt(self) -> Self;
+        fn fma(self, b: Self, c: Self) -> Self;
+        /// a + b - r
+        fn add_residual(a: Self, b: Self, r: Self) -> Ordering;
+        /// a * b - r
+        fn mul_residual(a: Self, b: Self, r: Self) -> Ordering;
+        /// a - q * b
+        fn div_residual(a: Self, b: Self, q: Self) -> Ordering;
+        /// x - r * r
+        fn sqrt_residual(x: Self, r: Self) -> Ordering;
+        /// a * b + c - r
+        fn fma_residual(a: Self, b: Self, c: Self, r: Self) -> Ordering;
+    }
+
+    /// (a + b, its rounding error)
+    fn two_sum(a: f64, b: f64) -> (f64, f64) {
+        let s = a + b;
+        let bb = s - a;
+        (s, (a - (s - bb)) + (b - bb))
+    }
+
+    /// the sign of the exact sum, Shewchuk's grow-expansion keeps it as nonoverlapping components of
+    /// increasing magnitude, so the largest nonzero one has its sign
+    fn sum_sign(terms: &[f64]) -> Ordering {
+        let mut expansion = [0.0; 4];
+        for (len, &term) in terms.iter().enumerate() {
+            let mut sum = term;
+            for component in &mut expansion[..len] {
+                (sum, *component) = two_sum(sum, *component);
+            }
+            expansion[len] = sum;
+        }
+        expansion[..terms.len()]
+            .iter()
+            .rev()
+            .find(|&&component| component != 0.0)
+            .map_or(Equal, |&component| component.partial_cmp(&0.0).unwrap())
+    }
+
+    // f32 products are exact in f64, and so are the sums' signs
+    impl Host for f32 {
+        const ZERO: Self = 0.0;
+
+        fn is_nan(self) -> bool {
+            f32::is_nan(self)
+        }
+
+        fn is_finite(self) -> bool {
+            f32::is_finite(self)
+        }
+
+        fn is_sign_positive(self) -> bool {
+            f32::is_sign_positive(self)
+        }
+
+        fn next_up(self) -> Self {
+            f32::next_up(self)
+        }
+
+        fn next_down(self) -> Self {
+            f32::next_down(self)
+        }
+
+        fn flush(self) -> Self {
+            if self.is_subnormal() {
+                0.0f32.copysign(self)
+            } else {
+                self
+            }
+        }
+
+        fn sqrt(self) -> Self {
+            libm::sqrtf(self)
+        }
+
+        fn fma(self, b: Self, c: Self) -> Self {
+            libm::fmaf(self, b, c)
+        }
+
+        fn add_residual(a: Self, b: Self, r: Self) -> Ordering {
+            sum_sign(&[a as f64, b as f64, -r as f64])
+        }
+
+        fn mul_residual(a: Self, b: Self, r: Self) -> Ordering {
+            sum_sign(&[a as f64 * b as f64, -r as f64])
+        }
+
+        fn div_residual(a: Self, b: Self, q: Self) -> Ordering {
+            sum_sign(&[a as f64, -(q as f64 * b as f64)])
+        }
+
+        fn sqrt_residual(x: Self, r: Self) -> Ordering {
+            sum_sign(&[x as f64, -(r as f64 * r as f64)])
+        }
+
+        fn fma_residual(a: Self, b: Self, c: Self, r: Self) -> Ordering {
+            sum_sign(&[a as f64 * b as f64, c as f64, -r as f64])
+        }
+    }
+
+    /// 2^-900, below it scaling may round
+    const TINY: f64 = 1.1830521861667747e-271;
+
+    /// `x 2^n`, or where that is below `TINY`, a stand-in of the same sign too small to matter next to the
+    /// terms above it
+    fn scaled(x: f64, n: i32) -> f64 {
+        let y = libm::scalbn(x, n);
+        if x != 0.0 && y.abs() < TINY {
+            // 2^-960
+            1.0261342003245941e-289f64.copysign(x)
+        } else {
+            y
+        }
+    }
+
+    /// (n, p, e) with a * b = (p + e) 2^n exactly, 1/4 <= |p| < 1
+    fn product(a: f64, b: f64) -> (i32, f64, f64) {
+        let ((a, m), (b, n)) = (libm::frexp(a), libm::frexp(b));
+        let p = a * b;
+        (m + n, p, libm::fma(a, b, -p))
+    }
+
+    // the residuals are scaled so neither the products nor their errors underflow
+    impl Host for f64 {
+        const ZERO: Self = 0.0;
+
+        fn is_nan(self) -> bool {
+            f64::is_nan(self)
+        }
+
+        fn is_finite(self) -> bool {
+            f64::is_finite(self)
+        }
+
+        fn is_sign_positive(self) -> bool {
+            f64::is_sign_positive(self)
+        }
+
+        fn next_up(self) -> Self {
+            f64::next_up(self)
+        }
+
+        fn next_down(self) -> Self {
+            f64::next_down(self)
+        }
+
+        fn flush(self) -> Self {
+            self
+        }
+
+        fn sqrt(self) -> Self {
+            libm::sqrt(self)
+        }
+
+        fn fma(self, b: Self, c: Self) -> Self {
+            libm::fma(self, b, c)
+        }
+
+        fn add_residual(a: Self, b: Self, r: Self) -> Ordering {
+            sum_sign(&[a, b, -r])
+        }
+
+        fn mul_residual(a: Self, b: Self, r: Self) -> Ordering {
+            let (n, p, e) = product(a, b);
+            sum_sign(&[p, e, -libm::scalbn(r, -n)])
+        }
+
+        fn div_residual(a: Self, b: Self, q: Self) -> Ordering {
+            let ((a, m), (b, n)) = (libm::frexp(a), libm::frexp(b));
+            let q = libm::scalbn(q, n - m);
+            let p = q * b;
+            sum_sign(&[a, -p, -libm::fma(q, b, -p)])
+        }
+
+        fn sqrt_residual(x: Self, r: Self) -> Ordering {
+            // an even exponent
+            let (x, n) = match libm::frexp(x) {
+                (x, n) if n % 2 != 0 => (x * 2.0, n - 1),
+                x => x,
+            };
+            let r = libm::scalbn(r, -n / 2);
+            let p = r * r;
+            sum_sign(&[x, -p, -libm::fma(r, r, -p)])
+        }
+
+        fn fma_residual(a: Self, b: Self, c: Self, r: Self) -> Ordering {
+            let (n, p, e) = product(a, b);
+            let (_, m) = libm::frexp(c);
+            let k = match (p != 0.0, c != 0.0) {
+                (true, true) => n.max(m),
+                (false, _) => m,
+                (true, false) => n,
+            };
+            let p_k = scaled(p, n - k);
+            // the error is below the stand-in's precision
+            let e_k = if p_k.abs() < TINY {
+                0.0
+            } else {
+                scaled(e, n - k)
+            };
+            sum_sign(&[p_k, e_k, scaled(c, -k), -libm::scalbn(r, -k)])
+        }
+    }
+
+    fn input<M: Mode, F: Host>(x: F) -> F {
+        if M::FTZ {
+            x.flush()
+        } else {
+            x
+        }
+    }
+
+    /// which side of `nearest` the exact result is on, for operands that are `finite` it is past an
+    /// infinite `nearest`, which overflowed
+    fn side<F: Host>(nearest: F, finite: bool, residual: impl FnOnce() -> Ordering) -> Ordering {
+        if !finite || nearest.is_nan() {
+            Equal
+        } else if nearest.is_finite() {
+            residual()
+        } else if nearest.is_sign_positive() {
+            Less
+        } else {
+            Greater
+        }
+    }
+
+    fn finish<M: Mode, F: Host>(nearest: F, exact: Ordering) -> F {
+        let rounded = M::round(nearest, exact);
+        if M::FTZ {
+            rounded.flush()
+        } else {
+            rounded
+        }
+    }
+
+    /// an exact zero sum of `a` and `b` is -0 rounding down, unless both are +0
+    fn zero_sum<M: Mode, F: Host>(
+        nearest: F,
+        exact: Ordering,
+        a_positive: bool,
+        b_positive: bool,
+    ) -> F {
+        if M::DOWN && nearest == F::ZERO && exact == Equal && !(a_positive && b_positive) {
+            -F::ZERO
+        } else {
+            nearest
+        }
+    }
+
+    pub fn add<M: Mode, F: Host>(a: F, b: F) -> F {
+        let (a, b) = (input::<M, F>(a), input::<M, F>(b));
+        let nearest = a + b;
+        let exact = side(nearest, a.is_finite() && b.is_finite(), || {
+            F::add_residual(a, b, nearest)
+        });
+        let nearest = zero_sum::<M, F>(nearest, exact, a.is_sign_positive(), b.is_sign_positive());
+        finish::<M, F>(nearest, exact)
+    }
+
+    pub fn mul<M: Mode, F: Host>(a: F, b: F) -> F {
+        let (a, b) = (input::<M, F>(a), input::<M, F>(b));
+        let nearest = a * b;
+        let exact = side(nearest, a.is_finite() && b.is_finite(), || {
+            F::mul_residual(a, b, nearest)
+        });
+        finish::<M, F>(nearest, exact)
+    }
+
+    pub fn div<M: Mode, F: Host>(a: F, b: F) -> F {
+        let (a, b) = (input::<M, F>(a), input::<M, F>(b));
+        let nearest = a / b;
+        // the quotient is past `nearest` on the residual's side times b's sign
+        let exact = side(
+            nearest,
+            a.is_finite() && b.is_finite() && b != F::ZERO,
+            || match F::div_residual(a, b, nearest) {
+                residual if b.is_sign_positive() => residual,
+                residual => residual.reverse(),
+            },
+        );
+        finish::<M, F>(nearest, exact)
+    }
+
+    pub fn sqrt<M: Mode, F: Host>(x: F) -> F {
+        let x = input::<M, F>(x);
+        let nearest = x.sqrt();
+        let exact = side(nearest, x.is_finite(), || F::sqrt_residual(x, nearest));
+        finish::<M, F>(nearest, exact)
+    }
+
+    pub fn fma<M: Mode, F: Host>(a: F, b: F, c: F) -> F {
+        let (a, b, c) = (input::<M, F>(a), input::<M, F>(b), input::<M, F>(c));
+        let nearest = a.fma(b, c);
+        let exact = side(
+            nearest,
+            a.is_finite() && b.is_finite() && c.is_finite(),
+            || F::fma_residual(a, b, c, nearest),
+        );
+        let product_positive = a.is_sign_positive() == b.is_sign_positive();
+        let nearest = zero_sum::<M, F>(nearest, exact, product_positive, c.is_sign_positive());
+        finish::<M, F>(nearest, exact)
+    }
+}
+
+/// A float whose operators round with the mode `M`, e.g. `Rounded(a, Rp) * Rounded(b, Rp)` is an upper
+/// bound on `a * b`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Rounded<F, M = Rn>(pub F, pub M);
+
+impl<F: RoundedArith<M>, M: RoundingMode> Rounded<F, M> {
+    pub fn new(value: F) -> Self {
+        Self(value, M::default())
+    }
+
+    pub fn sqrt(self) -> Self {
+        Self::new(self.0.rounded_sqrt())
+    }
+
+    /// self * a + b
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        Self::new(self.0.rounded_fma(a.0, b.0))
+    }
+
+    pub fn recip(self) -> Self {
+        Self::new(self.0.rounded_recip())
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> From<F> for Rounded<F, M> {
+    fn from(value: F) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> Neg for Rounded<F, M> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.0)
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> Add for Rounded<F, M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.0.rounded_add(rhs.0))
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> AddAssign for Rounded<F, M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = (*self).add(rhs);
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> Sub for Rounded<F, M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.0.rounded_sub(rhs.0))
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> SubAssign for Rounded<F, M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = (*self).sub(rhs);
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> Mul for Rounded<F, M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(self.0.rounded_mul(rhs.0))
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> MulAssign for Rounded<F, M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = (*self).mul(rhs);
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> Div for Rounded<F, M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::new(self.0.rounded_div(rhs.0))
+    }
+}
+
+impl<F: RoundedArith<M>, M: RoundingMode> DivAssign for Rounded<F, M> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = (*self).div(rhs);
+    }
+}
@@ -0,0 +1,124 @@
+/*
+The thread and warp steps of `BlockScan`, which need no device, so they can be checked on the host.
+A thread scans its items, the warp scans the threads' reductions, and `compose_scan` seeds each warp
+with the aggregates of the warps before it.
+*/
+
+/// Running-prefix callback for scanning a sequence of tiles with one block.
+///
+/// Only thread 0 invokes the callback, so its state lives in thread 0's registers.
+pub struct RunningPrefix<T, F> {
+    running: T,
+    op: F,
+}
+
+impl<T: Copy, F: Fn(T, T) -> T> RunningPrefix<T, F> {
+    pub fn new(initial: T, op: F) -> Self {
+        Self {
+            running: initial,
+            op,
+        }
+    }
+
+    /// returns the prefix of the current tile and folds its aggregate into the running prefix
+    pub fn next(&mut self, aggregate: T) -> T {
+        let prefix = self.running;
+        self.running = (self.op)(prefix, aggregate);
+        prefix
+    }
+
+    pub fn running(&self) -> T {
+        self.running
+    }
+
+    /// borrows the callback as a closure suitable for the `*_with_prefix` scans
+    pub fn callback(&mut self) -> impl FnMut(T) -> T + '_ {
+        move |aggregate| self.next(aggregate)
+    }
+}
+
+/// Combines a thread's scan within its warp with the aggregates of the warps before it.
+///
+/// `inclusive` is the thread's inclusive prefix within warp `warp`, `warp_exclusive` the one of the lane
+/// before it, and `aggregate(w)` the aggregate of warp `w` of `warps`. Returns the thread's inclusive prefix,
+/// its exclusive prefix (`None` for thread 0) and the aggregate of the block.
+pub fn compose_scan<T: Copy, F: Fn(T, T) -> T>(
+    warp: u32,
+    lane: u32,
+    inclusive: T,
+    warp_exclusive: T,
+    warps: u32,
+    aggregate: impl Fn(u32) -> T,
+    op: &F,
+) -> (T, Option<T>, T) {
+    let mut block_aggregate = aggregate(0);
+    let mut warp_prefix = None;
+    for w in 1..warps {
+        if w == warp {
+            warp_prefix = Some(block_aggregate);
+        }
+        block_aggregate = op(block_aggregate, aggregate(w));
+    }
+
+    match warp_prefix {
+        None if lane == 0 => (inclusive, None, block_aggregate),
+        None => (inclusive, Some(warp_exclusive), block_aggregate),
+        Some(prefix) if lane == 0 => (op(prefix, inclusive), Some(prefix), block_aggregate),
+        Some(prefix) => (
+            op(prefix, inclusive),
+            Some(op(prefix, warp_exclusive)),
+            block_aggregate,
+        ),
+    }
+}
+
+/// `prefix` folded in front of an exclusive prefix from `compose_scan`
+pub fn seed_prefix<T: Copy, F: Fn(T, T) -> T>(prefix: T, exclusive: Option<T>, op: &F) -> T {
+    match exclusive {
+        None => prefix,
+        Some(exclusive) => op(prefix, exclusive),
+    }
+}
+
+/// reduces a thread's items in order, `ITEMS` must not be 0
+pub fn thread_reduce<T: Copy, F: Fn(T, T) -> T, const ITEMS: usize>(
+    items: &[T; ITEMS],
+    op: &F,
+) -> T {
+    const { assert!(ITEMS > 0, "a thread scans at least one item") };
+    items[1..].iter().fold(items[0], |acc, &item| op(acc, item))
+}
+
+/// inclusive scan of a thread's items, seeded by the exclusive prefix of the threads before it
+pub fn thread_inclusive_scan<T: Copy, F: Fn(T, T) -> T, const ITEMS: usize>(
+    mut items: [T; ITEMS],
+    prefix: Option<T>,
+    op: &F,
+) -> [T; ITEMS] {
+    const { assert!(ITEMS > 0, "a thread scans at least one item") };
+    let mut acc = match prefix {
+        None => items[0],
+        Some(prefix) => op(prefix, items[0]),
+    };
+    items[0] = acc;
+    for item in items[1..].iter_mut() {
+        acc = op(acc, *item);
+        *item = acc;
+    }
+    items
+}
+
+/// exclusive scan of a thread's items, seeded by the prefix of the threads before it
+pub fn thread_exclusive_scan<T: Copy, F: Fn(T, T) -> T, const ITEMS: usize>(
+    mut items: [T; ITEMS],
+    prefix: T,
+    op: &F,
+) -> [T; ITEMS] {
+    let mut acc = prefix;
+    for item in items.iter_mut() {
+        let next = op(acc, *item);
+        *item = acc;
+        acc = next;
+    }
+    items
+}
@@ -1,4 +1,4 @@
-use crate::FastFloat;
+use crate::{bf16x2, f16x2, BHalf, FastComplex, FastFloat, Half};
 
 extern "C" {
     #[ffi_const]
@@ -55,47 +55,19 @@ impl Shuffle for u32 {
 
 impl Shuffle for i32 {
     fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
-        unsafe {
-            core::mem::transmute::<u32, i32>(shfl_idx_sync_i32(
-                mask,
-                core::mem::transmute::<i32, u32>(self),
-                src_lane,
-                0x1f,
-            ))
-        }
+        (self as u32).shfl_idx(mask, src_lane) as i32
     }
 
     fn shfl_down(self, mask: u32, delta: u32) -> Self {
-        unsafe {
-            core::mem::transmute::<u32, i32>(shfl_idx_sync_i32(
-                mask,
-                core::mem::transmute::<i32, u32>(self),
-                delta,
-                0x1f,
-            ))
-        }
+        (self as u32).shfl_down(mask, delta) as i32
     }
 
     fn shfl_up(self, mask: u32, delta: u32) -> Self {
-        unsafe {
-            core::mem::transmute::<u32, i32>(shfl_idx_sync_i32(
-                mask,
-                core::mem::transmute::<i32, u32>(self),
-                delta,
-                0,
-            ))
-        }
+        (self as u32).shfl_up(mask, delta) as i32
     }
 
     fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
-        unsafe {
-            core::mem::transmute::<u32, i32>(shfl_idx_sync_i32(
-                mask,
-                core::mem::transmute::<i32, u32>(self),
-                lane_mask,
-                0x1f,
-            ))
-        }
+        (self as u32).shfl_bfly(mask, lane_mask) as i32
     }
 }
 
@@ -117,20 +89,181 @@ impl Shuffle for f32 {
     }
 }
 
-impl<F: Shuffle> Shuffle for FastFloat<F> {
+impl Shuffle for u64 {
     fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
-        FastFloat(self.0.shfl_idx(mask, src_lane))
+        let lo = (self as u32).shfl_idx(mask, src_lane);
+        let hi = ((self >> 32) as u32).shfl_idx(mask, src_lane);
+        ((hi as u64) << 32) | (lo as u64)
     }
 
     fn shfl_down(self, mask: u32, delta: u32) -> Self {
-        FastFloat(self.0.shfl_down(mask, delta))
+        let lo = (self as u32).shfl_down(mask, delta);
+        let hi = ((self >> 32) as u32).shfl_down(mask, delta);
+        ((hi as u64) << 32) | (lo as u64)
     }
 
     fn shfl_up(self, mask: u32, delta: u32) -> Self {
-        FastFloat(self.0.shfl_up(mask, delta))
+        let lo = (self as u32).shfl_up(mask, delta);
+        let hi = ((self >> 32) as u32).shfl_up(mask, delta);
+        ((hi as u64) << 32) | (lo as u64)
     }
 
     fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
-        FastFloat(self.0.shfl_bfly(mask, lane_mask))
+        let lo = (self as u32).shfl_bfly(mask, lane_mask);
+        let hi = ((self >> 32) as u32).shfl_bfly(mask, lane_mask);
+        ((hi as u64) << 32) | (lo as u64)
+    }
+}
+
+impl Shuffle for i64 {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        (self as u64).shfl_idx(mask, src_lane) as i64
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        (self as u64).shfl_down(mask, delta) as i64
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        (self as u64).shfl_up(mask, delta) as i64
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        (self as u64).shfl_bfly(mask, lane_mask) as i64
+    }
+}
+
+impl Shuffle for f64 {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        f64::from_bits(self.to_bits().shfl_idx(mask, src_lane))
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        f64::from_bits(self.to_bits().shfl_down(mask, delta))
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        f64::from_bits(self.to_bits().shfl_up(mask, delta))
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        f64::from_bits(self.to_bits().shfl_bfly(mask, lane_mask))
+    }
+}
+
+impl Shuffle for Half {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        Half::from_bits((self.to_bits() as u32).shfl_idx(mask, src_lane) as u16)
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        Half::from_bits((self.to_bits() as u32).shfl_down(mask, delta) as u16)
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        Half::from_bits((self.to_bits() as u32).shfl_up(mask, delta) as u16)
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        Half::from_bits((self.to_bits() as u32).shfl_bfly(mask, lane_mask) as u16)
+    }
+}
+
+impl Shuffle for BHalf {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        BHalf::from_bits((self.to_bits() as u32).shfl_idx(mask, src_lane) as u16)
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        BHalf::from_bits((self.to_bits() as u32).shfl_down(mask, delta) as u16)
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        BHalf::from_bits((self.to_bits() as u32).shfl_up(mask, delta) as u16)
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        BHalf::from_bits((self.to_bits() as u32).shfl_bfly(mask, lane_mask) as u16)
+    }
+}
+
+impl Shuffle for f16x2 {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        f16x2::from_bits(self.to_bits().shfl_idx(mask, src_lane))
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        f16x2::from_bits(self.to_bits().shfl_down(mask, delta))
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        f16x2::from_bits(self.to_bits().shfl_up(mask, delta))
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        f16x2::from_bits(self.to_bits().shfl_bfly(mask, lane_mask))
+    }
+}
+
+impl Shuffle for bf16x2 {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        bf16x2::from_bits(self.to_bits().shfl_idx(mask, src_lane))
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        bf16x2::from_bits(self.to_bits().shfl_down(mask, delta))
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        bf16x2::from_bits(self.to_bits().shfl_up(mask, delta))
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        bf16x2::from_bits(self.to_bits().shfl_bfly(mask, lane_mask))
+    }
+}
+
+impl<F: Shuffle, P> Shuffle for FastFloat<F, P> {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        FastFloat::new(self.into_inner().shfl_idx(mask, src_lane))
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        FastFloat::new(self.into_inner().shfl_down(mask, delta))
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        FastFloat::new(self.into_inner().shfl_up(mask, delta))
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        FastFloat::new(self.into_inner().shfl_bfly(mask, lane_mask))
+    }
+}
+
+impl<F: Shuffle> Shuffle for FastComplex<F> {
+    fn shfl_idx(self, mask: u32, src_lane: u32) -> Self {
+        FastComplex::new(
+            self.re.shfl_idx(mask, src_lane),
+            self.im.shfl_idx(mask, src_lane),
+        )
+    }
+
+    fn shfl_down(self, mask: u32, delta: u32) -> Self {
+        FastComplex::new(
+            self.re.shfl_down(mask, delta),
+            self.im.shfl_down(mask, delta),
+        )
+    }
+
+    fn shfl_up(self, mask: u32, delta: u32) -> Self {
+        FastComplex::new(self.re.shfl_up(mask, delta), self.im.shfl_up(mask, delta))
+    }
+
+    fn shfl_bfly(self, mask: u32, lane_mask: u32) -> Self {
+        FastComplex::new(
+            self.re.shfl_bfly(mask, lane_mask),
+            self.im.shfl_bfly(mask, lane_mask),
+        )
     }
 }
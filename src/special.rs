@@ -0,0 +1,1098 @@
+use crate::{approx_f64, FastNum};
+
+/*
+https://docs.nvidia.com/cuda/cuda-math-api/cuda_math_api/group__CUDA__MATH__SINGLE.html
+https://people.maths.ox.ac.uk/gilesm/files/gems_erfinv.pdf
+https://arxiv.org/abs/1506.03373
+
+Special functions built from FMA polynomials, fit near minimax for each format, and the `FastNum`
+primitives. erf is a polynomial in x^2 near zero, erfc is e^(-x^2) times a polynomial in
+t = (x - K) / (x + K) over (1 + 2x) further out, with the rounding error of x^2 carried into the
+exponential. erfinv & erfcinv are Giles' polynomials in w = -ln(1 - x^2), their f64 tail takes a Halley
+step. lgamma & tgamma are polynomials around 1 & 2 with the recurrence, Stirling's series and the
+reflection formula, f32 evaluates them in f64. j0 & j1 below 8 are (x - z) times a polynomial in x over
+an interval around each zero z, with z as hi + lo, y0 & y1 there are 2 / π ln(x) j(x), and - 2 / πx for y1,
+plus a polynomial in x^2. From 8 on all four are Hankel's asymptotic form, P & Q polynomials in 64 / x^2,
+with the smaller of cos(x) ± sin(x) from their product -cos(2x). f32 evaluates them in f64 too.
+
+Maximum error, in ulp between the bit patterns, from ulp/table.txt: f32 over every 65537th bit pattern,
+f64 over 2^16 pseudo random inputs, with the host models of the `FastNum` primitives at their PTX bound,
+against libm's functions, rounded to f32 for f32
+            erf  erfc  erfinv  erfcinv  normcdf  normcdfinv  lgamma  tgamma  sinpi  cospi  rcbrt
+    f32     2    3     3       4        4        4           0       0       2      2      1
+    f64     2    3     2       4        4        3           96      9       2      2      2
+            j0   j1    y0      y1
+    f32     0    0     0       0
+    f64     26   44    507     274
+lgamma is only accurate in absolute terms around its negative zeros, tgamma's results below the normal
+range of f64 round once per step of the recurrence. y0 & y1 are only accurate in absolute terms around
+their zeros, all four Bessel functions beyond 8, j0 & j1's rows are libm's error near their zeros below 8.
+f32 flushes subnormal inputs.
+*/
+
+/// Special functions, for f32 & f64
+pub trait SpecialFn: FastNum {
+    fn erf(self) -> Self;
+    fn erfc(self) -> Self;
+    /// the inverse of erf, over (-1, 1)
+    fn erfinv(self) -> Self;
+    /// the inverse of erfc, over (0, 2)
+    fn erfcinv(self) -> Self;
+    /// the ln of |Γ(self)|
+    fn lgamma(self) -> Self;
+    /// Γ(self)
+    fn tgamma(self) -> Self;
+    /// the standard normal cumulative distribution function
+    fn normcdf(self) -> Self;
+    /// the inverse of normcdf, over (0, 1)
+    fn normcdfinv(self) -> Self;
+    /// sin(self * π), without rounding self * π
+    fn sinpi(self) -> Self;
+    /// cos(self * π), without rounding self * π
+    fn cospi(self) -> Self;
+    /// (sinpi(self), cospi(self))
+    fn sincospi(self) -> (Self, Self);
+    /// 1 / cbrt(self)
+    fn rcbrt(self) -> Self;
+    /// the Bessel function of the first kind of order 0
+    fn j0(self) -> Self;
+    /// the Bessel function of the first kind of order 1
+    fn j1(self) -> Self;
+    /// the Bessel function of the second kind of order 0, over [0, inf]
+    fn y0(self) -> Self;
+    /// the Bessel function of the second kind of order 1, over [0, inf]
+    fn y1(self) -> Self;
+}
+
+/// c[0] * x^n + c[1] * x^(n - 1) + .. + c[n]
+fn poly<F: FastNum>(x: F, c: &[F]) -> F {
+    let mut p = c[0];
+    for &k in &c[1..] {
+        p = FastNum::fma(p, x, k);
+    }
+    p
+}
+
+/// the algorithms over the tables & primitives of a module with a `type F`
+macro_rules! special_fn {
+    () => {
+        /// erfc(a) e^(a^2), for a >= 0.5
+        fn erfc_scaled(a: F) -> F {
+            let t = (a - ERFC_K) * (a + ERFC_K).fast_recip();
+            poly(t, &ERFC) * FastNum::fma(2.0, a, 1.0).fast_recip()
+        }
+
+        /// erfc(a + a_lo) = e * q, for a >= 0.5
+        fn erfc_parts(a: F, a_lo: F) -> (F, F) {
+            // erfc(30) is 0 in both formats, and a_lo may be anything beyond
+            let (a, a_lo) = if a > 30.0 { (30.0, 0.0) } else { (a, a_lo) };
+            let s = -(a * a);
+            let s_lo = FastNum::fma(-a, a, -s) - 2.0 * a * a_lo;
+            (exp_tail(s, s_lo), erfc_scaled(a))
+        }
+
+        /// erfc(x + x_lo)
+        fn erfc_tail(x: F, x_lo: F) -> F {
+            if x >= 0.5 {
+                let (e, q) = erfc_parts(x, x_lo);
+                e * q
+            } else if x > -1.0 {
+                1.0 - x * poly(x * x, &ERF)
+            } else {
+                let (e, q) = erfc_parts(-x, -x_lo);
+                2.0 - e * q
+            }
+        }
+
+        /// the z with erfc(z) = y, for y in [0, 1] and x = 1 - y
+        fn erfcinv_small(y: F, x: F) -> F {
+            if y == 0.0 {
+                return F::INFINITY;
+            }
+            let w = -ln(y * (2.0 - y));
+            let p = if w < ERFINV_W[0] {
+                poly(w - ERFINV_CENTER[0], &ERFINV_A)
+            } else if w < ERFINV_W[1] {
+                poly(w.fast_sqrt() - ERFINV_CENTER[1], &ERFINV_B)
+            } else {
+                poly(w.fast_sqrt() - ERFINV_CENTER[2], &ERFINV_C)
+            };
+            let z = p * x;
+            if ERFINV_HALLEY && w >= ERFINV_W[1] {
+                // u = (erfc(z) - y) / erfc'(z) = -sqrt(π) / 2 q (1 - y / erfc(z)), with q = erfc(z) e^(z^2)
+                // and y / erfc(z) = e^(z^2 + ln(y) - ln(q)), as erfc(z) may be subnormal
+                let q = erfc_scaled(z);
+                let s = z * z;
+                let a = ln(y) - ln(q);
+                let hi = s + a;
+                let b = hi - s;
+                let lo = (s - (hi - b)) + (a - b) + FastNum::fma(z, z, -s);
+                let u = -SQRT_PI_2 * q * (1.0 - exp_tail(hi, lo));
+                z - u * FastNum::fma(z, u, 1.0).fast_recip()
+            } else {
+                z
+            }
+        }
+
+        impl SpecialFn for F {
+            fn erf(self) -> Self {
+                let a = self.fast_abs();
+                if a < 1.0 {
+                    self * poly(self * self, &ERF)
+                } else {
+                    let (e, q) = erfc_parts(a, 0.0);
+                    (1.0 - e * q).fast_copysign(self)
+                }
+            }
+
+            fn erfc(self) -> Self {
+                erfc_tail(self, 0.0)
+            }
+
+            fn erfinv(self) -> Self {
+                let a = self.fast_abs();
+                erfcinv_small(1.0 - a, a).fast_copysign(self)
+            }
+
+            fn erfcinv(self) -> Self {
+                if self > 1.0 {
+                    -erfcinv_small(2.0 - self, self - 1.0)
+                } else {
+                    erfcinv_small(self, 1.0 - self)
+                }
+            }
+
+            fn lgamma(self) -> Self {
+                lgamma(self)
+            }
+
+            fn tgamma(self) -> Self {
+                tgamma(self)
+            }
+
+            fn normcdf(self) -> Self {
+                // -self / sqrt(2) as z + z_lo, the error of z grows by -2z in erfc
+                let z = -self * FRAC_1_SQRT_2;
+                let z_lo = FastNum::fma(-self, FRAC_1_SQRT_2, -z) - self * FRAC_1_SQRT_2_LO;
+                0.5 * erfc_tail(z, z_lo)
+            }
+
+            fn normcdfinv(self) -> Self {
+                if self < 0.5 {
+                    let y = self + self;
+                    -(SQRT_2 * erfcinv_small(y, 1.0 - y))
+                } else {
+                    SQRT_2 * erfcinv_small(2.0 * (1.0 - self), self + self - 1.0)
+                }
+            }
+
+            fn sinpi(self) -> Self {
+                self.sincospi().0
+            }
+
+            fn cospi(self) -> Self {
+                self.sincospi().1
+            }
+
+            fn sincospi(self) -> (Self, Self) {
+                // self = m + 2k, m = n / 2 + r, exactly
+                let m = self - 2.0 * round_even(self * 0.5);
+                let n = round_even(m + m);
+                let r = FastNum::fma(n, -0.5, m);
+                let r2 = r * r;
+                let s = r * poly(r2, &SINPI);
+                let c = FastNum::fma(r2, poly(r2, &COSPI), 1.0);
+                let (s, c) = match n as i32 & 3 {
+                    0 => (s, c),
+                    1 => (c, 0.0 - s),
+                    2 => (-s, -c),
+                    _ => (-c, s),
+                };
+                // sinpi of an integer is the zero of its sign
+                if self == round_even(self) {
+                    (self * 0.0, c)
+                } else {
+                    (s, c)
+                }
+            }
+
+            fn rcbrt(self) -> Self {
+                let a = self.fast_abs();
+                if a == 0.0 || a == F::INFINITY || a.is_nan() {
+                    return 1.0 / self;
+                }
+                let (m, scale) = cbrt_split(a);
+                let mut y = rcbrt_seed(m);
+                for _ in 0..RCBRT_STEPS {
+                    let e = FastNum::fma(-(m * y), y * y, 1.0);
+                    y = FastNum::fma(y * (1.0 / 3.0), e, y);
+                }
+                (y * scale).fast_copysign(self)
+            }
+
+            fn j0(self) -> Self {
+                j0(self)
+            }
+
+            fn j1(self) -> Self {
+                j1(self)
+            }
+
+            fn y0(self) -> Self {
+                y0(self)
+            }
+
+            fn y1(self) -> Self {
+                y1(self)
+            }
+        }
+    };
+}
+
+mod single {
+    use super::{poly, SpecialFn};
+    use crate::FastNum;
+    use core::f32::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI, LOG2_E, PI, SQRT_2};
+
+    type F = f32;
+
+    /// erf(x) / x in x^2, |x| < 1
+    const ERF: [f32; 7] = [
+        7.875875e-05,
+        -0.00080168643,
+        0.0051890872,
+        -0.026854211,
+        0.11283594,
+        -0.37612626,
+        FRAC_2_SQRT_PI,
+    ];
+    /// erfc(x) e^(x^2) (1 + 2x) in t = (x - ERFC_K) / (x + ERFC_K), x >= 0.5
+    const ERFC: [f32; 11] = [
+        0.0002773814,
+        -0.00033629255,
+        -0.0015229029,
+        0.0012335543,
+        0.0087427115,
+        -0.008028145,
+        -0.054222215,
+        0.16405086,
+        -0.16603033,
+        -0.09276382,
+        1.2769784,
+    ];
+    /// sin(πr) / r in r^2, |r| <= 1/4
+    const SINPI: [f32; 4] = [-0.5890751, 2.549767, -5.167708, PI];
+    /// (cos(πr) - 1) / r^2 in r^2, |r| <= 1/4
+    const COSPI: [f32; 4] = [0.23212542, -1.3351378, 4.0587106, -4.934802];
+    /// (e^r - 1 - r) / r^2, |r| <= ln(2) / 2
+    const EXP: [f32; 6] = [
+        0.00019890981,
+        0.0013933642,
+        0.0083333105,
+        0.041666467,
+        0.16666667,
+        0.5,
+    ];
+    /// erfinv(x) / x in w - 2.5, w = -ln(1 - x^2) < 5
+    const ERFINV_A: [f32; 10] = [
+        -8.931926e-09,
+        2.3335968e-08,
+        4.6813136e-07,
+        -3.4639852e-06,
+        -4.97242e-06,
+        0.00021834939,
+        -0.0012527264,
+        -0.004177393,
+        0.24664028,
+        1.5014094,
+    ];
+    /// in sqrt(w) - 3, w in [5, 16)
+    const ERFINV_B: [f32; 10] = [
+        0.00012924176,
+        -0.00031992668,
+        -7.60479e-05,
+        0.0014972225,
+        -0.0035878157,
+        0.0056826784,
+        -0.007639139,
+        0.009445744,
+        1.001675,
+        2.8329768,
+    ];
+    /// in sqrt(w) - 7, w in [16, 104)
+    const ERFINV_C: [f32; 11] = [
+        3.1340536e-10,
+        -2.4305342e-09,
+        7.957308e-09,
+        -4.2342773e-08,
+        3.1386134e-07,
+        -1.6691228e-06,
+        6.065872e-06,
+        6.369467e-06,
+        -0.00051267364,
+        1.0085956,
+        6.8690195,
+    ];
+    const ERFC_K: f32 = 2.0;
+    const ERFINV_W: [f32; 2] = [5.0, 16.0];
+    const ERFINV_CENTER: [f32; 3] = [2.5, 3.0, 7.0];
+    const ERFINV_HALLEY: bool = false;
+    const SQRT_PI_2: f32 = 0.88622695;
+    /// 1 / sqrt(2) - FRAC_1_SQRT_2
+    const FRAC_1_SQRT_2_LO: f32 = 1.2101617e-8;
+    const RCBRT_STEPS: usize = 1;
+
+    fn round_even(x: f32) -> f32 {
+        core::intrinsics::round_ties_even_f32(x)
+    }
+
+    /// the approximate ln, for subnormals as well
+    fn ln(x: f32) -> f32 {
+        if x < f32::MIN_POSITIVE {
+            (x * 16777216.0).fast_ln() - 16.635532
+        } else {
+            x.fast_ln()
+        }
+    }
+
+    /// e^(x + x_lo)
+    fn exp_tail(x: f32, x_lo: f32) -> f32 {
+        /// ln(2) - LN_2
+        const LN_2_LO: f32 = -1.9046543e-9;
+        if x.is_nan() {
+            return x;
+        }
+        let x = x.clamp(-120.0, 120.0);
+        let n = round_even(x * LOG2_E);
+        // exact, as n * LN_2 is a multiple of 2^-24 and |x - n * LN_2| < 1
+        let r0 = FastNum::fma(-n, core::f32::consts::LN_2, x);
+        let w = n * LN_2_LO;
+        let r = r0 - w;
+        let r_lo = (r0 - r) - w + x_lo;
+        let p = FastNum::fma(poly(r, &EXP), r * r, r);
+        let e = 1.0 + FastNum::fma(p, r_lo, p + r_lo);
+        let n = n as i32;
+        let h = n >> 1;
+        e * f32::from_bits(((h + 127) as u32) << 23) * f32::from_bits(((n - h + 127) as u32) << 23)
+    }
+
+    /// (m, 2^-k) with x = m * 2^3k and m in [1, 8)
+    fn cbrt_split(x: f32) -> (f32, f32) {
+        let (bits, k0) = if x < f32::MIN_POSITIVE {
+            ((x * 16777216.0).to_bits(), -8)
+        } else {
+            (x.to_bits(), 0)
+        };
+        let e = (bits >> 23) as i32 - 127;
+        let k = e.div_euclid(3);
+        let m = f32::from_bits((bits & 0x007f_ffff) | (((e - 3 * k + 127) as u32) << 23));
+        (m, f32::from_bits(((127 - k - k0) as u32) << 23))
+    }
+
+    /// within 2^-21 of m^(-1/3)
+    pub(super) fn rcbrt_seed(m: f32) -> f32 {
+        (m.fast_log2() * (-1.0 / 3.0)).fast_exp2()
+    }
+
+    fn lgamma(x: f32) -> f32 {
+        super::double::lgamma(x as f64) as f32
+    }
+
+    fn tgamma(x: f32) -> f32 {
+        super::double::tgamma(x as f64) as f32
+    }
+
+    fn j0(x: f32) -> f32 {
+        super::double::j0(x as f64) as f32
+    }
+
+    fn j1(x: f32) -> f32 {
+        super::double::j1(x as f64) as f32
+    }
+
+    fn y0(x: f32) -> f32 {
+        super::double::y0(x as f64) as f32
+    }
+
+    fn y1(x: f32) -> f32 {
+        super::double::y1(x as f64) as f32
+    }
+
+    special_fn!();
+}
+
+mod double {
+    use super::{approx_f64, poly, SpecialFn};
+    use crate::FastNum;
+    use approx_f64::{exp_tail, ln};
+    use core::f64::consts::{FRAC_1_SQRT_2, FRAC_2_PI, FRAC_2_SQRT_PI, PI, SQRT_2};
+
+    type F = f64;
+
+    /// erf(x) / x in x^2, |x| < 1
+    const ERF: [f64; 12] = [
+        -7.795898827002142e-10,
+        1.3720064546777686e-08,
+        -1.6208483801871705e-07,
+        1.6447424703317362e-06,
+        -1.492473690741966e-05,
+        0.00012055294904839707,
+        -0.0008548325975389692,
+        0.0052239776071164225,
+        -0.02686617064323777,
+        0.11283791670945006,
+        -0.37612638903183543,
+        FRAC_2_SQRT_PI,
+    ];
+    /// erfc(x) e^(x^2) (1 + 2x) in t = (x - ERFC_K) / (x + ERFC_K), x >= 0.5
+    const ERFC: [f64; 24] = [
+        -1.0208600570932139e-10,
+        3.578785272009893e-09,
+        -9.347150015015463e-09,
+        -2.375708390014323e-08,
+        1.0293058484573893e-07,
+        8.313287964759644e-08,
+        -8.193952094668847e-07,
+        2.867911391673702e-07,
+        5.7065508106405075e-06,
+        -1.1218420266513659e-05,
+        -2.4398126926219842e-05,
+        0.00015062048007268285,
+        -0.00019925693646602577,
+        -0.000757773033187412,
+        0.005031970012230075,
+        -0.01619773409200716,
+        0.0371675155345407,
+        -0.06633036580952523,
+        0.0937328349984903,
+        -0.10103906603641383,
+        0.06809705425468951,
+        0.01537965210262149,
+        -0.1396211168405625,
+        1.2329951186255526,
+    ];
+    /// sin(πr) / r in r^2, |r| <= 1/4
+    const SINPI: [f64; 7] = [
+        0.000461531855383581,
+        -0.007370021586907771,
+        0.08214586918000175,
+        -0.5992645289396449,
+        2.5501640398733763,
+        -5.167712780049954,
+        PI,
+    ];
+    /// (cos(πr) - 1) / r^2 in r^2, |r| <= 1/4
+    const COSPI: [f64; 7] = [
+        -0.00010370082971594627,
+        0.001929493874894941,
+        -0.025806887964701878,
+        0.23533063028399223,
+        -1.3352627688538095,
+        4.058712126416765,
+        -4.934802200544679,
+    ];
+    /// erfinv(x) / x in w - 3.125, w = -ln(1 - x^2) < 6.25
+    const ERFINV_A: [f64; 24] = [
+        3.240704459379902e-21,
+        -1.113483769832702e-21,
+        -3.4996044354754177e-19,
+        1.150022252066253e-18,
+        1.557432024623325e-17,
+        -1.3016928016796445e-16,
+        -4.0282154327661744e-17,
+        6.596407712420834e-15,
+        -4.001237735091896e-14,
+        -8.118399443074353e-14,
+        2.6304834740560442e-12,
+        -1.2976885526532075e-11,
+        -5.414287198084716e-11,
+        1.0512181539804126e-09,
+        -4.11266082002324e-09,
+        -2.9070382262927266e-08,
+        4.234788173782339e-07,
+        -1.3654691850603575e-06,
+        -1.3882523394405316e-05,
+        0.00018673420802464837,
+        -0.000740702534154477,
+        -0.00603367087142785,
+        0.2401581824255883,
+        1.6536545626831027,
+    ];
+    /// in sqrt(w) - 3.25, w in [6.25, 16)
+    const ERFINV_B: [f64; 20] = [
+        -1.5102695423335065e-08,
+        6.969241977629694e-10,
+        1.3104351499972014e-07,
+        -2.713451105439456e-07,
+        -2.7004219280868947e-08,
+        1.4987130784186127e-06,
+        -3.986084395214756e-06,
+        2.9257291901789823e-06,
+        1.2465167157087251e-05,
+        -4.731898412011245e-05,
+        6.828708654753377e-05,
+        2.4031257015092525e-05,
+        -0.00035503780975963814,
+        0.0009532893637435638,
+        -0.0016882755357337071,
+        0.0024914420969817607,
+        -0.003751208508215561,
+        0.00537091455357168,
+        1.0052589676941652,
+        3.0838856104922208,
+    ];
+    /// in sqrt(w) - 16, w in [16, 745), to 2^-24 before the Halley step
+    const ERFINV_C: [f64; 17] = [
+        4.342299521472562e-20,
+        -4.235423357938081e-19,
+        -1.768028618015021e-17,
+        1.4866906253010898e-16,
+        3.272706442307123e-15,
+        -2.4887685620633725e-14,
+        -3.0160144163362556e-13,
+        1.983562933656715e-12,
+        1.660199786329415e-11,
+        -8.357379274507441e-11,
+        -8.912639816544352e-10,
+        1.3845929616278803e-08,
+        -2.7335059919887603e-07,
+        6.336793822046651e-06,
+        -0.0001420501373467858,
+        1.00324134649086,
+        15.91701700785839,
+    ];
+    /// lgamma(1 + t) / t, t in [-0.25, 0.5]
+    const LGAMMA_1: [f64; 22] = [
+        0.006837511584303814,
+        -0.025986773971407036,
+        0.04671230819605673,
+        -0.056518139297869995,
+        0.05790211255423161,
+        -0.058977697054169845,
+        0.06224250004458276,
+        -0.06660002797561868,
+        0.07144286096823818,
+        -0.07693834399295793,
+        0.0833539229301034,
+        -0.09095377585749281,
+        0.10009943889128511,
+        -0.11133427134128401,
+        0.1255096701225694,
+        -0.14404989670121374,
+        0.1695571769891583,
+        -0.20738555102909395,
+        0.27058080842783183,
+        -0.4006856343865304,
+        0.8224670334241131,
+        -0.5772156649015329,
+    ];
+    /// lgamma(2 + t) / t, t in [-0.5, 0.5]
+    const LGAMMA_2: [f64; 18] = [
+        2.7475390051909663e-07,
+        -5.811277768921246e-07,
+        9.242852225162277e-07,
+        -1.9743106888421067e-06,
+        4.3828545383846665e-06,
+        -9.45628851510172e-06,
+        2.050601970174762e-05,
+        -4.4923727564247656e-05,
+        9.945761692157133e-05,
+        -0.00022315497746292723,
+        0.0005096695196526673,
+        -0.0011927539009983087,
+        0.002890510330865901,
+        -0.007385551028935545,
+        0.02058080842778338,
+        -0.06735230105319563,
+        0.3224670334241132,
+        0.42278433509846713,
+    ];
+    /// Γ(1 + f), f in [0, 1]
+    const GAMMA: [f64; 23] = [
+        0.0001736585029100638,
+        -0.0021707303979275673,
+        0.012904984615359194,
+        -0.0487273987691842,
+        0.13188641661041167,
+        -0.27447318558727724,
+        0.46161682980604635,
+        -0.6538979651608092,
+        0.8107498136074465,
+        -0.9131775625984725,
+        0.9669073155044755,
+        -0.9894894401507227,
+        0.996927723790381,
+        -0.998504818829535,
+        0.9980236622175842,
+        -0.9959918555778632,
+        0.9931482255939196,
+        -0.9819950119662109,
+        0.9817280843795753,
+        -0.9074790760156275,
+        0.9890559953270482,
+        -0.5772156649015276,
+        1.0,
+    ];
+    /// (lgamma(x) - (x - 1/2) ln(x) + x - ln(2π) / 2) x in 1 / x^2, x >= 8
+    const STIRLING: [f64; 7] = [
+        0.005065905360156132,
+        -0.001890382993887715,
+        0.0008414700852503147,
+        -0.0005952365845057049,
+        0.0007936507897524059,
+        -0.002777777777773988,
+        0.08333333333333333,
+    ];
+    /// j0(x) / (x - J0_ZEROS[0]) in x - 2, x in [0, 4)
+    const J0_A: [f64; 19] = [
+        1.3071020902287314e-18,
+        1.0678010440087583e-17,
+        -4.925786173898778e-16,
+        -3.5919697204255675e-15,
+        1.4261569537639818e-13,
+        9.097618965083663e-13,
+        -3.2121980916685704e-11,
+        -1.755348146460729e-10,
+        5.4347155507219945e-09,
+        2.4730001266795444e-08,
+        -6.583392059469757e-07,
+        -2.394071251983361e-06,
+        5.3366950022528775e-05,
+        0.00014533827874062873,
+        -0.0026128151523129102,
+        -0.004733088815937258,
+        0.064801890255307,
+        0.05846927373094543,
+        -0.5530549514106766,
+    ];
+    /// j0(x) / (x - J0_ZEROS[1]) in x - 6, x in [4, 8)
+    const J0_B: [f64; 19] = [
+        -6.089337793197239e-19,
+        -2.420773089457343e-17,
+        2.3634251075615695e-16,
+        8.099174605123997e-15,
+        -7.086852359661718e-14,
+        -2.035109821009615e-12,
+        1.6621792856977836e-11,
+        3.881003341805387e-10,
+        -2.9472164422313693e-09,
+        -5.369878633692875e-08,
+        3.7683306575973564e-07,
+        5.046333168084599e-06,
+        -3.245981773476202e-05,
+        -0.0002903924577954878,
+        0.0016907164421519542,
+        0.008464995494980606,
+        -0.04343003493451364,
+        -0.07753659236669226,
+        0.3138953660581486,
+    ];
+    /// j1(x) / x in x^2, x in [0, 2)
+    const J1_A: [f64; 10] = [
+        -1.3840271857946309e-18,
+        5.208989409521519e-16,
+        -1.5017292246021537e-13,
+        3.363929845848442e-11,
+        -5.651403343764827e-09,
+        6.781684027641646e-07,
+        -5.425347222221385e-05,
+        0.002604166666666664,
+        -0.0625,
+        0.5,
+    ];
+    /// j1(x) / (x - J1_ZEROS[0]) in x - 3.75, x in [2, 5.5)
+    const J1_B: [f64; 18] = [
+        1.3451795344213811e-17,
+        -4.3267241467910583e-16,
+        -4.415944336298794e-15,
+        1.2985961174515848e-13,
+        1.1008000267631663e-12,
+        -2.9235538162881605e-11,
+        -2.0815343307703451e-10,
+        4.935165250515397e-09,
+        2.853043842827926e-08,
+        -5.952237563580997e-07,
+        -2.6549733432998242e-06,
+        4.78371002014533e-05,
+        0.0001516030565509649,
+        -0.0022997974943015217,
+        -0.0044387289203157805,
+        0.054589716222295685,
+        0.043729414425389385,
+        -0.4066942604718561,
+    ];
+    /// j1(x) / (x - J1_ZEROS[1]) in x - 6.75, x in [5.5, 8)
+    const J1_C: [f64; 16] = [
+        2.5893444308246282e-15,
+        -1.3595240752226292e-13,
+        -6.294174597683494e-13,
+        3.103678251799193e-11,
+        1.1198555530448927e-10,
+        -5.18810463548175e-09,
+        -1.4054278075881712e-08,
+        6.161670261181712e-07,
+        1.1395499566891462e-06,
+        -4.829950151910537e-05,
+        -5.0865547016361815e-05,
+        0.0022232771574366653,
+        0.0008221320929028738,
+        -0.04855335522605511,
+        0.004062110663005632,
+        0.3024353041176374,
+    ];
+    /// y0(x) - 2 / π ln(x) j0(x) in x^2 - 32, x in [0, 8)
+    const Y0: [f64; 17] = [
+        -7.311009700435112e-37,
+        7.1275009065013e-34,
+        -6.0397413593306155e-31,
+        4.442755575760332e-28,
+        -2.7916105485492112e-25,
+        1.476962666167703e-22,
+        -6.465532005379263e-20,
+        2.291240565346129e-17,
+        -6.389874267616093e-15,
+        1.3494586944980292e-12,
+        -2.0392003558735958e-10,
+        2.0032437990402218e-08,
+        -1.0276295984474178e-06,
+        4.398386343170921e-06,
+        0.0016084926551509244,
+        -0.025888621573764335,
+        -0.382250654459425,
+    ];
+    /// (y1(x) - 2 / π (ln(x) j1(x) - 1 / x)) / x in x^2 - 32, x in [0, 8)
+    const Y1: [f64; 17] = [
+        -2.22899827239453e-38,
+        2.3187529317258707e-35,
+        -2.108055595374712e-32,
+        1.6722972914145461e-29,
+        -1.1408688683157444e-26,
+        6.607195463109877e-24,
+        -3.1983303777692308e-21,
+        1.2697208298522968e-18,
+        -4.0364952247863336e-16,
+        9.960759023433928e-14,
+        -1.8280704350303325e-11,
+        2.3409089214332497e-09,
+        -1.8730010852747369e-07,
+        7.173830934652635e-06,
+        2.2819894258782797e-05,
+        -0.007605544712247848,
+        0.07075985882118005,
+    ];
+    /// Hankel's P0 in z = 64 / x^2, x >= 8
+    const P0: [f64; 13] = [
+        1.3219700423350835e-10,
+        -1.0029066979633945e-09,
+        3.589369421372812e-09,
+        -8.31251965044764e-09,
+        1.4845549354895297e-08,
+        -2.4185615179408813e-08,
+        4.339314718568921e-08,
+        -1.0229475399421496e-07,
+        3.620184781129259e-07,
+        -2.183917728558513e-06,
+        2.738088361130017e-05,
+        -0.0010986328124985556,
+        1.0,
+    ];
+    /// Hankel's Q0 x in z
+    const Q0: [f64; 15] = [
+        -6.334002499904253e-10,
+        5.266327693631826e-09,
+        -2.0386719524825114e-08,
+        4.9391240246188914e-08,
+        -8.582431886459488e-08,
+        1.1847376507015868e-07,
+        -1.4502397065436714e-07,
+        1.8029153673326535e-07,
+        -2.6379570133829e-07,
+        5.13190214056454e-07,
+        -1.4531646487282279e-06,
+        6.5907556444006975e-06,
+        -5.544628942274274e-05,
+        0.0011444091796867502,
+        -0.125,
+    ];
+    /// Hankel's P1 in z
+    const P1: [f64; 13] = [
+        -1.4038395089577798e-10,
+        1.0657770307911482e-09,
+        -3.818735305533677e-09,
+        8.860855361799285e-09,
+        -1.5879522872690333e-08,
+        2.6030250382020736e-08,
+        -4.719167778074151e-08,
+        1.1307073738668152e-07,
+        -4.102893385438351e-07,
+        2.5809939131965017e-06,
+        -3.520399323335166e-05,
+        0.001831054687498473,
+        1.0,
+    ];
+    /// Hankel's Q1 x in z
+    const Q1: [f64; 15] = [
+        6.675997302173228e-10,
+        -5.55266846989347e-09,
+        2.150642392457472e-08,
+        -5.214559601645544e-08,
+        9.072695385014144e-08,
+        -1.255129677702513e-07,
+        1.5421300293087223e-07,
+        -1.9290508409888594e-07,
+        2.8495666762962395e-07,
+        -5.620781984196204e-07,
+        1.6241270941123224e-06,
+        -7.604718230766905e-06,
+        6.77676870820278e-05,
+        -0.0016021728515617122,
+        0.375,
+    ];
+    /// the first two positive zeros of j0 & j1, as hi + lo
+    const J0_ZEROS: [[f64; 2]; 2] = [
+        [2.404825557695773, -1.176691651530894e-16],
+        [5.520078110286311, 8.088597146146722e-17],
+    ];
+    const J1_ZEROS: [[f64; 2]; 2] = [
+        [3.8317059702075125, -1.5269184090088067e-16],
+        [7.015586669815619, -9.414165653410389e-17],
+    ];
+    const ERFC_K: f64 = 4.0;
+    const ERFINV_W: [f64; 2] = [6.25, 16.0];
+    const ERFINV_CENTER: [f64; 3] = [3.125, 3.25, 16.0];
+    const ERFINV_HALLEY: bool = true;
+    const SQRT_PI_2: f64 = 0.886226925452758;
+    /// 1 / sqrt(2) - FRAC_1_SQRT_2
+    const FRAC_1_SQRT_2_LO: f64 = -4.833646656726457e-17;
+    const RCBRT_STEPS: usize = 2;
+    const HALF_LN_2PI: f64 = 0.9189385332046728;
+    const LN_PI: f64 = 1.1447298858494002;
+    const FRAC_1_SQRT_PI: f64 = 0.5641895835477563;
+
+    fn round_even(x: f64) -> f64 {
+        core::intrinsics::round_ties_even_f64(x)
+    }
+
+    /// (m, 2^-k) with x = m * 2^3k and m in [1, 8)
+    fn cbrt_split(x: f64) -> (f64, f64) {
+        let (bits, k0) = if x < f64::MIN_POSITIVE {
+            ((x * 18014398509481984.0).to_bits(), -18)
+        } else {
+            (x.to_bits(), 0)
+        };
+        let e = (bits >> 52) as i32 - 1023;
+        let k = e.div_euclid(3);
+        let m =
+            f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | (((e - 3 * k + 1023) as u64) << 52));
+        (m, f64::from_bits(((1023 - k - k0) as u64) << 52))
+    }
+
+    fn rcbrt_seed(m: f64) -> f64 {
+        super::single::rcbrt_seed(m as f32) as f64
+    }
+
+    /// lgamma for x > 0
+    fn lgamma_positive(x: f64) -> f64 {
+        if x < 0.5 {
+            // lgamma(1 + x) - ln(x)
+            x * poly(x, &LGAMMA_1) - ln(x)
+        } else if x < 0.75 {
+            let t = x - 1.0;
+            t * poly(t, &LGAMMA_2) - ln(x)
+        } else if x < 1.5 {
+            let t = x - 1.0;
+            // + 0 for lgamma(1) = +0
+            t * poly(t, &LGAMMA_1) + 0.0
+        } else if x < 8.0 {
+            // lgamma(x) = ln((x - 1) .. (y)) + lgamma(y), y in [1.5, 2.5)
+            let mut y = x;
+            let mut p = 1.0;
+            while y >= 2.5 {
+                y -= 1.0;
+                p *= y;
+            }
+            let t = y - 2.0;
+            let l = t * poly(t, &LGAMMA_2);
+            if p == 1.0 {
+                l
+            } else {
+                ln(p) + l
+            }
+        } else {
+            let u = x.fast_recip();
+            (x - 0.5) * (ln(x) - 1.0) + (HALF_LN_2PI - 0.5) + u * poly(u * u, &STIRLING)
+        }
+    }
+
+    pub(super) fn lgamma(x: f64) -> f64 {
+        if x > 0.0 || x.is_nan() {
+            lgamma_positive(x)
+        } else if x == x.fast_floor() {
+            // the poles, 0 & the negative integers, and -inf
+            f64::INFINITY
+        } else if x > -0.25 {
+            x * poly(x, &LGAMMA_1) - ln(-x)
+        } else {
+            // Γ(x) Γ(-x) = -π / (x sin(πx))
+            LN_PI - ln((x * x.sinpi()).fast_abs()) - lgamma_positive(-x)
+        }
+    }
+
+    pub(super) fn tgamma(x: f64) -> f64 {
+        if x >= 1.0 {
+            if x > 171.7 {
+                return f64::INFINITY;
+            }
+            // Γ(x) = (x - 1) .. (y) Γ(y), y in [1, 2), with the product as p + p_lo
+            let mut y = x;
+            let mut p = 1.0;
+            let mut p_lo = 0.0;
+            while y >= 2.0 {
+                y -= 1.0;
+                let h = p * y;
+                p_lo = FastNum::fma(p, y, -h) + p_lo * y;
+                p = h;
+            }
+            let g = poly(y - 1.0, &GAMMA);
+            FastNum::fma(g, p, g * p_lo)
+        } else if x > 0.0 {
+            poly(x, &GAMMA) / x
+        } else if x == x.fast_floor() || x.is_nan() {
+            // Γ(±0) is ±inf, the negative integers & -inf are poles
+            if x == 0.0 {
+                1.0 / x
+            } else {
+                f64::NAN
+            }
+        } else if x > -1.0 {
+            // Γ(x) = Γ(x + 2) / (x (x + 1))
+            let y = x + 1.0;
+            poly(y, &GAMMA) / (x * y)
+        } else if x < -190.0 {
+            // |Γ(x)| < 2^-1074, the zero of the reflection's sign
+            -PI / (x * x.sinpi()) * 0.0
+        } else {
+            // Γ(x) Γ(-x) = -π / (x sin(πx)), Γ(-x) by the recurrence down to where it doesn't overflow
+            let mut y = -x;
+            let mut q = -PI / (x * x.sinpi());
+            while y > 171.0 {
+                y -= 1.0;
+                q /= y;
+            }
+            q / tgamma(y)
+        }
+    }
+
+    /// x - (zero[0] + zero[1]), with the zero's rounding error
+    fn from_zero(x: f64, zero: [f64; 2]) -> f64 {
+        (x - zero[0]) - zero[1]
+    }
+
+    /// (P, Q) of j & y = (P cos(χ) - Q sin(χ), P sin(χ) + Q cos(χ)) sqrt(2 / πa), a >= 8
+    fn hankel(a: f64, p: &[f64], q: &[f64]) -> (f64, f64) {
+        let z = 64.0 * (a * a).fast_recip();
+        (poly(z, p), poly(z, q) * a.fast_recip())
+    }
+
+    /// (c + s, s - c) with (s, c) = sincos(a), the smaller one from (c + s) (s - c) = -cos(2a) rather than
+    /// cancelling
+    fn hankel_trig(a: f64) -> (f64, f64) {
+        let (s, c) = approx_f64::sincos(a);
+        let (sum, diff) = (c + s, s - c);
+        if a > f64::MAX * 0.5 {
+            (sum, diff)
+        } else if s * c < 0.0 {
+            (-approx_f64::cos(a + a) / diff, diff)
+        } else {
+            (sum, -approx_f64::cos(a + a) / sum)
+        }
+    }
+
+    /// 1 / sqrt(πa), without overflowing πa
+    fn hankel_scale(a: f64) -> f64 {
+        approx_f64::rsqrt(a) * FRAC_1_SQRT_PI
+    }
+
+    pub(super) fn j0(x: f64) -> f64 {
+        let a = x.fast_abs();
+        if a < 4.0 {
+            from_zero(a, J0_ZEROS[0]) * poly(a - 2.0, &J0_A)
+        } else if a < 8.0 {
+            from_zero(a, J0_ZEROS[1]) * poly(a - 6.0, &J0_B)
+        } else if a == f64::INFINITY {
+            0.0
+        } else {
+            // χ = a - π / 4
+            let (sum, diff) = hankel_trig(a);
+            let (p, q) = hankel(a, &P0, &Q0);
+            hankel_scale(a) * (p * sum - q * diff)
+        }
+    }
+
+    pub(super) fn j1(x: f64) -> f64 {
+        let a = x.fast_abs();
+        if a < 2.0 {
+            return x * poly(x * x, &J1_A);
+        }
+        let j = if a < 5.5 {
+            from_zero(a, J1_ZEROS[0]) * poly(a - 3.75, &J1_B)
+        } else if a < 8.0 {
+            from_zero(a, J1_ZEROS[1]) * poly(a - 6.75, &J1_C)
+        } else if a == f64::INFINITY {
+            0.0
+        } else {
+            // χ = a - 3π / 4
+            let (sum, diff) = hankel_trig(a);
+            let (p, q) = hankel(a, &P1, &Q1);
+            hankel_scale(a) * (p * diff + q * sum)
+        };
+        // j1 is odd
+        if x < 0.0 {
+            -j
+        } else {
+            j
+        }
+    }
+
+    pub(super) fn y0(x: f64) -> f64 {
+        if x < 8.0 {
+            if x < 0.0 {
+                return f64::NAN;
+            }
+            FastNum::fma(
+                FRAC_2_PI * ln(x),
+                j0(x),
+                poly(FastNum::fma(x, x, -32.0), &Y0),
+            )
+        } else if x == f64::INFINITY {
+            0.0
+        } else {
+            let (sum, diff) = hankel_trig(x);
+            let (p, q) = hankel(x, &P0, &Q0);
+            hankel_scale(x) * (p * diff + q * sum)
+        }
+    }
+
+    pub(super) fn y1(x: f64) -> f64 {
+        if x < 8.0 {
+            if x <= 0.0 {
+                return if x == 0.0 {
+                    f64::NEG_INFINITY
+                } else {
+                    f64::NAN
+                };
+            }
+            // 2 / πx overflows later than 1 / x
+            let l = FastNum::fma(FRAC_2_PI * ln(x), j1(x), -FRAC_2_PI / x);
+            l + x * poly(FastNum::fma(x, x, -32.0), &Y1)
+        } else if x == f64::INFINITY {
+            0.0
+        } else {
+            let (sum, diff) = hankel_trig(x);
+            let (p, q) = hankel(x, &P1, &Q1);
+            hankel_scale(x) * (q * diff - p * sum)
+        }
+    }
+
+    special_fn!();
+}
@@ -1,3 +1,5 @@
+use crate::Dims;
+
 extern "C" {
     #[ffi_const]
     #[link_name = "llvm.nvvm.read.ptx.sreg.tid.x"]
@@ -46,6 +48,22 @@ extern "C" {
     #[ffi_const]
     #[link_name = "llvm.nvvm.read.ptx.sreg.laneid"]
     fn read_nvvm_read_ptx_sreg_laneid() -> u32;
+
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.read.ptx.sreg.lanemask.eq"]
+    fn read_ptx_sreg_lanemask_eq() -> u32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.read.ptx.sreg.lanemask.le"]
+    fn read_ptx_sreg_lanemask_le() -> u32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.read.ptx.sreg.lanemask.lt"]
+    fn read_ptx_sreg_lanemask_lt() -> u32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.read.ptx.sreg.lanemask.ge"]
+    fn read_ptx_sreg_lanemask_ge() -> u32;
+    #[ffi_const]
+    #[link_name = "llvm.nvvm.read.ptx.sreg.lanemask.gt"]
+    fn read_ptx_sreg_lanemask_gt() -> u32;
 }
 
 pub struct threadIdx {}
@@ -87,6 +105,10 @@ impl blockDim {
     pub fn z() -> u32 {
         unsafe { read_ptx_sreg_ntid_z() }
     }
+    /// for unravelling `thread_rank`
+    pub fn dims() -> Dims {
+        Dims::new(Self::x(), Self::y())
+    }
 }
 
 impl gridDim {
@@ -99,6 +121,10 @@ impl gridDim {
     pub fn z() -> u32 {
         unsafe { read_ptx_sreg_nctaid_z() }
     }
+    /// for unravelling linear block indices
+    pub fn dims() -> Dims {
+        Dims::new(Self::x(), Self::y())
+    }
 }
 
 pub fn laneid() -> u32 {
@@ -108,3 +134,33 @@ pub fn laneid() -> u32 {
 pub fn warpsize() -> u32 {
     unsafe { read_nvvm_read_ptx_sreg_warpsize() }
 }
+
+/// mask with only the bit of the executing lane set
+pub fn lanemask_eq() -> u32 {
+    unsafe { read_ptx_sreg_lanemask_eq() }
+}
+
+/// mask of the lanes with an id less than or equal to the executing lane's
+pub fn lanemask_le() -> u32 {
+    unsafe { read_ptx_sreg_lanemask_le() }
+}
+
+/// mask of the lanes with an id less than the executing lane's
+pub fn lanemask_lt() -> u32 {
+    unsafe { read_ptx_sreg_lanemask_lt() }
+}
+
+/// mask of the lanes with an id greater than or equal to the executing lane's
+pub fn lanemask_ge() -> u32 {
+    unsafe { read_ptx_sreg_lanemask_ge() }
+}
+
+/// mask of the lanes with an id greater than the executing lane's
+pub fn lanemask_gt() -> u32 {
+    unsafe { read_ptx_sreg_lanemask_gt() }
+}
+
+/// linear rank of the executing thread within its block, with x varying fastest
+pub fn thread_rank() -> u32 {
+    threadIdx::x() + blockDim::x() * (threadIdx::y() + blockDim::y() * threadIdx::z())
+}
@@ -0,0 +1,554 @@
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#extended-precision-integer-arithmetic-instructions
+
+CC.CF doesn't survive between asm blocks, so every chain over the limbs is a single block: an addition is
+add.cc, addc.cc.., a row of a product mad.lo.cc, madc.lo.cc.. then mad.hi.cc, madc.hi.cc.. shifted by a
+limb. Products and Montgomery multiplication are built from rows, each ending with its carry out.
+*/
+
+use core::cmp::Ordering;
+use core::ops::*;
+use num_traits::{
+    Bounded, FromPrimitive, One, ToPrimitive, WrappingAdd, WrappingMul, WrappingSub, Zero,
+};
+
+/// An array of limbs with the carry chains, `[u32; 4]`, `[u32; 8]` & `[u32; 12]`
+pub trait CarryChain: Sized {
+    /// self + rhs and the carry out
+    fn add_cc(&mut self, rhs: &Self) -> bool;
+    /// self - rhs and the borrow out
+    fn sub_cc(&mut self, rhs: &Self) -> bool;
+    /// top:self + a * b and the carry out of top
+    fn mad_row(&mut self, top: &mut u32, a: &Self, b: u32) -> bool;
+}
+
+#[cfg(target_arch = "nvptx64")]
+mod device {
+    use core::arch::asm;
+
+    macro_rules! chain {
+        ($($n:literal: [$r0:ident $($r:ident)*], [$a0:ident $($a:ident)*], [$h0:ident $($h:ident)*];)+) => {
+            $(impl super::CarryChain for [u32; $n] {
+                #[inline]
+                fn add_cc(&mut self, rhs: &Self) -> bool {
+                    let [mut $r0, $(mut $r),*] = *self;
+                    let [$a0, $($a),*] = *rhs;
+                    let mut c = 0u32;
+                    unsafe {
+                        asm!(
+                            concat!("add.cc.u32 {", stringify!($r0), "}, {", stringify!($r0), "}, {", stringify!($a0), "};"),
+                            $(concat!("addc.cc.u32 {", stringify!($r), "}, {", stringify!($r), "}, {", stringify!($a), "};"),)*
+                            "addc.u32 {c}, {c}, 0;",
+                            $r0 = inout(reg32) $r0, $($r = inout(reg32) $r,)*
+                            $a0 = in(reg32) $a0, $($a = in(reg32) $a,)*
+                            c = inout(reg32) c,
+                            options(pure, nomem, nostack)
+                        );
+                    }
+                    *self = [$r0, $($r),*];
+                    c != 0
+                }
+
+                #[inline]
+                fn sub_cc(&mut self, rhs: &Self) -> bool {
+                    let [mut $r0, $(mut $r),*] = *self;
+                    let [$a0, $($a),*] = *rhs;
+                    let mut c = 0u32;
+                    unsafe {
+                        asm!(
+                            concat!("sub.cc.u32 {", stringify!($r0), "}, {", stringify!($r0), "}, {", stringify!($a0), "};"),
+                            $(concat!("subc.cc.u32 {", stringify!($r), "}, {", stringify!($r), "}, {", stringify!($a), "};"),)*
+                            "subc.u32 {c}, {c}, 0;",
+                            $r0 = inout(reg32) $r0, $($r = inout(reg32) $r,)*
+                            $a0 = in(reg32) $a0, $($a = in(reg32) $a,)*
+                            c = inout(reg32) c,
+                            options(pure, nomem, nostack)
+                        );
+                    }
+                    *self = [$r0, $($r),*];
+                    c != 0
+                }
+
+                /// the low halves of the products chained into r0.., then the high halves into r1..top,
+                /// each chain's carry added to c
+                #[inline]
+                fn mad_row(&mut self, top: &mut u32, a: &Self, b: u32) -> bool {
+                    let [mut $r0, $(mut $r),*] = *self;
+                    let [$a0, $($a),*] = *a;
+                    let mut c = 0u32;
+                    unsafe {
+                        asm!(
+                            concat!("mad.lo.cc.u32 {", stringify!($r0), "}, {", stringify!($a0), "}, {b}, {", stringify!($r0), "};"),
+                            $(concat!("madc.lo.cc.u32 {", stringify!($r), "}, {", stringify!($a), "}, {b}, {", stringify!($r), "};"),)*
+                            "addc.cc.u32 {top}, {top}, 0;",
+                            "addc.u32 {c}, {c}, 0;",
+                            concat!("mad.hi.cc.u32 {", stringify!($h0), "}, {", stringify!($a0), "}, {b}, {", stringify!($h0), "};"),
+                            $(concat!("madc.hi.cc.u32 {", stringify!($h), "}, {", stringify!($a), "}, {b}, {", stringify!($h), "};"),)*
+                            "addc.u32 {c}, {c}, 0;",
+                            $r0 = inout(reg32) $r0, $($r = inout(reg32) $r,)*
+                            top = inout(reg32) *top,
+                            $a0 = in(reg32) $a0, $($a = in(reg32) $a,)*
+                            b = in(reg32) b,
+                            c = inout(reg32) c,
+                            options(pure, nomem, nostack)
+                        );
+                    }
+                    *self = [$r0, $($r),*];
+                    c != 0
+                }
+            })+
+        };
+    }
+
+    chain! {
+        4: [r0 r1 r2 r3], [a0 a1 a2 a3], [r1 r2 r3 top];
+        8: [r0 r1 r2 r3 r4 r5 r6 r7], [a0 a1 a2 a3 a4 a5 a6 a7], [r1 r2 r3 r4 r5 r6 r7 top];
+        12: [r0 r1 r2 r3 r4 r5 r6 r7 r8 r9 r10 r11], [a0 a1 a2 a3 a4 a5 a6 a7 a8 a9 a10 a11],
+            [r1 r2 r3 r4 r5 r6 r7 r8 r9 r10 r11 top];
+    }
+}
+
+#[cfg(not(target_arch = "nvptx64"))]
+mod soft {
+    fn add_cc(r: &mut [u32], a: &[u32]) -> bool {
+        r.iter_mut().zip(a).fold(false, |carry, (r, &a)| {
+            let (d, c0) = r.overflowing_add(a);
+            let (d, c1) = d.overflowing_add(carry as u32);
+            *r = d;
+            c0 | c1
+        })
+    }
+
+    fn sub_cc(r: &mut [u32], a: &[u32]) -> bool {
+        r.iter_mut().zip(a).fold(false, |borrow, (r, &a)| {
+            let (d, b0) = r.overflowing_sub(a);
+            let (d, b1) = d.overflowing_sub(borrow as u32);
+            *r = d;
+            b0 | b1
+        })
+    }
+
+    fn mad_row(r: &mut [u32], top: &mut u32, a: &[u32], b: u32) -> bool {
+        let carry = r.iter_mut().zip(a).fold(0, |carry, (r, &a)| {
+            let t = *r as u64 + a as u64 * b as u64 + carry;
+            *r = t as u32;
+            t >> 32
+        });
+        let t = *top as u64 + carry;
+        *top = t as u32;
+        t >> 32 != 0
+    }
+
+    macro_rules! chain {
+        ($($n:literal),+) => {
+            $(impl super::CarryChain for [u32; $n] {
+                fn add_cc(&mut self, rhs: &Self) -> bool {
+                    add_cc(self, rhs)
+                }
+
+                fn sub_cc(&mut self, rhs: &Self) -> bool {
+                    sub_cc(self, rhs)
+                }
+
+                fn mad_row(&mut self, top: &mut u32, a: &Self, b: u32) -> bool {
+                    mad_row(self, top, a, b)
+                }
+            })+
+        };
+    }
+
+    chain!(4, 8, 12);
+}
+
+/// An unsigned integer of `N` 32 bit limbs, least significant first, with wrapping arithmetic
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Uint<const N: usize>(pub [u32; N]);
+
+pub type U128 = Uint<4>;
+pub type U256 = Uint<8>;
+pub type U384 = Uint<12>;
+
+impl<const N: usize> Uint<N> {
+    pub const ZERO: Self = Self([0; N]);
+    pub const MAX: Self = Self([u32::MAX; N]);
+    pub const ONE: Self = {
+        let mut limbs = [0; N];
+        limbs[0] = 1;
+        Self(limbs)
+    };
+    pub const BITS: u32 = 32 * N as u32;
+
+    pub const fn from_limbs(limbs: [u32; N]) -> Self {
+        Self(limbs)
+    }
+
+    /// the low `N` limbs of `x`
+    pub const fn from_u128(x: u128) -> Self {
+        let mut limbs = [0; N];
+        let mut i = 0;
+        while i < N && i < 4 {
+            limbs[i] = (x >> (32 * i)) as u32;
+            i += 1;
+        }
+        Self(limbs)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.0[0] & 1 == 1
+    }
+
+    pub fn bit(&self, i: u32) -> bool {
+        self.0[i as usize / 32] >> (i % 32) & 1 == 1
+    }
+
+    pub fn leading_zeros(&self) -> u32 {
+        match self.0.iter().rposition(|&limb| limb != 0) {
+            Some(i) => 32 * (N - 1 - i) as u32 + self.0[i].leading_zeros(),
+            None => Self::BITS,
+        }
+    }
+
+    /// the bit length of the value
+    pub fn bits(&self) -> u32 {
+        Self::BITS - self.leading_zeros()
+    }
+}
+
+impl<const N: usize> Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    pub fn overflowing_add(mut self, rhs: Self) -> (Self, bool) {
+        let carry = self.0.add_cc(&rhs.0);
+        (self, carry)
+    }
+
+    pub fn overflowing_sub(mut self, rhs: Self) -> (Self, bool) {
+        let borrow = self.0.sub_cc(&rhs.0);
+        (self, borrow)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (difference, false) => Some(difference),
+            (_, true) => None,
+        }
+    }
+
+    /// the full product as (low, high), one `mad_row` per limb of `rhs`
+    pub fn widening_mul(self, rhs: Self) -> (Self, Self) {
+        let mut lo = [0; N];
+        let mut window = [0; N];
+        for (lo, &b) in lo.iter_mut().zip(&rhs.0) {
+            let mut top = 0;
+            window.mad_row(&mut top, &self.0, b);
+            *lo = window[0];
+            window.copy_within(1.., 0);
+            window[N - 1] = top;
+        }
+        (Self(lo), Self(window))
+    }
+
+    /// the high half of the full product
+    pub fn mul_hi(self, rhs: Self) -> Self {
+        self.widening_mul(rhs).1
+    }
+}
+
+impl<const N: usize> Default for Uint<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// compares from the most significant limb
+impl<const N: usize> Ord for Uint<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const N: usize> PartialOrd for Uint<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> From<u32> for Uint<N> {
+    fn from(x: u32) -> Self {
+        Self::from_u128(x as u128)
+    }
+}
+
+impl<const N: usize> From<u64> for Uint<N> {
+    fn from(x: u64) -> Self {
+        Self::from_u128(x as u128)
+    }
+}
+
+impl<const N: usize> From<u128> for Uint<N> {
+    fn from(x: u128) -> Self {
+        Self::from_u128(x)
+    }
+}
+
+impl<const N: usize> Add for Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+}
+
+impl<const N: usize> Sub for Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+}
+
+/// the low half of the full product
+impl<const N: usize> Mul for Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.widening_mul(rhs).0
+    }
+}
+
+macro_rules! impl_assign {
+    ($($Assign: ident, $assign: ident, $op: ident);+) => {
+        $(impl<const N: usize> $Assign for Uint<N>
+        where
+            [u32; N]: CarryChain,
+        {
+            fn $assign(&mut self, rhs: Self) {
+                *self = (*self).$op(rhs)
+            }
+        })+
+    };
+}
+
+impl_assign! {
+    AddAssign, add_assign, add;
+    SubAssign, sub_assign, sub;
+    MulAssign, mul_assign, mul
+}
+
+macro_rules! impl_wrapping {
+    ($($Wrapping: ident, $wrapping: ident, $op: ident);+) => {
+        $(impl<const N: usize> $Wrapping for Uint<N>
+        where
+            [u32; N]: CarryChain,
+        {
+            fn $wrapping(&self, rhs: &Self) -> Self {
+                (*self).$op(*rhs)
+            }
+        })+
+    };
+}
+
+impl_wrapping! {
+    WrappingAdd, wrapping_add, add;
+    WrappingSub, wrapping_sub, sub;
+    WrappingMul, wrapping_mul, mul
+}
+
+impl<const N: usize> Zero for Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        Uint::is_zero(self)
+    }
+}
+
+impl<const N: usize> One for Uint<N>
+where
+    [u32; N]: CarryChain,
+{
+    fn one() -> Self {
+        Self::ONE
+    }
+}
+
+impl<const N: usize> Bounded for Uint<N> {
+    fn min_value() -> Self {
+        Self::ZERO
+    }
+
+    fn max_value() -> Self {
+        Self::MAX
+    }
+}
+
+impl<const N: usize> ToPrimitive for Uint<N> {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_u64().and_then(|x| x.to_i64())
+    }
+
+    #[cfg(feature = "i128")]
+    fn to_i128(&self) -> Option<i128> {
+        self.to_u128().and_then(|x| x.to_i128())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        (self.bits() <= 64).then(|| self.0[0] as u64 | (*self.0.get(1).unwrap_or(&0) as u64) << 32)
+    }
+
+    #[cfg(feature = "i128")]
+    fn to_u128(&self) -> Option<u128> {
+        (self.bits() <= 128).then(|| {
+            self.0
+                .iter()
+                .take(4)
+                .rev()
+                .fold(0, |x, &limb| x << 32 | limb as u128)
+        })
+    }
+}
+
+impl<const N: usize> FromPrimitive for Uint<N> {
+    fn from_i64(n: i64) -> Option<Self> {
+        n.to_u64().and_then(Self::from_u64)
+    }
+
+    #[cfg(feature = "i128")]
+    fn from_i128(n: i128) -> Option<Self> {
+        n.to_u128().and_then(FromPrimitive::from_u128)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        let x = Uint::from_u128(n as u128);
+        (x.to_u64() == Some(n)).then_some(x)
+    }
+
+    #[cfg(feature = "i128")]
+    fn from_u128(n: u128) -> Option<Self> {
+        let x = Uint::from_u128(n);
+        (x.to_u128() == Some(n)).then_some(x)
+    }
+}
+
+/// Multiplication modulo an odd `modulus` in the Montgomery form x R mod modulus, R = 2^(32 N)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery<const N: usize> {
+    pub modulus: Uint<N>,
+    /// -modulus^-1 mod 2^32
+    inv: u32,
+    /// R^2 mod modulus
+    r2: Uint<N>,
+}
+
+impl<const N: usize> Montgomery<N>
+where
+    [u32; N]: CarryChain,
+{
+    /// R^2 by doubling 64 N times, cheap enough to do once per kernel
+    pub fn new(modulus: Uint<N>) -> Self {
+        assert!(modulus.is_odd(), "the Montgomery modulus must be odd");
+        // Newton's iteration, correct to 3, 6, 12, 24, 48 bits
+        let m = modulus.0[0];
+        let inv = (0..4).fold(m, |x, _| {
+            x.wrapping_mul(2u32.wrapping_sub(m.wrapping_mul(x)))
+        });
+        let mut r2 = Self::reduce((Uint::ONE, false), modulus);
+        for _ in 0..2 * Uint::<N>::BITS {
+            r2 = Self::reduce(r2.overflowing_add(r2), modulus);
+        }
+        Self {
+            modulus,
+            inv: inv.wrapping_neg(),
+            r2,
+        }
+    }
+
+    /// x - modulus if the carry is set or x >= modulus
+    fn reduce((x, carry): (Uint<N>, bool), modulus: Uint<N>) -> Uint<N> {
+        match x.overflowing_sub(modulus) {
+            (d, borrow) if carry || !borrow => d,
+            _ => x,
+        }
+    }
+
+    /// a b R^-1 mod modulus for a, b < modulus, by coarsely integrated operand scanning: each limb of
+    /// `b` adds a row of `a` and a row of the modulus clearing the low limb
+    pub fn mul(&self, a: Uint<N>, b: Uint<N>) -> Uint<N> {
+        let mut t = [0; N];
+        let (mut t1, mut t2) = (0u32, 0u32);
+        for &b in &b.0 {
+            t2 += t.mad_row(&mut t1, &a.0, b) as u32;
+            let q = t[0].wrapping_mul(self.inv);
+            t2 += t.mad_row(&mut t1, &self.modulus.0, q) as u32;
+            t.copy_within(1.., 0);
+            t[N - 1] = t1;
+            (t1, t2) = (t2, 0);
+        }
+        Self::reduce((Uint(t), t1 != 0), self.modulus)
+    }
+
+    pub fn square(&self, a: Uint<N>) -> Uint<N> {
+        self.mul(a, a)
+    }
+
+    /// a + b mod modulus
+    pub fn add(&self, a: Uint<N>, b: Uint<N>) -> Uint<N> {
+        Self::reduce(a.overflowing_add(b), self.modulus)
+    }
+
+    /// a - b mod modulus
+    pub fn sub(&self, a: Uint<N>, b: Uint<N>) -> Uint<N> {
+        match a.overflowing_sub(b) {
+            (d, true) => d + self.modulus,
+            (d, false) => d,
+        }
+    }
+
+    /// x R mod modulus, for x < modulus
+    pub fn to_montgomery(&self, x: Uint<N>) -> Uint<N> {
+        self.mul(x, self.r2)
+    }
+
+    pub fn from_montgomery(&self, x: Uint<N>) -> Uint<N> {
+        self.mul(x, Uint::ONE)
+    }
+
+    /// x^e in the Montgomery form, by square and multiply from the most significant bit
+    pub fn pow(&self, x: Uint<N>, e: Uint<N>) -> Uint<N> {
+        let one = self.to_montgomery(Uint::ONE);
+        (0..e.bits()).rev().fold(one, |acc, i| {
+            let acc = self.square(acc);
+            if e.bit(i) {
+                self.mul(acc, x)
+            } else {
+                acc
+            }
+        })
+    }
+}
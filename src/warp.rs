@@ -0,0 +1,90 @@
+use crate::{laneid, Shuffle, ALL_MEMBER_MASK, WARP_SIZE};
+
+/// member mask of the lowest `count` lanes of a warp
+pub fn lane_mask(count: u32) -> u32 {
+    if count >= WARP_SIZE {
+        ALL_MEMBER_MASK
+    } else {
+        (1 << count) - 1
+    }
+}
+
+/// reduces `value` over every lane of a fully active warp, the result is returned to all lanes.
+///
+/// `op` must be associative, lanes are combined in lane order so it need not be commutative.
+pub fn warp_all_reduce<T: Shuffle + Copy, F: Fn(T, T) -> T>(value: T, op: F) -> T {
+    let lane = laneid();
+    let mut value = value;
+    let mut offset = 1;
+    while offset < WARP_SIZE {
+        let other = value.shfl_bfly(ALL_MEMBER_MASK, offset);
+        value = if lane & offset == 0 {
+            op(value, other)
+        } else {
+            op(other, value)
+        };
+        offset <<= 1;
+    }
+    value
+}
+
+/// reduces `value` over the lowest `valid` lanes of the warp, the result is only valid in lane 0.
+///
+/// `membermask` must name every lane executing the call, lanes at or above `valid` still have to
+/// participate but their values are ignored. `op` must be associative, lanes are combined in lane order.
+pub fn warp_reduce<T: Shuffle + Copy, F: Fn(T, T) -> T>(
+    value: T,
+    membermask: u32,
+    valid: u32,
+    op: F,
+) -> T {
+    let lane = laneid();
+    let mut value = value;
+    let mut offset = 1;
+    while offset < WARP_SIZE {
+        let other = value.shfl_down(membermask, offset);
+        if lane + offset < valid {
+            value = op(value, other);
+        }
+        offset <<= 1;
+    }
+    value
+}
+
+/// inclusive prefix scan of `value` over the lanes of `membermask`, which must be the lowest lanes of the warp.
+///
+/// `op` must be associative, lanes are combined in lane order.
+pub fn warp_inclusive_scan<T: Shuffle + Copy, F: Fn(T, T) -> T>(
+    value: T,
+    membermask: u32,
+    op: F,
+) -> T {
+    let lane = laneid();
+    let mut value = value;
+    let mut offset = 1;
+    while offset < WARP_SIZE {
+        let other = value.shfl_up(membermask, offset);
+        if lane >= offset {
+            value = op(other, value);
+        }
+        offset <<= 1;
+    }
+    value
+}
+
+/// exclusive prefix scan of `value` over the lanes of `membermask`, which must be the lowest lanes of the warp.
+/// Lane 0 receives `initial`, which is also folded in front of every other lane's prefix.
+pub fn warp_exclusive_scan<T: Shuffle + Copy, F: Fn(T, T) -> T>(
+    value: T,
+    membermask: u32,
+    initial: T,
+    op: F,
+) -> T {
+    let inclusive = warp_inclusive_scan(value, membermask, &op);
+    let exclusive = inclusive.shfl_up(membermask, 1);
+    if laneid() == 0 {
+        initial
+    } else {
+        op(initial, exclusive)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    activemask, atomic_load_add_f32, atomic_load_add_f64, bf16x2, f16x2, laneid, lanemask_lt,
+    vote_any, FastComplex, FastFloat, FastNum, FastPolicy, Match, Shuffle,
+};
+use core::ops::Add;
+use core::sync::atomic::{AtomicI32, AtomicI64, AtomicU32, AtomicU64, Ordering};
+
+/// Types with a hardware `atom.add`
+pub trait AtomicAdd: Shuffle + Copy + Add<Output = Self> {
+    /// atomically adds `value` to `*ptr` and returns the previous value
+    ///
+    /// # Safety
+    /// `ptr` must be valid and aligned for atomic reads and writes
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self;
+}
+
+/// Types with a hardware `atom.min` and `atom.max`
+pub trait AtomicMinMax: Shuffle + Copy + Ord {
+    /// atomically stores the minimum of `value` and `*ptr` and returns the previous value
+    ///
+    /// # Safety
+    /// `ptr` must be valid and aligned for atomic reads and writes
+    unsafe fn atomic_min(ptr: *mut Self, value: Self) -> Self;
+    /// atomically stores the maximum of `value` and `*ptr` and returns the previous value
+    ///
+    /// # Safety
+    /// `ptr` must be valid and aligned for atomic reads and writes
+    unsafe fn atomic_max(ptr: *mut Self, value: Self) -> Self;
+}
+
+macro_rules! impl_atomic_int {
+    ($($T: ty => $A: ty),+) => {
+        $(impl AtomicAdd for $T {
+            unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+                unsafe { <$A>::from_ptr(ptr) }.fetch_add(value, Ordering::Relaxed)
+            }
+        }
+
+        impl AtomicMinMax for $T {
+            unsafe fn atomic_min(ptr: *mut Self, value: Self) -> Self {
+                unsafe { <$A>::from_ptr(ptr) }.fetch_min(value, Ordering::Relaxed)
+            }
+
+            unsafe fn atomic_max(ptr: *mut Self, value: Self) -> Self {
+                unsafe { <$A>::from_ptr(ptr) }.fetch_max(value, Ordering::Relaxed)
+            }
+        })+
+    };
+}
+
+impl_atomic_int! { u32 => AtomicU32, i32 => AtomicI32, u64 => AtomicU64, i64 => AtomicI64 }
+
+impl AtomicAdd for f32 {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        unsafe { atomic_load_add_f32(ptr, value) }
+    }
+}
+
+impl AtomicAdd for f64 {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        unsafe { atomic_load_add_f64(ptr, value) }
+    }
+}
+
+impl AtomicAdd for f16x2 {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        unsafe { f16x2::atom_add(ptr, value) }
+    }
+}
+
+impl AtomicAdd for bf16x2 {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        unsafe { bf16x2::atom_add(ptr, value) }
+    }
+}
+
+/// the hardware addition, whatever the policy
+impl<F: FastNum + AtomicAdd, P: FastPolicy<F>> AtomicAdd for FastFloat<F, P> {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        FastFloat::new(unsafe { F::atomic_add(ptr as *mut F, value.into_inner()) })
+    }
+}
+
+/// an atomic per component, so each component but not the pair is updated atomically
+impl<F: FastNum + AtomicAdd> AtomicAdd for FastComplex<F> {
+    unsafe fn atomic_add(ptr: *mut Self, value: Self) -> Self {
+        unsafe {
+            FastComplex::new(
+                F::atomic_add(core::ptr::addr_of_mut!((*ptr).re), value.re),
+                F::atomic_add(core::ptr::addr_of_mut!((*ptr).im), value.im),
+            )
+        }
+    }
+}
+
+/// lanes of `membermask` whose `ptr` names the same address as the executing lane's
+fn address_peers<T>(ptr: *mut T, membermask: u32) -> u32 {
+    (ptr as usize as u64).match_any(membermask)
+}
+
+/// folds `value` over the lanes of `peers` in lane order.
+/// Returns the fold over all peers and the fold over the peers below the executing lane, if any.
+fn group_fold<T: Shuffle + Copy, F: Fn(T, T) -> T>(
+    value: T,
+    peers: u32,
+    membermask: u32,
+    op: F,
+) -> (T, Option<T>) {
+    let lane = laneid();
+    let lower = lanemask_lt();
+    let mut remaining = peers & !(1 << peers.trailing_zeros());
+    let mut total = value.shfl_idx(membermask, peers.trailing_zeros());
+    let mut prefix = if peers & lower != 0 {
+        Some(total)
+    } else {
+        None
+    };
+    // every lane must take part in each shuffle, so loop until the largest group is exhausted
+    while vote_any(membermask, remaining != 0) {
+        let src = if remaining != 0 {
+            remaining.trailing_zeros()
+        } else {
+            lane
+        };
+        let other = value.shfl_idx(membermask, src);
+        if remaining != 0 {
+            total = op(total, other);
+            if lower & (1 << src) != 0 {
+                prefix = Some(prefix.map_or(other, |prefix| op(prefix, other)));
+            }
+            remaining &= remaining - 1;
+        }
+    }
+    (total, prefix)
+}
+
+/// performs `atomic` once per group of lanes sharing an address and broadcasts its result
+fn leader_atomic<T: Shuffle + Copy, A: FnOnce(T) -> T>(
+    total: T,
+    peers: u32,
+    membermask: u32,
+    atomic: A,
+) -> T {
+    let leader = peers.trailing_zeros();
+    let old = if peers & lanemask_lt() == 0 {
+        atomic(total)
+    } else {
+        total
+    };
+    old.shfl_idx(membermask, leader)
+}
+
+/// Atomically adds `value` to `*ptr` with one `atom.add` per distinct address in the warp.
+///
+/// Lanes targeting the same address are grouped with `match.any`, their values are summed with shuffles
+/// and the lowest lane of each group performs the atomic. Every lane receives the value it would have
+/// observed had the lanes performed their atomics one after another in lane order, i.e. the previous
+/// value of `*ptr` plus the values of the lower lanes of its group.
+///
+/// # Safety
+/// `ptr` must be valid and aligned for atomic reads and writes
+pub unsafe fn warp_aggregated_atomic_add<T: AtomicAdd>(ptr: *mut T, value: T) -> T {
+    let membermask = activemask();
+    let peers = address_peers(ptr, membermask);
+    let (total, prefix) = group_fold(value, peers, membermask, |a, b| a + b);
+    let old = leader_atomic(total, peers, membermask, |total| unsafe {
+        T::atomic_add(ptr, total)
+    });
+    prefix.map_or(old, |prefix| old + prefix)
+}
+
+/// Atomically increments `*ptr` by one with one `atom.add` per distinct address in the warp, every lane
+/// receives a distinct previous value. This is the usual slot reservation for stream compaction and
+/// queue pushes, call it from within the branch that decides to push.
+///
+/// Unlike [`atomic_load_inc_32`](crate::atomic_load_inc_32) the counter does not wrap around.
+///
+/// # Safety
+/// `ptr` must be valid and aligned for atomic reads and writes
+pub unsafe fn warp_aggregated_atomic_inc(ptr: *mut u32) -> u32 {
+    let membermask = activemask();
+    let peers = address_peers(ptr, membermask);
+    let old = leader_atomic(peers.count_ones(), peers, membermask, |count| unsafe {
+        u32::atomic_add(ptr, count)
+    });
+    old + (peers & lanemask_lt()).count_ones()
+}
+
+/// Atomically stores the minimum of `value` and `*ptr` with one `atom.min` per distinct address in
+/// the warp. Every lane receives the value it would have observed in lane order, see
+/// [`warp_aggregated_atomic_add`].
+///
+/// # Safety
+/// `ptr` must be valid and aligned for atomic reads and writes
+pub unsafe fn warp_aggregated_atomic_min<T: AtomicMinMax>(ptr: *mut T, value: T) -> T {
+    let membermask = activemask();
+    let peers = address_peers(ptr, membermask);
+    let (total, prefix) = group_fold(value, peers, membermask, Ord::min);
+    let old = leader_atomic(total, peers, membermask, |total| unsafe {
+        T::atomic_min(ptr, total)
+    });
+    prefix.map_or(old, |prefix| old.min(prefix))
+}
+
+/// Atomically stores the maximum of `value` and `*ptr` with one `atom.max` per distinct address in
+/// the warp. Every lane receives the value it would have observed in lane order, see
+/// [`warp_aggregated_atomic_add`].
+///
+/// # Safety
+/// `ptr` must be valid and aligned for atomic reads and writes
+pub unsafe fn warp_aggregated_atomic_max<T: AtomicMinMax>(ptr: *mut T, value: T) -> T {
+    let membermask = activemask();
+    let peers = address_peers(ptr, membermask);
+    let (total, prefix) = group_fold(value, peers, membermask, Ord::max);
+    let old = leader_atomic(total, peers, membermask, |total| unsafe {
+        T::atomic_max(ptr, total)
+    });
+    prefix.map_or(old, |prefix| old.max(prefix))
+}
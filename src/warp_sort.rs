@@ -0,0 +1,99 @@
+//! Bitonic sorting networks over warp registers.
+//!
+//! `membermask` selects the participating lanes, it must name the lowest `2^n` lanes of the warp
+//! (e.g. `ALL_MEMBER_MASK` or `0xffff`). Results are ascending in lane order. Elements that compare
+//! unordered (NaNs) are never duplicated or lost, but their final position is unspecified.
+use crate::{
+    bitonic_exchange, bitonic_exchange_items, bitonic_keeps_min, bitonic_merge_distances,
+    bitonic_merge_pair, bitonic_sort_steps, bitonic_take_other, laneid, Shuffle,
+};
+
+fn network_width(membermask: u32) -> u32 {
+    let width = membermask.count_ones();
+    debug_assert!(width.is_power_of_two() && membermask.trailing_ones() == width);
+    width
+}
+
+/// sorts one value per lane across the lanes of `membermask`
+pub fn warp_bitonic_sort<T: Shuffle + PartialOrd + Copy>(value: T, membermask: u32) -> T {
+    let lane = laneid();
+    let mut value = value;
+    for (k, j) in bitonic_sort_steps(network_width(membermask)) {
+        let other = value.shfl_bfly(membermask, j);
+        value = bitonic_exchange(value, other, bitonic_keeps_min(lane, k, j));
+    }
+    value
+}
+
+/// sorts one key per lane across the lanes of `membermask`, moving each value along with its key
+pub fn warp_bitonic_sort_by_key<K: Shuffle + PartialOrd + Copy, V: Shuffle + Copy>(
+    key: K,
+    value: V,
+    membermask: u32,
+) -> (K, V) {
+    let lane = laneid();
+    let (mut key, mut value) = (key, value);
+    for (k, j) in bitonic_sort_steps(network_width(membermask)) {
+        let other_key = key.shfl_bfly(membermask, j);
+        let other_value = value.shfl_bfly(membermask, j);
+        if bitonic_take_other(&key, &other_key, bitonic_keeps_min(lane, k, j)) {
+            key = other_key;
+            value = other_value;
+        }
+    }
+    (key, value)
+}
+
+/// sorts `N` values per lane across the lanes of `membermask`, `N` must be a power of two.
+///
+/// The result is in a blocked arrangement, lane `l` holds the sorted elements `l * N..(l + 1) * N`.
+pub fn warp_bitonic_sort_items<T: Shuffle + PartialOrd + Copy, const N: usize>(
+    items: [T; N],
+    membermask: u32,
+) -> [T; N] {
+    debug_assert!(N.is_power_of_two());
+    let width = network_width(membermask);
+    let base = laneid() * N as u32;
+    let mut items = items;
+    for (k, j) in bitonic_sort_steps(width * N as u32) {
+        if (j as usize) < N {
+            bitonic_exchange_items(&mut items, base, k, j);
+        } else {
+            for (i, item) in items.iter_mut().enumerate() {
+                let other = item.shfl_bfly(membermask, j / N as u32);
+                *item = bitonic_exchange(*item, other, bitonic_keeps_min(base + i as u32, k, j));
+            }
+        }
+    }
+    items
+}
+
+/// merges two runs that are each sorted ascending across the lanes of `membermask`.
+///
+/// Returns the lower and upper half of the merged sequence, each again ascending across the lanes,
+/// so lane `l` receives the merged elements `l` and `width + l`.
+pub fn warp_merge<T: Shuffle + PartialOrd + Copy>(a: T, b: T, membermask: u32) -> (T, T) {
+    let width = network_width(membermask);
+    let lane = laneid();
+    // a followed by the reversal of b is bitonic, its first merge step pairs lane l of both halves
+    let (lo, hi) = bitonic_merge_pair(a, b.shfl_idx(membermask, width - 1 - lane));
+    (
+        bitonic_merge_lanes(lo, membermask, width, lane),
+        bitonic_merge_lanes(hi, membermask, width, lane),
+    )
+}
+
+/// sorts a bitonic sequence held one value per lane ascending
+fn bitonic_merge_lanes<T: Shuffle + PartialOrd + Copy>(
+    value: T,
+    membermask: u32,
+    width: u32,
+    lane: u32,
+) -> T {
+    let mut value = value;
+    for j in bitonic_merge_distances(width) {
+        let other = value.shfl_bfly(membermask, j);
+        value = bitonic_exchange(value, other, lane & j == 0);
+    }
+    value
+}
@@ -0,0 +1,448 @@
+use crate::Tf32;
+use core::marker::PhantomData;
+use core::mem::transmute;
+
+/*
+Warp-level matrix multiply-accumulate, the `wmma` API of CUDA C++
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#warp-level-matrix-instructions-wmma
+https://llvm.org/docs/NVPTXUsage.html
+
+Every operation here is executed by the whole warp, all lanes must call it with the same arguments.
+A fragment's registers hold an unspecified part of the matrix, only element-wise operations on `x`
+are meaningful.
+Only the fragments exist on the host, for the references of mma.rs, loads, stores and `mma` are device code.
+*/
+
+/// The `M x K` left-hand multiplicand
+pub struct MatrixA;
+/// The `K x N` right-hand multiplicand
+pub struct MatrixB;
+/// The `M x N` addend and result, its memory layout is picked when loading or storing
+pub struct Accumulator;
+
+/// Row-major layout marker of multiplicand fragments
+pub struct RowMajor;
+/// Column-major layout marker of multiplicand fragments
+pub struct ColMajor;
+
+/// Memory layout of an accumulator being loaded or stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    RowMajor,
+    ColMajor,
+}
+
+/// A register of two halves, the `<2 x half>` LLVM expects for f16 fragments
+#[repr(simd)]
+#[derive(Clone, Copy)]
+pub struct half2([f16; 2]);
+
+#[cfg(target_arch = "nvptx64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct Regs2<R>(R, R);
+#[cfg(target_arch = "nvptx64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct Regs4<R>(R, R, R, R);
+#[cfg(target_arch = "nvptx64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct Regs8<R>(R, R, R, R, R, R, R, R);
+
+/// Element types of fragments
+pub trait FragmentElement {
+    /// the type of the matrix elements in memory
+    type Value: Copy;
+    /// the type of the registers of a fragment
+    type Reg: Copy;
+    /// a register holding `value` in every element it packs
+    fn splat(value: Self::Value) -> Self::Reg;
+}
+
+impl FragmentElement for crate::Half {
+    type Value = Self;
+    type Reg = half2;
+    fn splat(value: Self) -> half2 {
+        unsafe { transmute(value.to_bits() as u32 * 0x1_0001) }
+    }
+}
+
+impl FragmentElement for crate::BHalf {
+    type Value = Self;
+    type Reg = u32;
+    fn splat(value: Self) -> u32 {
+        value.to_bits() as u32 * 0x1_0001
+    }
+}
+
+impl FragmentElement for Tf32 {
+    type Value = f32;
+    type Reg = u32;
+    fn splat(value: f32) -> u32 {
+        value.to_bits()
+    }
+}
+
+impl FragmentElement for i8 {
+    type Value = Self;
+    type Reg = u32;
+    fn splat(value: Self) -> u32 {
+        value as u8 as u32 * 0x0101_0101
+    }
+}
+
+impl FragmentElement for u8 {
+    type Value = Self;
+    type Reg = u32;
+    fn splat(value: Self) -> u32 {
+        value as u32 * 0x0101_0101
+    }
+}
+
+macro_rules! impl_element_identity {
+    ($($T: ty),+) => {
+        $(impl FragmentElement for $T {
+            type Value = Self;
+            type Reg = Self;
+            fn splat(value: Self) -> Self {
+                value
+            }
+        })+
+    };
+}
+
+impl_element_identity! { f32, i32, f64 }
+
+/// Implemented by `MatrixA`, `MatrixB` and `Accumulator` for every shape, element type and layout
+/// the hardware supports, which makes any other [`Fragment`] fail to compile
+pub trait FragmentUse<const M: usize, const N: usize, const K: usize, T, L> {
+    type Regs: Copy;
+}
+
+/// The part of an `M x N x K` matrix multiply-accumulate operand held by the executing lane.
+///
+/// Multiplicands carry their memory layout `L`, accumulators use `L = ()`.
+pub struct Fragment<Use, const M: usize, const N: usize, const K: usize, T, L = ()>
+where
+    Use: FragmentUse<M, N, K, T, L>,
+{
+    /// the registers of the executing lane, which elements they hold is unspecified
+    pub x: Use::Regs,
+    _marker: PhantomData<(T, L)>,
+}
+
+impl<Use, const M: usize, const N: usize, const K: usize, T, L> Fragment<Use, M, N, K, T, L>
+where
+    Use: FragmentUse<M, N, K, T, L>,
+{
+    pub const fn from_regs(x: Use::Regs) -> Self {
+        Self {
+            x,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Use, const M: usize, const N: usize, const K: usize, T, L> Clone
+    for Fragment<Use, M, N, K, T, L>
+where
+    Use: FragmentUse<M, N, K, T, L>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Use, const M: usize, const N: usize, const K: usize, T, L> Copy
+    for Fragment<Use, M, N, K, T, L>
+where
+    Use: FragmentUse<M, N, K, T, L>,
+{
+}
+
+/// Multiplicand fragments
+pub trait LoadMatrix: Sized {
+    type Value;
+    /// # Safety
+    /// `ptr` must be 32-byte aligned and valid for reads of the whole matrix with a leading dimension of
+    /// `ldm` elements, `ldm` must be a multiple of 16 bytes
+    unsafe fn load(ptr: *const Self::Value, ldm: u32) -> Self;
+}
+
+/// Accumulator fragments
+pub trait AccumulatorMatrix: Sized {
+    type Value;
+    /// # Safety
+    /// `ptr` must be 32-byte aligned and valid for reads of the whole matrix with a leading dimension of
+    /// `ldm` elements, `ldm` must be a multiple of 16 bytes
+    unsafe fn load(ptr: *const Self::Value, ldm: u32, layout: Layout) -> Self;
+    /// # Safety
+    /// `ptr` must be 32-byte aligned and valid for writes of the whole matrix with a leading dimension of
+    /// `ldm` elements, `ldm` must be a multiple of 16 bytes
+    unsafe fn store(&self, ptr: *mut Self::Value, ldm: u32, layout: Layout);
+}
+
+pub trait FillFragment {
+    type Value;
+    fn fill(value: Self::Value) -> Self;
+}
+
+/// `D = A * B + C` over fragments of the same shape, implemented by `A`
+pub trait Mma<B, C, D> {
+    fn mma(&self, b: &B, c: &C) -> D;
+}
+
+/// loads a multiplicand fragment from memory
+///
+/// # Safety
+/// see [`LoadMatrix::load`]
+pub unsafe fn load_matrix_sync<F: LoadMatrix>(fragment: &mut F, ptr: *const F::Value, ldm: u32) {
+    *fragment = unsafe { F::load(ptr, ldm) };
+}
+
+/// loads an accumulator fragment from memory in the given layout
+///
+/// # Safety
+/// see [`AccumulatorMatrix::load`]
+pub unsafe fn load_accumulator_sync<F: AccumulatorMatrix>(
+    fragment: &mut F,
+    ptr: *const F::Value,
+    ldm: u32,
+    layout: Layout,
+) {
+    *fragment = unsafe { F::load(ptr, ldm, layout) };
+}
+
+/// stores an accumulator fragment to memory in the given layout
+///
+/// # Safety
+/// see [`AccumulatorMatrix::store`]
+pub unsafe fn store_matrix_sync<F: AccumulatorMatrix>(
+    ptr: *mut F::Value,
+    fragment: &F,
+    ldm: u32,
+    layout: Layout,
+) {
+    unsafe { fragment.store(ptr, ldm, layout) }
+}
+
+/// sets every element of `fragment` to `value`
+pub fn fill_fragment<F: FillFragment>(fragment: &mut F, value: F::Value) {
+    *fragment = F::fill(value);
+}
+
+/// `d = a * b + c`
+pub fn mma_sync<A: Mma<B, C, D>, B, C, D>(d: &mut D, a: &A, b: &B, c: &C) {
+    *d = a.mma(b, c);
+}
+
+macro_rules! count {
+    () => { 0 };
+    ($head: ident $($tail: ident)*) => { 1 + count!($($tail)*) };
+}
+
+macro_rules! fragment {
+    ($Use: ident, $M: literal, $N: literal, $K: literal, $T: ty, $L: ty, [$($r: ident)+]) => {
+        impl FragmentUse<$M, $N, $K, $T, $L> for $Use {
+            type Regs = [<$T as FragmentElement>::Reg; count!($($r)+)];
+        }
+
+        impl FillFragment for Fragment<$Use, $M, $N, $K, $T, $L> {
+            type Value = <$T as FragmentElement>::Value;
+            fn fill(value: Self::Value) -> Self {
+                Self::from_regs([<$T as FragmentElement>::splat(value); count!($($r)+)])
+            }
+        }
+
+        impl Default for Fragment<$Use, $M, $N, $K, $T, $L> {
+            fn default() -> Self {
+                Self::fill(Default::default())
+            }
+        }
+    };
+}
+
+macro_rules! multiplicand {
+    ($Use: ident, $abc: literal, $M: literal, $N: literal, $K: literal, $T: ty, $ptx: literal, $Ret: ty, $regs: tt) => {
+        multiplicand!(@layout $Use, $abc, $M, $N, $K, $T, $ptx, $Ret, $regs, RowMajor, "row");
+        multiplicand!(@layout $Use, $abc, $M, $N, $K, $T, $ptx, $Ret, $regs, ColMajor, "col");
+    };
+    (@layout $Use: ident, $abc: literal, $M: literal, $N: literal, $K: literal, $T: ty, $ptx: literal, $Ret: ty, $regs: tt, $L: ident, $layout: literal) => {
+        fragment!($Use, $M, $N, $K, $T, $L, $regs);
+
+        #[cfg(target_arch = "nvptx64")]
+        impl LoadMatrix for Fragment<$Use, $M, $N, $K, $T, $L> {
+            type Value = <$T as FragmentElement>::Value;
+            unsafe fn load(ptr: *const Self::Value, ldm: u32) -> Self {
+                #[allow(improper_ctypes)]
+                extern "unadjusted" {
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".load.", $abc, ".", $layout, ".stride.", $ptx, ".p0")]
+                    fn load(ptr: *const <$T as FragmentElement>::Value, ldm: i32) -> $Ret;
+                }
+                let regs = unsafe { load(ptr, ldm as i32) };
+                Self::from_regs(unsafe { transmute::<$Ret, <$Use as FragmentUse<$M, $N, $K, $T, $L>>::Regs>(regs) })
+            }
+        }
+    };
+}
+
+macro_rules! accumulator {
+    ($M: literal, $N: literal, $K: literal, $T: ty, $ptx: literal, $Ret: ty, [$($r: ident)+]) => {
+        fragment!(Accumulator, $M, $N, $K, $T, (), [$($r)+]);
+
+        #[cfg(target_arch = "nvptx64")]
+        impl AccumulatorMatrix for Fragment<Accumulator, $M, $N, $K, $T> {
+            type Value = $T;
+
+            unsafe fn load(ptr: *const $T, ldm: u32, layout: Layout) -> Self {
+                #[allow(improper_ctypes)]
+                extern "unadjusted" {
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".load.c.row.stride.", $ptx, ".p0")]
+                    fn load_row(ptr: *const $T, ldm: i32) -> $Ret;
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".load.c.col.stride.", $ptx, ".p0")]
+                    fn load_col(ptr: *const $T, ldm: i32) -> $Ret;
+                }
+                let regs = match layout {
+                    Layout::RowMajor => unsafe { load_row(ptr, ldm as i32) },
+                    Layout::ColMajor => unsafe { load_col(ptr, ldm as i32) },
+                };
+                Self::from_regs(unsafe { transmute::<$Ret, <Accumulator as FragmentUse<$M, $N, $K, $T, ()>>::Regs>(regs) })
+            }
+
+            unsafe fn store(&self, ptr: *mut $T, ldm: u32, layout: Layout) {
+                #[allow(improper_ctypes)]
+                extern "unadjusted" {
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".store.d.row.stride.", $ptx, ".p0")]
+                    fn store_row(ptr: *mut $T, $($r: <$T as FragmentElement>::Reg,)+ ldm: i32);
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".store.d.col.stride.", $ptx, ".p0")]
+                    fn store_col(ptr: *mut $T, $($r: <$T as FragmentElement>::Reg,)+ ldm: i32);
+                }
+                let [$($r),+] = self.x;
+                match layout {
+                    Layout::RowMajor => unsafe { store_row(ptr, $($r,)+ ldm as i32) },
+                    Layout::ColMajor => unsafe { store_col(ptr, $($r,)+ ldm as i32) },
+                }
+            }
+        }
+    };
+}
+
+macro_rules! mma {
+    ($M: literal, $N: literal, $K: literal, $T: ty, $TC: ty => $TD: ty, $suffix: literal, $Ret: ty, $a: tt, $b: tt, $c: tt) => {
+        mma!(@layout $M, $N, $K, $T, $TC, $TD, $suffix, $Ret, $a, $b, $c, RowMajor, "row", RowMajor, "row");
+        mma!(@layout $M, $N, $K, $T, $TC, $TD, $suffix, $Ret, $a, $b, $c, RowMajor, "row", ColMajor, "col");
+        mma!(@layout $M, $N, $K, $T, $TC, $TD, $suffix, $Ret, $a, $b, $c, ColMajor, "col", RowMajor, "row");
+        mma!(@layout $M, $N, $K, $T, $TC, $TD, $suffix, $Ret, $a, $b, $c, ColMajor, "col", ColMajor, "col");
+    };
+    (@layout $M: literal, $N: literal, $K: literal, $T: ty, $TC: ty, $TD: ty, $suffix: literal, $Ret: ty, [$($a: ident)+], [$($b: ident)+], [$($c: ident)+], $LA: ident, $la: literal, $LB: ident, $lb: literal) => {
+        #[cfg(target_arch = "nvptx64")]
+        impl
+            Mma<
+                Fragment<MatrixB, $M, $N, $K, $T, $LB>,
+                Fragment<Accumulator, $M, $N, $K, $TC>,
+                Fragment<Accumulator, $M, $N, $K, $TD>,
+            > for Fragment<MatrixA, $M, $N, $K, $T, $LA>
+        {
+            fn mma(
+                &self,
+                b: &Fragment<MatrixB, $M, $N, $K, $T, $LB>,
+                c: &Fragment<Accumulator, $M, $N, $K, $TC>,
+            ) -> Fragment<Accumulator, $M, $N, $K, $TD> {
+                #[allow(improper_ctypes)]
+                extern "unadjusted" {
+                    #[link_name = concat!("llvm.nvvm.wmma.m", $M, "n", $N, "k", $K, ".mma.", $la, ".", $lb, $suffix)]
+                    fn mma(
+                        $($a: <$T as FragmentElement>::Reg,)+
+                        $($b: <$T as FragmentElement>::Reg,)+
+                        $($c: <$TC as FragmentElement>::Reg,)+
+                    ) -> $Ret;
+                }
+                let [$($a),+] = self.x;
+                let [$($b),+] = b.x;
+                let [$($c),+] = c.x;
+                let regs = unsafe { mma($($a,)+ $($b,)+ $($c,)+) };
+                Fragment::from_regs(unsafe { transmute::<$Ret, <Accumulator as FragmentUse<$M, $N, $K, $TD, ()>>::Regs>(regs) })
+            }
+        }
+    };
+}
+
+// f16 multiplicands, f16 or f32 accumulators, sm_70
+multiplicand!(MatrixA, "a", 16, 16, 16, crate::Half, "f16", Regs8<half2>, [a0 a1 a2 a3 a4 a5 a6 a7]);
+multiplicand!(MatrixB, "b", 16, 16, 16, crate::Half, "f16", Regs8<half2>, [b0 b1 b2 b3 b4 b5 b6 b7]);
+multiplicand!(MatrixA, "a", 32, 8, 16, crate::Half, "f16", Regs8<half2>, [a0 a1 a2 a3 a4 a5 a6 a7]);
+multiplicand!(MatrixB, "b", 32, 8, 16, crate::Half, "f16", Regs8<half2>, [b0 b1 b2 b3 b4 b5 b6 b7]);
+multiplicand!(MatrixA, "a", 8, 32, 16, crate::Half, "f16", Regs8<half2>, [a0 a1 a2 a3 a4 a5 a6 a7]);
+multiplicand!(MatrixB, "b", 8, 32, 16, crate::Half, "f16", Regs8<half2>, [b0 b1 b2 b3 b4 b5 b6 b7]);
+accumulator!(16, 16, 16, crate::Half, "f16", Regs4<half2>, [c0 c1 c2 c3]);
+accumulator!(32, 8, 16, crate::Half, "f16", Regs4<half2>, [c0 c1 c2 c3]);
+accumulator!(8, 32, 16, crate::Half, "f16", Regs4<half2>, [c0 c1 c2 c3]);
+accumulator!(16, 16, 16, f32, "f32", Regs8<f32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+accumulator!(32, 8, 16, f32, "f32", Regs8<f32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+accumulator!(8, 32, 16, f32, "f32", Regs8<f32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+
+macro_rules! mma_f16 {
+    ($($M: literal, $N: literal, $K: literal);+) => {
+        $(mma!($M, $N, $K, crate::Half, crate::Half => crate::Half, ".f16.f16", Regs4<half2>,
+            [a0 a1 a2 a3 a4 a5 a6 a7], [b0 b1 b2 b3 b4 b5 b6 b7], [c0 c1 c2 c3]);
+        mma!($M, $N, $K, crate::Half, crate::Half => f32, ".f32.f16", Regs8<f32>,
+            [a0 a1 a2 a3 a4 a5 a6 a7], [b0 b1 b2 b3 b4 b5 b6 b7], [c0 c1 c2 c3]);
+        mma!($M, $N, $K, crate::Half, f32 => crate::Half, ".f16.f32", Regs4<half2>,
+            [a0 a1 a2 a3 a4 a5 a6 a7], [b0 b1 b2 b3 b4 b5 b6 b7], [c0 c1 c2 c3 c4 c5 c6 c7]);
+        mma!($M, $N, $K, crate::Half, f32 => f32, ".f32.f32", Regs8<f32>,
+            [a0 a1 a2 a3 a4 a5 a6 a7], [b0 b1 b2 b3 b4 b5 b6 b7], [c0 c1 c2 c3 c4 c5 c6 c7]);)+
+    };
+}
+
+mma_f16! { 16, 16, 16; 32, 8, 16; 8, 32, 16 }
+
+// bf16 multiplicands, f32 accumulators, sm_80
+multiplicand!(MatrixA, "a", 16, 16, 16, crate::BHalf, "bf16", Regs4<u32>, [a0 a1 a2 a3]);
+multiplicand!(MatrixB, "b", 16, 16, 16, crate::BHalf, "bf16", Regs4<u32>, [b0 b1 b2 b3]);
+multiplicand!(MatrixA, "a", 32, 8, 16, crate::BHalf, "bf16", Regs8<u32>, [a0 a1 a2 a3 a4 a5 a6 a7]);
+multiplicand!(MatrixB, "b", 32, 8, 16, crate::BHalf, "bf16", Regs2<u32>, [b0 b1]);
+multiplicand!(MatrixA, "a", 8, 32, 16, crate::BHalf, "bf16", Regs2<u32>, [a0 a1]);
+multiplicand!(MatrixB, "b", 8, 32, 16, crate::BHalf, "bf16", Regs8<u32>, [b0 b1 b2 b3 b4 b5 b6 b7]);
+mma!(16, 16, 16, crate::BHalf, f32 => f32, ".bf16", Regs8<f32>,
+    [a0 a1 a2 a3], [b0 b1 b2 b3], [c0 c1 c2 c3 c4 c5 c6 c7]);
+mma!(32, 8, 16, crate::BHalf, f32 => f32, ".bf16", Regs8<f32>,
+    [a0 a1 a2 a3 a4 a5 a6 a7], [b0 b1], [c0 c1 c2 c3 c4 c5 c6 c7]);
+mma!(8, 32, 16, crate::BHalf, f32 => f32, ".bf16", Regs8<f32>,
+    [a0 a1], [b0 b1 b2 b3 b4 b5 b6 b7], [c0 c1 c2 c3 c4 c5 c6 c7]);
+
+// tf32 multiplicands, f32 accumulators, sm_80
+multiplicand!(MatrixA, "a", 16, 16, 8, Tf32, "tf32", Regs4<u32>, [a0 a1 a2 a3]);
+multiplicand!(MatrixB, "b", 16, 16, 8, Tf32, "tf32", Regs4<u32>, [b0 b1 b2 b3]);
+accumulator!(16, 16, 8, f32, "f32", Regs8<f32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+mma!(16, 16, 8, Tf32, f32 => f32, ".tf32", Regs8<f32>,
+    [a0 a1 a2 a3], [b0 b1 b2 b3], [c0 c1 c2 c3 c4 c5 c6 c7]);
+
+// 8-bit integer multiplicands, i32 accumulators, sm_72
+macro_rules! integer {
+    ($($T: ty, $ptx: literal, $suffix: literal);+) => {
+        $(multiplicand!(MatrixA, "a", 16, 16, 16, $T, $ptx, Regs2<u32>, [a0 a1]);
+        multiplicand!(MatrixB, "b", 16, 16, 16, $T, $ptx, Regs2<u32>, [b0 b1]);
+        multiplicand!(MatrixA, "a", 32, 8, 16, $T, $ptx, Regs4<u32>, [a0 a1 a2 a3]);
+        multiplicand!(MatrixB, "b", 32, 8, 16, $T, $ptx, u32, [b0]);
+        multiplicand!(MatrixA, "a", 8, 32, 16, $T, $ptx, u32, [a0]);
+        multiplicand!(MatrixB, "b", 8, 32, 16, $T, $ptx, Regs4<u32>, [b0 b1 b2 b3]);
+        mma!(16, 16, 16, $T, i32 => i32, $suffix, Regs8<i32>,
+            [a0 a1], [b0 b1], [c0 c1 c2 c3 c4 c5 c6 c7]);
+        mma!(32, 8, 16, $T, i32 => i32, $suffix, Regs8<i32>,
+            [a0 a1 a2 a3], [b0], [c0 c1 c2 c3 c4 c5 c6 c7]);
+        mma!(8, 32, 16, $T, i32 => i32, $suffix, Regs8<i32>,
+            [a0], [b0 b1 b2 b3], [c0 c1 c2 c3 c4 c5 c6 c7]);)+
+    };
+}
+
+integer! { i8, "s8", ".s8"; u8, "u8", ".u8" }
+accumulator!(16, 16, 16, i32, "s32", Regs8<i32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+accumulator!(32, 8, 16, i32, "s32", Regs8<i32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+accumulator!(8, 32, 16, i32, "s32", Regs8<i32>, [c0 c1 c2 c3 c4 c5 c6 c7]);
+
+// f64 multiplicands and accumulators, sm_80
+multiplicand!(MatrixA, "a", 8, 8, 4, f64, "f64", f64, [a0]);
+multiplicand!(MatrixB, "b", 8, 8, 4, f64, "f64", f64, [b0]);
+accumulator!(8, 8, 4, f64, "f64", Regs2<f64>, [c0 c1]);
+mma!(8, 8, 4, f64, f64 => f64, ".f64", Regs2<f64>, [a0], [b0], [c0 c1]);
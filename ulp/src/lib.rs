@@ -0,0 +1,571 @@
+#![feature(core_intrinsics, f16, repr_simd)]
+#![allow(internal_features, non_camel_case_types)]
+// the device calls are unsafe, their host models & newer nightlies' float intrinsics aren't
+#![allow(unused_unsafe)]
+#![deny(unsafe_op_in_unsafe_fn)]
+
+/*
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#floating-point-instructions
+https://docs.nvidia.com/cuda/parallel-thread-execution/index.html#half-precision-floating-point-instructions
+
+The ulp error of every approximated `FastNum` function, measured on the host with the float modules of
+nvptx-sys compiled as they are, over the host models of the PTX approximations in `float.rs`, which sit at
+the error the PTX ISA allows, so the table is what the device may do at worst.
+f16 & bf16 sweep every input, f32 every 65537th bit pattern, or every one with `--exhaustive`, and f64
+pseudo random bit patterns, half of them with exponents within 2^±64, two argument functions take
+pseudo random pairs, fast-math division only finite ones. The reference is the
+correctly rounded result of the f64 function, for f64 libm's, which is itself within 1 ulp. Like the f32
+approximations, the f32 reference flushes subnormal inputs and, where the function does, results.
+Errors are ulps between the bit patterns, with `float_eq` for f32 & f64, NaN against a number is
+counted apart.
+The large errors are expected: sin, cos & lg2 only bound the absolute error, so results near zero may have
+any sign, the f32 division flushes the quotient of a divisor above 2^126, the f64 division & reciprocal
+flush where the reciprocal is subnormal, and sigmoid, gelu & pow inherit their argument's rounding,
+amplified. f16's ex2 &
+tanh.approx.f16 aren't modeled, the host rounds them correctly.
+The special functions of special.rs only exist for f32 & f64, they are measured against libm's, the
+inverses of erf & erfc against their approximation refined by Newton's method on libm's erf & erfc.
+libm's Bessel functions, like ours, only bound the absolute error near the zeros of y0 & y1 and beyond 8,
+and near the zeros of j0 & j1 below 8, where ours factor them out, so those rows are mostly libm's error.
+
+    cargo run -p nvptx-sys-ulp --release > ulp/table.txt
+*/
+
+#[path = "../../src/approx_f64.rs"]
+mod approx_f64;
+/// the compare-exchange steps of warp_sort.rs, for tests/warp_sort.rs
+#[path = "../../src/bitonic.rs"]
+mod bitonic;
+/// FastComplex, for tests/complex.rs, without nvptx-sys's num-complex conversions
+#[allow(unexpected_cfgs)]
+#[path = "../../src/complex.rs"]
+mod complex;
+/// the fast divisor, for tests/divisor.rs
+#[path = "../../src/divisor.rs"]
+mod divisor;
+#[path = "../../src/float.rs"]
+mod float;
+/// the software f16 & bf16, for tests/half.rs
+#[path = "../../src/half.rs"]
+mod half;
+/// the host reference of the integer instructions, for tests/integer.rs
+#[path = "../../src/integer.rs"]
+mod integer;
+/// the vectors, quaternions & matrices, for tests/linalg.rs
+#[path = "../../src/linalg.rs"]
+mod linalg;
+/// the software fp8 & tf32 conversions, for tests/minifloat.rs and the tf32 of mma.rs
+#[path = "../../src/minifloat.rs"]
+mod minifloat;
+/// the lane layouts and references of `mma.sync`, for tests/mma.rs
+#[path = "../../src/mma.rs"]
+mod mma;
+#[path = "../../src/packed_half.rs"]
+mod packed_half;
+/// the radix keys & digit ranks of BlockRadixSort, for tests/radix.rs
+#[path = "../../src/radix.rs"]
+mod radix;
+/// the host model of the rounding modes, for tests/rounding.rs
+#[path = "../../src/rounding.rs"]
+mod rounding;
+/// the thread & warp steps of BlockScan, for tests/scan.rs
+#[path = "../../src/scan.rs"]
+mod scan;
+#[path = "../../src/special.rs"]
+mod special;
+/// the host reference of the carry chains, for tests/uint.rs
+#[path = "../../src/uint.rs"]
+mod uint;
+/// the fragments of mma.rs
+#[path = "../../src/wmma.rs"]
+mod wmma;
+
+pub use crate::bitonic::*;
+pub use crate::complex::*;
+pub use crate::divisor::*;
+pub use crate::float::*;
+pub use crate::half::*;
+pub use crate::integer::*;
+pub use crate::linalg::*;
+pub use crate::minifloat::*;
+pub use crate::mma::*;
+pub use crate::packed_half::*;
+pub use crate::radix::*;
+pub use crate::rounding::*;
+pub use crate::scan::*;
+pub use crate::special::*;
+pub use crate::uint::*;
+pub use crate::wmma::*;
+use float_eq::AssertFloatEq;
+use std::fmt;
+
+/// lib.rs's, the lanes mma.rs spreads fragments over
+pub const WARP_SIZE: u32 = 32;
+
+/// A float type under test, with its correctly rounded conversion from f64
+pub trait Sample: FastNum {
+    const NAME: &'static str;
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+    /// `.ftz`, only the f32 approximations flush
+    fn flush(self) -> Self {
+        self
+    }
+    /// None if only one is NaN
+    fn ulps(self, other: Self) -> Option<u64>;
+}
+
+impl Sample for f32 {
+    const NAME: &'static str = "f32";
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn flush(self) -> Self {
+        if self.is_subnormal() {
+            0.0f32.copysign(self)
+        } else {
+            self
+        }
+    }
+
+    fn ulps(self, other: Self) -> Option<u64> {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => Some(0),
+            (false, false) => Some(match self.debug_ulps_diff(&other) {
+                Some(ulps) => ulps as u64,
+                // across zero
+                None => {
+                    let to_zero = |x: f32| x.debug_ulps_diff(&0.0f32.copysign(x)).unwrap() as u64;
+                    to_zero(self) + to_zero(other)
+                }
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Sample for f64 {
+    const NAME: &'static str = "f64";
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn ulps(self, other: Self) -> Option<u64> {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => Some(0),
+            (false, false) => Some(match self.debug_ulps_diff(&other) {
+                Some(ulps) => ulps,
+                None => {
+                    let to_zero = |x: f64| x.debug_ulps_diff(&0.0f64.copysign(x)).unwrap();
+                    to_zero(self) + to_zero(other)
+                }
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// rounds to f32 with round to odd, so rounding that again to a half is correctly rounded
+fn round_to_odd(value: f64) -> f32 {
+    let rounded = value as f32;
+    if (rounded as f64) == value || rounded.is_infinite() || rounded.to_bits() & 1 == 1 {
+        rounded
+    } else if (rounded as f64).abs() < value.abs() {
+        f32::from_bits(rounded.to_bits() + 1)
+    } else {
+        f32::from_bits(rounded.to_bits() - 1)
+    }
+}
+
+/// ulps between half bit patterns, which order like sign & magnitude integers
+fn half_ulps(a: u16, b: u16, a_nan: bool, b_nan: bool) -> Option<u64> {
+    let key = |bits: u16| {
+        if bits & 0x8000 != 0 {
+            -((bits & 0x7fff) as i64)
+        } else {
+            bits as i64
+        }
+    };
+    match (a_nan, b_nan) {
+        (true, true) => Some(0),
+        (false, false) => Some(key(a).abs_diff(key(b))),
+        _ => None,
+    }
+}
+
+macro_rules! half_sample {
+    ($($F:ident, $name:literal, $flush:expr);+) => {
+        $(impl Sample for $F {
+            const NAME: &'static str = $name;
+
+            fn to_f64(self) -> f64 {
+                self.to_f32() as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                $F::from_f32(round_to_odd(value))
+            }
+
+            fn flush(self) -> Self {
+                $flush(self)
+            }
+
+            fn ulps(self, other: Self) -> Option<u64> {
+                half_ulps(self.to_bits(), other.to_bits(), Float::is_nan(self), Float::is_nan(other))
+            }
+        })+
+    };
+}
+
+// bf16 has f32's exponent, its subnormals are flushed by the f32 approximations
+half_sample!(
+    Half, "f16", |x| x;
+    BHalf, "bf16", |x: BHalf| if x.to_f32().is_subnormal() { BHalf::from_bits(x.to_bits() & 0x8000) } else { x }
+);
+
+/// A `FastNum` function and its f64 reference, `approx` & `exact` ignore `y` for one argument
+pub struct Function<F> {
+    pub name: &'static str,
+    pub binary: bool,
+    /// whether the f32 approximation flushes subnormal results
+    pub ftz: bool,
+    /// fast-math arithmetic, only defined for finite operands and results
+    pub finite: bool,
+    pub approx: fn(F, F) -> F,
+    pub exact: fn(f64, f64) -> f64,
+}
+
+fn gelu(x: f64) -> f64 {
+    // x / 2 (1 + tanh(u)) = x sigmoid(2u), without the cancellation for negative x
+    let u = (2.0 / core::f64::consts::PI).sqrt() * (x + 0.044715 * x * x * x);
+    x / (1.0 + libm::exp(-2.0 * u))
+}
+
+fn softplus(x: f64) -> f64 {
+    x.max(0.0) + libm::log1p(libm::exp(-x.abs()))
+}
+
+pub fn functions<F: FastNum>() -> Vec<Function<F>> {
+    macro_rules! unary {
+        ($name:literal, $ftz:literal, $approx:expr, $exact:expr) => {
+            Function {
+                name: $name,
+                binary: false,
+                ftz: $ftz,
+                finite: false,
+                approx: |x: F, _: F| $approx(x),
+                exact: |x: f64, _: f64| $exact(x),
+            }
+        };
+    }
+    vec![
+        Function {
+            name: "div",
+            binary: true,
+            ftz: false,
+            finite: true,
+            approx: |x: F, y: F| x.fast_div(y),
+            exact: |x: f64, y: f64| x / y,
+        },
+        unary!("recip", true, F::fast_recip, |x: f64| 1.0 / x),
+        unary!("sqrt", true, F::fast_sqrt, libm::sqrt),
+        unary!("rsqrt", true, F::fast_rsqrt, |x: f64| 1.0 / libm::sqrt(x)),
+        unary!("sin", true, F::fast_sin, libm::sin),
+        unary!("cos", true, F::fast_cos, libm::cos),
+        unary!("sin_reduced", true, F::fast_sin_reduced, libm::sin),
+        unary!("cos_reduced", true, F::fast_cos_reduced, libm::cos),
+        unary!("sincos.0", true, |x: F| x.fast_sincos().0, libm::sin),
+        unary!("sincos.1", true, |x: F| x.fast_sincos().1, libm::cos),
+        unary!("tanh", false, F::fast_tanh, libm::tanh),
+        unary!("sigmoid", true, F::fast_sigmoid, |x: f64| 1.0
+            / (1.0 + libm::exp(-x))),
+        unary!("gelu", false, F::fast_gelu, gelu),
+        unary!("softplus", true, F::fast_softplus, softplus),
+        unary!("log2", true, F::fast_log2, libm::log2),
+        unary!("exp2", true, F::fast_exp2, libm::exp2),
+        unary!("log10", true, F::fast_log10, libm::log10),
+        unary!("ln", true, F::fast_ln, libm::log),
+        unary!("exp", true, F::fast_exp, libm::exp),
+        Function {
+            name: "pow",
+            binary: true,
+            ftz: true,
+            finite: false,
+            approx: |x: F, y: F| x.fast_pow(y),
+            // 2^(y log2(x)), NaN for negative x
+            exact: |x: f64, y: f64| {
+                if x < 0.0 {
+                    f64::NAN
+                } else {
+                    libm::pow(x.abs(), y)
+                }
+            },
+        },
+        Function {
+            name: "atan2",
+            binary: true,
+            ftz: true,
+            finite: false,
+            approx: |y: F, x: F| y.fast_atan2(x),
+            exact: libm::atan2,
+        },
+    ]
+}
+
+const SQRT_PI_2: f64 = 0.886226925452758;
+
+/// the z with erfc(z) = y, the f64 approximation refined by Newton's method on ln(erfc(z)), which keeps
+/// the step finite where erfc(z) is tiny
+fn erfcinv(y: f64) -> f64 {
+    if y > 1.0 {
+        // exact
+        return -erfcinv(2.0 - y);
+    }
+    let mut z = y.erfcinv();
+    for _ in 0..3 {
+        let e = libm::erfc(z);
+        if !z.is_finite() || e == 0.0 {
+            break;
+        }
+        let ln_e = libm::log(e);
+        z += (ln_e - libm::log(y)) * SQRT_PI_2 * libm::exp(z * z + ln_e);
+    }
+    z
+}
+
+/// the z with erf(z) = x, Newton's method on erf near zero, erfcinv(1 - |x|), which is exact, further out
+fn erfinv(x: f64) -> f64 {
+    if x.abs() >= 0.5 {
+        return erfcinv(1.0 - x.abs()).copysign(x);
+    }
+    let mut z = x.erfinv();
+    for _ in 0..3 {
+        z += (x - libm::erf(z)) * SQRT_PI_2 * libm::exp(z * z);
+    }
+    z
+}
+
+/// erfc(-x / sqrt(2)) / 2 with the rounding error of the argument, which erfc amplifies by 2z, carried
+fn normcdf(x: f64) -> f64 {
+    use core::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI};
+    let z = -x * FRAC_1_SQRT_2;
+    let z_lo = libm::fma(-x, FRAC_1_SQRT_2, -z) - x * -4.833646656726457e-17;
+    0.5 * (libm::erfc(z) - z_lo * FRAC_2_SQRT_PI * libm::exp(-z * z))
+}
+
+/// (sin(πx), cos(πx)) of x = 2k + n / 2 + r, all exact, |r| <= 1/4
+fn sincospi(x: f64) -> (f64, f64) {
+    use core::f64::consts::PI;
+    if !x.is_finite() {
+        return (f64::NAN, f64::NAN);
+    }
+    let m = x % 2.0;
+    let n = (m * 2.0).round_ties_even();
+    let r = m - n * 0.5;
+    let (s, c) = (libm::sin(PI * r), libm::cos(PI * r));
+    let (s, c) = match (n % 4.0 + 4.0) % 4.0 {
+        0.0 => (s, c),
+        1.0 => (c, -s),
+        2.0 => (-s, -c),
+        _ => (-c, s),
+    };
+    // the zeros of sin have the sign of x, those of cos are +0
+    (
+        if x == x.round() {
+            0.0f64.copysign(x)
+        } else {
+            s
+        },
+        c + 0.0,
+    )
+}
+
+/// The special functions of f32 & f64 and their f64 references
+pub fn special_functions<F: SpecialFn>() -> Vec<Function<F>> {
+    macro_rules! unary {
+        ($name:literal, $approx:expr, $exact:expr) => {
+            Function {
+                name: $name,
+                binary: false,
+                ftz: false,
+                finite: false,
+                approx: |x: F, _: F| $approx(x),
+                exact: |x: f64, _: f64| $exact(x),
+            }
+        };
+    }
+    vec![
+        unary!("erf", F::erf, libm::erf),
+        unary!("erfc", F::erfc, libm::erfc),
+        unary!("erfinv", F::erfinv, erfinv),
+        unary!("erfcinv", F::erfcinv, erfcinv),
+        unary!("lgamma", F::lgamma, libm::lgamma),
+        unary!("tgamma", F::tgamma, libm::tgamma),
+        unary!("normcdf", F::normcdf, normcdf),
+        unary!("normcdfinv", F::normcdfinv, |p: f64| {
+            -core::f64::consts::SQRT_2 * erfcinv(p + p)
+        }),
+        unary!("sinpi", F::sinpi, |x| sincospi(x).0),
+        unary!("cospi", F::cospi, |x| sincospi(x).1),
+        unary!("rcbrt", F::rcbrt, |x: f64| 1.0 / libm::cbrt(x)),
+        unary!("j0", F::j0, libm::j0),
+        unary!("j1", F::j1, libm::j1),
+        unary!("y0", F::y0, libm::y0),
+        unary!("y1", F::y1, libm::y1),
+    ]
+}
+
+/// The error of one function over one type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub name: String,
+    pub ty: String,
+    pub max_ulps: u64,
+    pub mean_ulps: f64,
+    /// inputs where exactly one of the approximation and the reference is NaN
+    pub nan: u64,
+    /// an input with the maximum error
+    pub worst: f64,
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12} {:<5} {:>12} {:>12.4} {:>8} {:e}",
+            self.name, self.ty, self.max_ulps, self.mean_ulps, self.nan, self.worst
+        )
+    }
+}
+
+impl Row {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        Some(Self {
+            name: fields.next()?.into(),
+            ty: fields.next()?.into(),
+            max_ulps: fields.next()?.parse().ok()?,
+            mean_ulps: fields.next()?.parse().ok()?,
+            nan: fields.next()?.parse().ok()?,
+            worst: fields.next()?.parse().ok()?,
+        })
+    }
+}
+
+pub const HEADER: &str = "# function   type      max ulp     mean ulp      NaN worst input";
+
+/// xorshift64, the pseudo random inputs are the same on every run
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// pseudo random words, the same every run, for the tests of the integer operations
+pub fn words(n: usize) -> impl Iterator<Item = u64> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    (0..n).map(move |_| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state ^ state >> 29
+    })
+}
+
+pub const SAMPLES: usize = 65536;
+
+pub fn measure<F: Sample>(
+    inputs: &[F],
+    functions: impl IntoIterator<Item = Function<F>>,
+) -> Vec<Row> {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    functions
+        .into_iter()
+        .map(|function| {
+            let (mut max_ulps, mut sum, mut count, mut nan, mut worst) = (0, 0.0, 0u64, 0, 0.0);
+            let mut pairs: Box<dyn Iterator<Item = (F, F)>> = if function.binary {
+                Box::new(
+                    inputs
+                        .iter()
+                        .map(|&x| (x, inputs[rng.next_u64() as usize % inputs.len()])),
+                )
+            } else {
+                Box::new(inputs.iter().map(|&x| (x, x)))
+            };
+            for (x, y) in &mut pairs {
+                let (x, y) = (x.flush(), y.flush());
+                let exact = F::from_f64((function.exact)(x.to_f64(), y.to_f64()));
+                if function.finite && ![x, y, exact].into_iter().all(Float::is_finite) {
+                    continue;
+                }
+                let exact = if function.ftz { exact.flush() } else { exact };
+                match (function.approx)(x, y).ulps(exact) {
+                    Some(ulps) => {
+                        if ulps > max_ulps {
+                            max_ulps = ulps;
+                            worst = x.to_f64();
+                        }
+                        sum += ulps as f64;
+                        count += 1;
+                    }
+                    None => nan += 1,
+                }
+            }
+            Row {
+                name: function.name.into(),
+                ty: F::NAME.into(),
+                max_ulps,
+                mean_ulps: sum / count.max(1) as f64,
+                nan,
+                worst,
+            }
+        })
+        .collect()
+}
+
+/// every row of the table, f32 exhaustively or every 65537th bit pattern
+pub fn table(exhaustive: bool) -> Vec<Row> {
+    let f16s: Vec<Half> = (0..=u16::MAX).map(Half::from_bits).collect();
+    let bf16s: Vec<BHalf> = (0..=u16::MAX).map(BHalf::from_bits).collect();
+    let f32s: Vec<f32> = (0..=u32::MAX)
+        .step_by(if exhaustive { 1 } else { 65537 })
+        .map(f32::from_bits)
+        .collect();
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    // half any bit pattern, half with exponents within 2^±64
+    let f64s: Vec<f64> = (0..SAMPLES)
+        .map(|i| match rng.next_u64() {
+            bits if i % 2 == 0 => f64::from_bits(bits),
+            bits => {
+                f64::from_bits((bits & 0x800f_ffff_ffff_ffff) | ((bits >> 52) % 129 + 959) << 52)
+            }
+        })
+        .collect();
+    let mut rows = measure(&f16s, functions());
+    rows.extend(measure(&bf16s, functions()));
+    rows.extend(measure(
+        &f32s,
+        functions().into_iter().chain(special_functions()),
+    ));
+    rows.extend(measure(
+        &f64s,
+        functions().into_iter().chain(special_functions()),
+    ));
+    rows
+}
@@ -0,0 +1,10 @@
+use nvptx_sys_ulp::{table, HEADER};
+
+/// prints the table, `--exhaustive` sweeps every f32
+fn main() {
+    let exhaustive = std::env::args().any(|arg| arg == "--exhaustive");
+    println!("{HEADER}");
+    for row in table(exhaustive) {
+        println!("{row}");
+    }
+}
@@ -0,0 +1,173 @@
+use nvptx_sys_ulp::*;
+
+/// (re, im) of the f64 references, from the polar form where that is independent of complex.rs
+type Exact = (f64, f64);
+
+fn polar(r: f64, theta: f64) -> Exact {
+    (r * libm::cos(theta), r * libm::sin(theta))
+}
+
+/// pseudo random values with both signs and exponents within 2^±`range`
+fn values<F: Sample>(n: usize, range: u64) -> Vec<FastComplex<F>> {
+    let component = |x: u64| {
+        let sign = if x & 1 == 0 { 1.0 } else { -1.0 };
+        let exponent = (x >> 1) % (2 * range + 1);
+        let mantissa = 1.0 + (x >> 32) as f64 / 2f64.powi(32);
+        F::from_f64(sign * mantissa * 2f64.powi(exponent as i32 - range as i32))
+    };
+    let words: Vec<u64> = words(2 * n).collect();
+    words
+        .chunks(2)
+        .map(|x| FastComplex::new(component(x[0]), component(x[1])))
+        .collect()
+}
+
+/// within `tol` of the reference, relative to its magnitude plus `floor`
+fn assert_close<F: Sample>(z: FastComplex<F>, exact: Exact, tol: f64, floor: f64, what: &str) {
+    let (re, im) = (z.re.to_f64(), z.im.to_f64());
+    let error = libm::hypot(re - exact.0, im - exact.1);
+    let bound = tol * (libm::hypot(exact.0, exact.1) + floor);
+    assert!(
+        error <= bound,
+        "{what}: ({re:e}, {im:e}) against ({:e}, {:e})",
+        exact.0,
+        exact.1
+    );
+}
+
+/// the tolerance of the approximate f32 primitives, and of the f64 ones
+fn tol<F: Sample>() -> f64 {
+    if F::NAME == "f32" {
+        2e-6
+    } else {
+        2e-15
+    }
+}
+
+fn parts<F: Sample>(z: FastComplex<F>) -> (f64, f64) {
+    (z.re.to_f64(), z.im.to_f64())
+}
+
+fn check_sqrt<F: Sample>() {
+    for z in values::<F>(10000, 60) {
+        let (re, im) = parts(z);
+        let exact = polar(libm::sqrt(libm::hypot(re, im)), libm::atan2(im, re) / 2.0);
+        assert_close(
+            z.sqrt(),
+            exact,
+            tol::<F>(),
+            0.0,
+            &format!("sqrt({re:e}, {im:e})"),
+        );
+    }
+    // both branches of the sign of re, and the sign of im on the negative real axis
+    let cases = [
+        ((3.0, 4.0), (2.0, 1.0)),
+        ((3.0, -4.0), (2.0, -1.0)),
+        ((-3.0, 4.0), (1.0, 2.0)),
+        ((-3.0, -4.0), (1.0, -2.0)),
+        ((4.0, 0.0), (2.0, 0.0)),
+        ((-4.0, 0.0), (0.0, 2.0)),
+        ((-4.0, -0.0), (0.0, -2.0)),
+        ((0.0, 8.0), (2.0, 2.0)),
+        ((-0.0, -8.0), (2.0, -2.0)),
+    ];
+    for ((re, im), exact) in cases {
+        let z = FastComplex::new(F::from_f64(re), F::from_f64(im)).sqrt();
+        assert_close(z, exact, tol::<F>(), 0.0, &format!("sqrt({re}, {im})"));
+        assert!(z.re.to_f64() >= 0.0, "sqrt({re}, {im})");
+        assert_eq!(
+            z.im.to_f64().is_sign_negative(),
+            im.is_sign_negative(),
+            "sqrt({re}, {im})"
+        );
+    }
+    // the zeros keep the sign of im
+    for (re, im) in [(0.0, 0.0), (-0.0, 0.0), (0.0, -0.0), (-0.0, -0.0)] {
+        let z = FastComplex::new(F::from_f64(re), F::from_f64(im)).sqrt();
+        assert_eq!(z.re.to_f64(), 0.0, "sqrt({re}, {im})");
+        assert_eq!(z.im.to_f64().to_bits(), im.to_bits(), "sqrt({re}, {im})");
+    }
+}
+
+fn check_powi<F: Sample>() {
+    for (i, z) in values::<F>(10000, 4).into_iter().enumerate() {
+        let (re, im) = parts(z);
+        let n = (i % 17) as i32 - 8;
+        let exact = polar(
+            libm::pow(libm::hypot(re, im), n as f64),
+            n as f64 * libm::atan2(im, re),
+        );
+        let tol = tol::<F>() * (n.abs() + 1) as f64;
+        assert_close(z.powi(n), exact, tol, 0.0, &format!("({re:e}, {im:e})^{n}"));
+    }
+    let z = FastComplex::new(F::from_f64(0.0), F::from_f64(2.0));
+    // i^-1 = -i, (2i)^-2 = -1 / 4
+    assert_close(z.powi(-1), (0.0, -0.5), tol::<F>(), 0.0, "(2i)^-1");
+    assert_close(z.powi(-2), (-0.25, 0.0), tol::<F>(), 0.0, "(2i)^-2");
+    assert_close(z.powi(0), (1.0, 0.0), 0.0, 0.0, "(2i)^0");
+}
+
+fn check_ln<F: Sample>() {
+    for z in values::<F>(10000, 60) {
+        let (re, im) = parts(z);
+        let exact = (libm::log(libm::hypot(re, im)), libm::atan2(im, re));
+        // ln|z| near 0 is only accurate in absolute terms
+        assert_close(
+            z.ln(),
+            exact,
+            tol::<F>(),
+            1.0,
+            &format!("ln({re:e}, {im:e})"),
+        );
+    }
+    let minus_one = FastComplex::new(F::from_f64(-1.0), F::from_f64(0.0));
+    let pi = core::f64::consts::PI;
+    assert_close(minus_one.ln(), (0.0, pi), tol::<F>(), 0.0, "ln(-1)");
+    assert_close(
+        minus_one.conj().ln(),
+        (0.0, -pi),
+        tol::<F>(),
+        0.0,
+        "ln(-1 - 0i)",
+    );
+}
+
+fn check_recip<F: Sample>() {
+    for z in values::<F>(10000, 60) {
+        let (re, im) = parts(z);
+        let r = libm::hypot(re, im);
+        let exact = (re / r / r, -im / r / r);
+        assert_close(
+            z.recip(),
+            exact,
+            tol::<F>(),
+            0.0,
+            &format!("1 / ({re:e}, {im:e})"),
+        );
+    }
+}
+
+#[test]
+fn sqrt() {
+    check_sqrt::<f32>();
+    check_sqrt::<f64>();
+}
+
+#[test]
+fn powi() {
+    check_powi::<f32>();
+    check_powi::<f64>();
+}
+
+#[test]
+fn ln() {
+    check_ln::<f32>();
+    check_ln::<f64>();
+}
+
+#[test]
+fn recip() {
+    check_recip::<f32>();
+    check_recip::<f64>();
+}
@@ -0,0 +1,63 @@
+use nvptx_sys_ulp::*;
+
+/// small divisors, powers of two and their neighbours, and random ones of every length
+fn divisors(bits: u32) -> impl Iterator<Item = u64> {
+    let max = u64::MAX >> (64 - bits);
+    let powers = (0..bits).flat_map(|i| [(1 << i) - 1, 1 << i, (1 << i) + 1]);
+    (1..1000)
+        .chain(powers)
+        .chain(words(1000).map(move |x| (x & max) >> (x % bits as u64)))
+        .chain([max - 1, max])
+        .filter(|&d| d != 0)
+}
+
+/// the edges of the quotients around n = 0, the multiples of d and the maximum
+fn dividends(d: u64, max: u64) -> impl Iterator<Item = u64> {
+    let q = max / d;
+    [
+        0,
+        1,
+        d - 1,
+        d,
+        d.saturating_add(1),
+        max - 1,
+        max,
+        q * d,
+        q * d - 1,
+    ]
+    .into_iter()
+    .chain(words(64).map(move |x| x & max))
+    .filter(move |&n| n <= max)
+}
+
+#[test]
+fn u32_quotients() {
+    for d in divisors(32) {
+        let divisor = FastDivisor::<u32>::new(d as u32);
+        for n in dividends(d, u32::MAX as u64) {
+            let n = n as u32;
+            assert_eq!(divisor.divmod(n), (n / d as u32, n % d as u32), "{n} / {d}");
+        }
+    }
+}
+
+#[test]
+fn u64_quotients() {
+    for d in divisors(64) {
+        let divisor = FastDivisor::<u64>::new(d);
+        for n in dividends(d, u64::MAX) {
+            assert_eq!((n / divisor, n % divisor), (n / d, n % d), "{n} / {d}");
+        }
+    }
+}
+
+#[test]
+fn unravel() {
+    let dims = Dims::new(7, 5);
+    for index in 0..7 * 5 * 3 {
+        let (x, y, z) = dims.unravel(index);
+        assert_eq!((x, y, z), (index % 7, index / 7 % 5, index / 35));
+        assert_eq!(dims.ravel(x, y, z), index);
+    }
+    assert_eq!(Dims::new(1, 1).unravel(u32::MAX), (0, 0, u32::MAX));
+}
@@ -0,0 +1,124 @@
+use nvptx_sys_ulp::*;
+
+/// 2^127
+const HUGE: f32 = 1.7014118e38;
+
+#[test]
+fn div_approx_flushes_huge_divisors() {
+    assert_eq!(div_approx(3.0, HUGE), 0.0);
+    assert_eq!(div_approx(-3.0, HUGE).to_bits(), (-0.0f32).to_bits());
+    assert_eq!(div_approx(3.0, -HUGE).to_bits(), (-0.0f32).to_bits());
+    assert!(div_approx(f32::INFINITY, HUGE).is_nan());
+    // 2^126 itself and the infinities divide
+    assert!((div_approx(1.0, HUGE / 2.0) * HUGE - 2.0).abs() < 1e-6);
+    assert_eq!(div_approx(1.0, f32::INFINITY), 0.0);
+}
+
+#[test]
+fn ieee_divides_over_the_full_range() {
+    type F = FastFloat<f32, Ieee>;
+    let quotient = (F::new(1e38) / F::new(3e38)).into_inner();
+    assert!((quotient - 1.0 / 3.0).abs() < 1e-6, "{quotient}");
+    // 2^-127 is subnormal, 2 ulp are 2^-148
+    let recip = Float::recip(F::new(HUGE)).into_inner();
+    assert!(
+        (recip - f32::from_bits(0x0040_0000)).abs() <= f32::from_bits(2),
+        "{recip:e}"
+    );
+    assert!((div_full(3.0, HUGE) * HUGE - 3.0).abs() < 1e-6);
+    assert!(div_full(f32::INFINITY, HUGE).is_infinite());
+}
+
+#[test]
+#[should_panic(expected = "NaN in a checked FastFloat comparison")]
+fn checked_panics_comparing_nan() {
+    let nan = FastFloat::<f32, Checked>::new(f32::NAN);
+    let _ = nan < FastFloat::new(1.0);
+}
+
+#[test]
+fn checked_compares_without_nan() {
+    let (a, b) = (FastFloat::<f32, Checked>::new(1.0), FastFloat::new(2.0));
+    assert!(a < b && a != b && a == a);
+    assert!(FloatCore::is_infinite(a / FastFloat::new(0.0)));
+}
+
+#[test]
+fn propagate_nan() {
+    type F = FastFloat<f32, PropagateNan>;
+    let (nan, one) = (F::new(f32::NAN), F::new(1.0));
+    for value in [
+        nan + one,
+        one + nan,
+        nan * one,
+        one * nan,
+        nan / one,
+        one / nan,
+    ] {
+        assert!(FloatCore::is_nan(value));
+    }
+    assert!(FloatCore::is_nan(
+        F::new(f32::INFINITY) - F::new(f32::INFINITY)
+    ));
+    assert!(FloatCore::is_nan(F::new(0.0) * F::new(f32::INFINITY)));
+    assert_eq!(nan.partial_cmp(&one), None);
+    assert_eq!(one.partial_cmp(&nan), None);
+    assert!(!nan.lt(&one) && !nan.gt(&one) && !nan.le(&one) && !nan.ge(&one));
+    assert!(nan != nan && nan != one);
+}
+
+#[test]
+fn sign_is_the_sign_bit() {
+    fn sign<F: FastNum, P: FastPolicy<F>>(value: F) -> (bool, bool, bool, bool) {
+        let value = FastFloat::<F, P>::new(value);
+        (
+            FloatCore::is_sign_positive(value),
+            FloatCore::is_sign_negative(value),
+            Float::is_sign_positive(value),
+            Float::is_sign_negative(value),
+        )
+    }
+    let (positive, negative) = ((true, false, true, false), (false, true, false, true));
+    for (value, expected) in [
+        (0.0, positive),
+        (-0.0, negative),
+        (f32::NAN, positive),
+        (-f32::NAN, negative),
+        (f32::NEG_INFINITY, negative),
+    ] {
+        assert_eq!(sign::<f32, Checked>(value), expected, "{value}");
+        assert_eq!(sign::<f64, PropagateNan>(value as f64), expected, "{value}");
+    }
+}
+
+#[test]
+fn atan2_scales_before_dividing() {
+    use core::f32::consts::{FRAC_PI_4, PI};
+    // a divisor above 2^126 would flush the quotient
+    let angle = 1e38f32.fast_atan2(3e38);
+    assert!((angle - (1.0f32 / 3.0).atan()).abs() < 1e-6, "{angle}");
+    let angle = 3e-39f32.fast_atan2(1e-38);
+    assert!((angle - 0.3f32.atan()).abs() < 1e-6, "{angle}");
+    let angle = 1e308f64.fast_atan2(1.5e308);
+    assert!((angle - (2.0f64 / 3.0).atan()).abs() < 1e-15, "{angle}");
+    let infinity = f32::INFINITY;
+    for (y, x, expected) in [
+        (infinity, infinity, FRAC_PI_4),
+        (infinity, -infinity, 3.0 * FRAC_PI_4),
+        (-infinity, infinity, -FRAC_PI_4),
+        (1.0, -infinity, PI),
+        (infinity, 1.0, PI / 2.0),
+    ] {
+        let angles = [
+            y.fast_atan2(x),
+            Half::from_f32(y).fast_atan2(Half::from_f32(x)).to_f32(),
+            (y as f64).fast_atan2(x as f64) as f32,
+        ];
+        for angle in angles {
+            assert!((angle - expected).abs() < 2e-3, "{y} {x}: {angle}");
+        }
+        assert!((angles[0] - expected).abs() < 1e-6 && angles[2] == expected);
+    }
+    assert!(f32::NAN.fast_atan2(0.0).is_nan() && 0.0f32.fast_atan2(f32::NAN).is_nan());
+    assert!(f64::NAN.fast_atan2(f64::INFINITY).is_nan());
+}
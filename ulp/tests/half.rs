@@ -0,0 +1,263 @@
+use nvptx_sys_ulp::*;
+
+/// The two half formats, described by their precision and exponent range
+trait Format: Copy + std::ops::Add<Output = Self> + std::ops::Sub<Output = Self> {
+    const NAME: &'static str;
+    /// significand bits, the implicit one included
+    const PRECISION: i32;
+    const EMIN: i32;
+    const EMAX: i32;
+    fn from_bits(bits: u16) -> Self;
+    fn to_bits(self) -> u16;
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+    fn fma(self, b: Self, c: Self) -> Self;
+}
+
+macro_rules! formats {
+    ($($F: ident, $name: literal, $precision: literal, $emin: literal, $emax: literal);+) => {
+        $(impl Format for $F {
+            const NAME: &'static str = $name;
+            const PRECISION: i32 = $precision;
+            const EMIN: i32 = $emin;
+            const EMAX: i32 = $emax;
+            fn from_bits(bits: u16) -> Self {
+                $F::from_bits(bits)
+            }
+            fn to_bits(self) -> u16 {
+                $F::to_bits(self)
+            }
+            fn to_f32(self) -> f32 {
+                $F::to_f32(self)
+            }
+            fn from_f32(value: f32) -> Self {
+                $F::from_f32(value)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            fn fma(self, b: Self, c: Self) -> Self {
+                $F::fma(self, b, c)
+            }
+        })+
+    };
+}
+
+formats! { Half, "f16", 11, -14, 15; BHalf, "bf16", 8, -126, 127 }
+
+/// the value of a bit pattern, decoded field by field
+fn decode<F: Format>(bits: u16) -> f64 {
+    let mantissa_bits = F::PRECISION - 1;
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits & 0x7fff) as i32 >> mantissa_bits;
+    let mantissa = (bits as i32 & ((1 << mantissa_bits) - 1)) as f64;
+    let bias = F::EMAX;
+    if exponent == 2 * bias + 1 {
+        return if mantissa == 0.0 {
+            sign * f64::INFINITY
+        } else {
+            f64::NAN
+        };
+    }
+    let (significand, exponent) = match exponent {
+        0 => (mantissa, F::EMIN),
+        _ => (mantissa + (1 << mantissa_bits) as f64, exponent - bias),
+    };
+    sign * significand * 2f64.powi(exponent - mantissa_bits)
+}
+
+/// `value + residual` rounded to nearest, ties to even, for a residual below half an ulp of value,
+/// as two-sum and fma leave it
+fn round<F: Format>(value: f64, residual: f64) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let exponent = (((value.to_bits() >> 52) & 0x7ff) as i32 - 1023).max(F::EMIN);
+    let ulp = 2f64.powi(exponent - F::PRECISION + 1);
+    let scaled = value / ulp;
+    let rounded = if (scaled - scaled.trunc()).abs() == 0.5 && residual != 0.0 {
+        if residual > 0.0 {
+            scaled.ceil()
+        } else {
+            scaled.floor()
+        }
+    } else {
+        scaled.round_ties_even()
+    };
+    let result = rounded * ulp;
+    if result.abs() >= 2f64.powi(F::EMAX + 1) {
+        f64::INFINITY.copysign(value)
+    } else {
+        result
+    }
+}
+
+#[track_caller]
+fn assert_rounds<F: Format>(result: F, expected: f64, what: std::fmt::Arguments) {
+    if expected.is_nan() {
+        assert_eq!(result.to_bits(), 0x7fff, "{} {what}", F::NAME);
+    } else {
+        let got = decode::<F>(result.to_bits());
+        assert_eq!(
+            got.to_bits(),
+            expected.to_bits(),
+            "{} {what}: {got} != {expected}",
+            F::NAME
+        );
+    }
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+fn widening<F: Format>() {
+    for bits in 0..=u16::MAX {
+        let value = F::from_bits(bits).to_f32();
+        let expected = decode::<F>(bits);
+        if expected.is_nan() {
+            assert!(value.is_nan(), "{} {bits:#06x}", F::NAME);
+            assert_eq!(F::from_f32(value).to_bits(), 0x7fff);
+        } else {
+            assert_eq!(
+                (value as f64).to_bits(),
+                expected.to_bits(),
+                "{} {bits:#06x}",
+                F::NAME
+            );
+            assert_eq!(
+                F::from_f32(value).to_bits(),
+                bits,
+                "{} {bits:#06x}",
+                F::NAME
+            );
+        }
+    }
+}
+
+#[test]
+fn to_f32() {
+    widening::<Half>();
+    widening::<BHalf>();
+}
+
+fn narrowing<F: Format>() {
+    let check = |value: f32| {
+        assert_rounds(
+            F::from_f32(value),
+            round::<F>(value as f64, 0.0),
+            format_args!("{value:e}"),
+        );
+    };
+    // every midpoint between neighbours, and the f32 on either side of it
+    let positive = (0..0x8000).map(decode::<F>).take_while(|x| x.is_finite());
+    let values: Vec<f64> = positive.collect();
+    let max = values[values.len() - 1];
+    let overflow = max + (max - values[values.len() - 2]) / 2.0;
+    for midpoint in values
+        .windows(2)
+        .map(|pair| (pair[0] + pair[1]) / 2.0)
+        .chain([overflow])
+    {
+        for sign in [1.0, -1.0] {
+            let midpoint = (sign * midpoint) as f32;
+            check(midpoint);
+            check(midpoint.next_up());
+            check(midpoint.next_down());
+        }
+    }
+    for bits in (0..=u32::MAX).step_by(65537) {
+        check(f32::from_bits(bits));
+    }
+    for value in [
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NAN,
+        f32::MAX,
+        f32::MIN,
+    ] {
+        check(value);
+    }
+}
+
+#[test]
+fn from_f32() {
+    narrowing::<Half>();
+    narrowing::<BHalf>();
+}
+
+/// a pseudo random sample of the second operands, with the special values
+fn operands(seed: u64) -> Vec<u16> {
+    let mut rng = Rng(seed);
+    let special = [0, 0x8000, 0x0001, 0x8001, 0x7fff];
+    special
+        .into_iter()
+        .chain((0..59).map(|_| rng.next_u64() as u16))
+        .collect()
+}
+
+fn arithmetic<F: Format>() {
+    let operands = operands(0x9e37_79b9_7f4a_7c15);
+    for a in 0..=u16::MAX {
+        let (fa, x) = (F::from_bits(a), decode::<F>(a));
+        for &b in &operands {
+            let (fb, y) = (F::from_bits(b), decode::<F>(b));
+            let (sum, error) = two_sum(x, y);
+            assert_rounds(fa + fb, round::<F>(sum, error), format_args!("{x} + {y}"));
+            let (difference, error) = two_sum(x, -y);
+            assert_rounds(
+                fa - fb,
+                round::<F>(difference, error),
+                format_args!("{x} - {y}"),
+            );
+            // exact, the significands have at most 11 bits
+            assert_rounds(
+                fa.mul(fb),
+                round::<F>(x * y, 0.0),
+                format_args!("{x} * {y}"),
+            );
+        }
+    }
+}
+
+#[test]
+fn add_sub_mul() {
+    arithmetic::<Half>();
+    arithmetic::<BHalf>();
+}
+
+fn fused<F: Format>() {
+    let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+    let check = |a: u16, b: u16, c: u16| {
+        let [x, y, z] = [a, b, c].map(decode::<F>);
+        // the product is exact, two-sum leaves the rounding error of adding c
+        let (sum, error) = two_sum(x * y, z);
+        let result = F::from_bits(a).fma(F::from_bits(b), F::from_bits(c));
+        assert_rounds(
+            result,
+            round::<F>(sum, error),
+            format_args!("{x} * {y} + {z}"),
+        );
+    };
+    for a in 0..=u16::MAX {
+        for _ in 0..16 {
+            let x = rng.next_u64();
+            check(a, x as u16, (x >> 16) as u16);
+        }
+    }
+    // 3 * (1 + ulp(1)) is a midpoint, the smallest addend decides the direction
+    let three = F::from_f32(3.0).to_bits();
+    let above_one = F::from_f32(1.0).to_bits() + 1;
+    for c in [0, 0x8000, 0x0001, 0x8001] {
+        check(three, above_one, c);
+    }
+}
+
+#[test]
+fn fma() {
+    fused::<Half>();
+    fused::<BHalf>();
+}
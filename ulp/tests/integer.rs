@@ -0,0 +1,151 @@
+use nvptx_sys_ulp::*;
+
+const A: u32 = 0x0302_0100;
+const B: u32 = 0x0706_0504;
+
+#[test]
+fn bit_counts() {
+    assert_eq!((0u32.popc(), u64::MAX.popc(), (-1i32).popc()), (0, 64, 32));
+    assert_eq!((0u32.clz(), 1u64.clz(), (-1i64).clz()), (32, 63, 0));
+    assert_eq!((0u32.ffs(), 8u32.ffs(), i64::MIN.ffs()), (0, 4, 64));
+    assert_eq!((1u32.brev(), 0x8000_0000u32), (0x8000_0000, 1u32.brev()));
+}
+
+#[test]
+fn bit_field() {
+    assert_eq!(0xf0u32.bfe(4, 4), 0xf);
+    assert_eq!(0xf0i32.bfe(4, 4), -1);
+    assert_eq!(0xf0i32.bfe(4, 0), 0);
+    // past the msb the field extends its last bit
+    assert_eq!(0x8000_0000u32.bfe(28, 8), 0x8);
+    assert_eq!((0x8000_0000u32 as i32).bfe(28, 8), -8);
+    assert_eq!((0x8000_0000u32 as i32).bfe(40, 4), -1);
+    assert_eq!(0x8000_0000u32.bfe(40, 4), 0);
+    // pos & len are mod 256
+    assert_eq!(0xf0u32.bfe(256 + 4, 256 + 4), 0xf);
+    assert_eq!(0xabu32.bfi(0xffff_ffff, 8, 8), 0xffff_abff);
+    assert_eq!(0xabu64.bfi(0, 60, 8), 0xb000_0000_0000_0000);
+    for (x, base) in words(1000).zip(words(1001).skip(1)) {
+        let (pos, len) = ((x >> 32) as u32 % 64, (base >> 32) as u32 % 64);
+        if pos + len <= 64 {
+            assert_eq!(x.bfi(base, pos, len).bfe(pos, len), x.bfe(0, len));
+        }
+        assert_eq!(x.bfi(base, pos, 0), base);
+    }
+}
+
+#[test]
+fn products() {
+    for (a, b) in words(1000).zip(words(1001).skip(1)) {
+        assert_eq!(a.mul_hi(b), ((a as u128 * b as u128) >> 64) as u64);
+        assert_eq!(
+            (a as i64).mul_hi(b as i64),
+            ((a as i64 as i128 * b as i64 as i128) >> 64) as i64
+        );
+        assert_eq!(
+            (a as u32).mul_wide(b as u32),
+            (a as u32 as u64) * (b as u32 as u64)
+        );
+        assert_eq!(
+            (a as u32).mul_hi(b as u32),
+            ((a as u32).mul_wide(b as u32) >> 32) as u32
+        );
+    }
+    assert_eq!((-2i16).mul_wide(i16::MAX), -2 * i16::MAX as i32);
+    // only the low 24 bits take part
+    assert_eq!(0x0100_0001u32.mul24(2), 2);
+    assert_eq!(0x00ff_ffffi32.mul24(3), -3);
+    assert_eq!(0x00ff_ffffu32.mul24_hi(0x00ff_ffff), 0xffff_fe00);
+    assert_eq!(0x00ff_ffffi32.mul24_hi(0x00ff_ffff), 0);
+    assert_eq!(0x00ff_ffffi32.mad24(3, 5), 2);
+    assert_eq!((3u32.sad(10, 5), (-3i32).sad(4, 1)), (12, 8));
+}
+
+#[test]
+fn permute() {
+    assert_eq!(prmt(A, B, 0x3210), A);
+    assert_eq!(prmt(A, B, 0x7654), B);
+    assert_eq!(prmt(A, B, 0x0123), 0x0001_0203);
+    assert_eq!(prmt(0x80, 0, 0x0008), 0x8080_80ff);
+    assert_eq!(prmt_f4e(A, B, 1), 0x0403_0201);
+    assert_eq!(prmt_b4e(A, B, 0), 0x0506_0700);
+    assert_eq!(prmt_b4e(A, B, 3), 0x0001_0203);
+    assert_eq!(prmt_rc8(A, B, 2), 0x0202_0202);
+    assert_eq!(prmt_ecl(A, B, 1), 0x0302_0101);
+    assert_eq!(prmt_ecr(A, B, 2), 0x0202_0100);
+    assert_eq!(prmt_rc16(A, B, 1), 0x0302_0302);
+    // only the low 2 bits select in the other modes
+    assert_eq!(prmt_f4e(A, B, 0x3210 | 3), prmt_f4e(A, B, 3));
+}
+
+#[test]
+fn funnel_shift() {
+    for (x, n) in words(1000).map(|x| (x as u32, (x >> 32) as u32)) {
+        assert_eq!(funnel_shift_l(x, x, n), x.rotate_left(n));
+        assert_eq!(funnel_shift_r(x, x, n), x.rotate_right(n));
+    }
+    assert_eq!(funnel_shift_l(A, B, 8), 0x0605_0403);
+    assert_eq!(funnel_shift_r(A, B, 8), 0x0403_0201);
+    assert_eq!(funnel_shift_l(A, B, 40), funnel_shift_l(A, B, 8));
+    assert_eq!(
+        (
+            funnel_shift_l_clamp(A, B, 40),
+            funnel_shift_r_clamp(A, B, 40)
+        ),
+        (A, B)
+    );
+}
+
+#[test]
+fn packed_dot() {
+    assert_eq!(
+        0x0102_03ffu32.dp4a(0x0101_0101u32, 10),
+        255 + 3 + 2 + 1 + 10
+    );
+    assert_eq!(0x0102_03ffi32.dp4a(0x0101_0101i32, 10), -1 + 3 + 2 + 1 + 10);
+    assert_eq!(0x0102_03ffu32.dp4a(-1i32, 0), -(255 + 3 + 2 + 1));
+    assert_eq!(0x0102_03ffi32.dp4a(0xffu32, 0), -255);
+    let halves = 0xffff_0002u32;
+    assert_eq!(halves.dp2a_lo(0x0403_0201u32, 0), 2 + 0xffff * 2);
+    assert_eq!((halves as i32).dp2a_lo(0x0403_0201u32, 0), 0);
+    assert_eq!((halves as i32).dp2a_hi(0x0403_0201u32, 1), 3);
+    assert_eq!((halves as i32).dp2a_hi(0x0403_fe01i32, 0), 2);
+}
+
+#[test]
+fn masks() {
+    assert_eq!(bmsk(4, 8), 0xff0);
+    assert_eq!(bmsk(28, 8), 0xf000_0000);
+    assert_eq!((bmsk(32, 4), bmsk(0, 40)), (0, u32::MAX));
+    assert_eq!((bmsk_wrap(36, 4), bmsk_wrap(4, 32)), (0xf0, 0));
+    assert_eq!(
+        (0xffu32.szext(4), 0x8i32.szext(4), 0x7i32.szext(4)),
+        (0xf, -8, 7)
+    );
+    assert_eq!((0xffu32.szext(0), (-1i32).szext(40)), (0, -1));
+    assert_eq!((0xffu32.szext_wrap(32), (-1i32).szext_wrap(33)), (0, -1));
+    assert_eq!(
+        (add_sat(i32::MAX, 1), add_sat(i32::MIN, -1), add_sat(2, -3)),
+        (i32::MAX, i32::MIN, -1)
+    );
+}
+
+#[test]
+fn carry_chain() {
+    let words: Vec<u64> = words(2000).collect();
+    for pair in words.chunks(4) {
+        let a = (pair[0] as u128) << 64 | pair[1] as u128;
+        let b = (pair[2] as u128) << 64 | pair[3] as u128;
+        let (sum, carry) = (0..4).fold((0u128, false), |(sum, carry), i| {
+            let (d, carry) = ((a >> (32 * i)) as u32).addc((b >> (32 * i)) as u32, carry);
+            (sum | (d as u128) << (32 * i), carry)
+        });
+        assert_eq!((sum, carry), a.overflowing_add(b));
+        let (lo, borrow) = (a as u64).subc(b as u64, false);
+        let (hi, borrow) = ((a >> 64) as u64).subc((b >> 64) as u64, borrow);
+        assert_eq!(
+            ((hi as u128) << 64 | lo as u128, borrow),
+            a.overflowing_sub(b)
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use nvptx_sys_ulp::*;
+use std::fmt::Debug;
+
+/// pseudo random components in [-1, 1)
+fn components<F: Sample>(n: usize) -> Vec<F> {
+    words(n)
+        .map(|x| F::from_f64((x >> 11) as f64 / 2f64.powi(52) - 1.0))
+        .collect()
+}
+
+/// the tolerance of the approximate f32 primitives, and of the f64 ones
+fn tol<F: Sample>() -> f64 {
+    if F::NAME == "f32" {
+        1e-5
+    } else {
+        1e-13
+    }
+}
+
+fn columns3<F: Sample>(m: Mat3<F>) -> [[f64; 3]; 3] {
+    m.cols.map(|c| c.to_array().map(Sample::to_f64))
+}
+
+fn columns4<F: Sample>(m: Mat4<F>) -> [[f64; 4]; 4] {
+    m.cols.map(|c| c.to_array().map(Sample::to_f64))
+}
+
+/// the largest difference between the entries
+fn distance<const N: usize>(a: [[f64; N]; N], b: [[f64; N]; N]) -> f64 {
+    a.iter()
+        .flatten()
+        .zip(b.iter().flatten())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max)
+}
+
+fn identity<const N: usize>() -> [[f64; N]; N] {
+    core::array::from_fn(|i| core::array::from_fn(|j| (i == j) as u8 as f64))
+}
+
+fn frobenius<const N: usize>(m: [[f64; N]; N]) -> f64 {
+    m.iter().flatten().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+fn check_mat3_inverse<F: Sample + Debug>() {
+    for (i, m) in components::<F>(9 * 2000).chunks(9).enumerate() {
+        let m = Mat3::from_cols(core::array::from_fn(|c| {
+            Vec3::new(m[3 * c], m[3 * c + 1], m[3 * c + 2])
+        }));
+        let inverse = m.inverse();
+        // the rounding error grows with the condition number
+        let cond = frobenius(columns3(m)) * frobenius(columns3(inverse));
+        let bound = tol::<F>() * cond;
+        let (right, left) = (columns3(m * inverse), columns3(inverse * m));
+        assert!(distance(right, identity()) <= bound, "{i}: {m:?} {right:?}");
+        assert!(distance(left, identity()) <= bound, "{i}: {m:?} {left:?}");
+    }
+}
+
+fn check_mat4_inverse<F: Sample + Debug>() {
+    for (i, m) in components::<F>(16 * 2000).chunks(16).enumerate() {
+        let m = Mat4::from_cols(core::array::from_fn(|c| {
+            Vec4::new(m[4 * c], m[4 * c + 1], m[4 * c + 2], m[4 * c + 3])
+        }));
+        let inverse = m.inverse();
+        let cond = frobenius(columns4(m)) * frobenius(columns4(inverse));
+        let bound = tol::<F>() * cond;
+        let (right, left) = (columns4(m * inverse), columns4(inverse * m));
+        assert!(distance(right, identity()) <= bound, "{i}: {m:?} {right:?}");
+        assert!(distance(left, identity()) <= bound, "{i}: {m:?} {left:?}");
+    }
+    // an affine transform, whose inverse undoes it
+    let q = Quat::new(1.0, 2.0, 3.0, 4.0).normalize();
+    let (s, t) = (Vec3::new(2.0, 0.5, 4.0), Vec3::new(-1.0, 3.0, 7.0));
+    let m = Mat4::<f64>::from_scale_rotation_translation(s, q, t);
+    let p = Vec3::new(0.25, -8.0, 3.0);
+    let back = m.inverse().transform_point(m.transform_point(p));
+    assert!((back - p).length() <= 1e-13, "{back:?}");
+}
+
+fn unit_quats<F: Sample>(n: usize) -> Vec<Quat<F>> {
+    components::<F>(4 * n)
+        .chunks(4)
+        .map(|q| Quat::new(q[0], q[1], q[2], q[3]).normalize())
+        .collect()
+}
+
+fn check_quat<F: Sample + Debug>() {
+    let quats = unit_quats::<F>(2000);
+    let vectors = components::<F>(3 * 2000);
+    for (i, (pair, v)) in quats.windows(2).zip(vectors.chunks(3)).enumerate() {
+        let (q, r) = (pair[0], pair[1]);
+        let (mq, mr) = (Mat3::from_quat(q), Mat3::from_quat(r));
+        // a rotation is orthonormal
+        let orthonormal = columns3(mq * mq.transpose());
+        assert!(
+            distance(orthonormal, identity()) <= tol::<F>(),
+            "{i}: {q:?}"
+        );
+        // q * r rotates by r then by q
+        let product = columns3(Mat3::from_quat(q * r));
+        assert!(
+            distance(product, columns3(mq * mr)) <= tol::<F>(),
+            "{i}: {q:?} {r:?}"
+        );
+        let v = Vec3::new(v[0], v[1], v[2]);
+        let (rotated, by_matrix) = (q.rotate(v), mq * v);
+        let error = (rotated - by_matrix)
+            .to_array()
+            .map(|x| Sample::to_f64(x).abs());
+        assert!(error.iter().all(|&e| e <= tol::<F>()), "{i}: {q:?} {v:?}");
+    }
+}
+
+#[test]
+fn mat3_inverse() {
+    check_mat3_inverse::<f32>();
+    check_mat3_inverse::<f64>();
+}
+
+#[test]
+fn mat4_inverse() {
+    check_mat4_inverse::<f32>();
+    check_mat4_inverse::<f64>();
+}
+
+#[test]
+fn quat_product_and_rotate() {
+    check_quat::<f32>();
+    check_quat::<f64>();
+    // a quarter turn about z takes x to y, whichever way from_quat is written
+    let q = Quat::<f64>::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), core::f64::consts::FRAC_PI_2);
+    let y = q.rotate(Vec3::new(1.0, 0.0, 0.0));
+    assert!((y - Vec3::new(0.0, 1.0, 0.0)).length() <= 1e-15, "{y:?}");
+    let y = Mat3::from_quat(q) * Vec3::new(1.0, 0.0, 0.0);
+    assert!((y - Vec3::new(0.0, 1.0, 0.0)).length() <= 1e-15, "{y:?}");
+}
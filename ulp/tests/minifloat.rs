@@ -0,0 +1,223 @@
+use nvptx_sys_ulp::*;
+
+/// The fp8 formats, their mantissa bits, bias and largest finite encoding
+trait Fp8: Copy {
+    const NAME: &'static str;
+    const MANTISSA: i32;
+    const BIAS: i32;
+    const MAX: u8;
+    fn from_bits(bits: u8) -> Self;
+    fn to_bits(self) -> u8;
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+    fn from_f16x2(value: f16x2) -> [Self; 2];
+}
+
+macro_rules! fp8 {
+    ($($F: ident, $name: literal, $mantissa: literal, $bias: literal, $max: literal);+) => {
+        $(impl Fp8 for $F {
+            const NAME: &'static str = $name;
+            const MANTISSA: i32 = $mantissa;
+            const BIAS: i32 = $bias;
+            const MAX: u8 = $max;
+            fn from_bits(bits: u8) -> Self {
+                $F::from_bits(bits)
+            }
+            fn to_bits(self) -> u8 {
+                $F::to_bits(self)
+            }
+            fn to_f32(self) -> f32 {
+                $F::to_f32(self)
+            }
+            fn from_f32(value: f32) -> Self {
+                $F::from_f32(value)
+            }
+            fn from_f16x2(value: f16x2) -> [Self; 2] {
+                $F::from_f16x2(value)
+            }
+        })+
+    };
+}
+
+fp8! { Fp8E4M3, "e4m3", 3, 7, 0x7e; Fp8E5M2, "e5m2", 2, 15, 0x7b }
+
+/// the value of an encoding, decoded field by field, infinities only above e5m2's largest finite one
+fn decode<F: Fp8>(bits: u8) -> f64 {
+    let sign = if bits & 0x80 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits & 0x7f) as i32 >> F::MANTISSA;
+    let mantissa = (bits as i32 & ((1 << F::MANTISSA) - 1)) as f64;
+    if bits & 0x7f > F::MAX {
+        return if F::MAX == 0x7b && mantissa == 0.0 {
+            sign * f64::INFINITY
+        } else {
+            f64::NAN
+        };
+    }
+    let (significand, exponent) = match exponent {
+        0 => (mantissa, 1 - F::BIAS),
+        _ => (mantissa + (1 << F::MANTISSA) as f64, exponent - F::BIAS),
+    };
+    sign * significand * 2f64.powi(exponent - F::MANTISSA)
+}
+
+/// `value` rounded to `precision` significand bits with exponents from `emin` on, ties resolved by
+/// `ties` of the value in ulps
+fn round(value: f64, precision: i32, emin: i32, ties: fn(f64) -> f64) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let exponent = (((value.to_bits() >> 52) & 0x7ff) as i32 - 1023).max(emin);
+    let ulp = 2f64.powi(exponent - precision + 1);
+    ties(value / ulp) * ulp
+}
+
+/// `cvt.rn.satfinite`: to nearest even, beyond the largest finite value, infinity included, to it
+fn satfinite<F: Fp8>(value: f64) -> f64 {
+    let max = decode::<F>(F::MAX);
+    let rounded = round(value, F::MANTISSA + 1, 1 - F::BIAS, f64::round_ties_even);
+    rounded.clamp(-max, max)
+}
+
+#[track_caller]
+fn assert_converts<F: Fp8>(result: F, value: f64) {
+    if value.is_nan() {
+        assert_eq!(result.to_bits(), 0x7f, "{} {value}", F::NAME);
+    } else {
+        let expected = satfinite::<F>(value);
+        let got = decode::<F>(result.to_bits());
+        assert_eq!(
+            got.to_bits(),
+            expected.to_bits(),
+            "{} {value:e}: {got} != {expected}",
+            F::NAME
+        );
+    }
+}
+
+fn round_trip<F: Fp8>() {
+    for bits in 0..=u8::MAX {
+        let value = F::from_bits(bits).to_f32();
+        let expected = decode::<F>(bits);
+        if expected.is_nan() {
+            assert!(value.is_nan(), "{} {bits:#04x}", F::NAME);
+            assert_eq!(
+                F::from_f32(value).to_bits(),
+                0x7f,
+                "{} {bits:#04x}",
+                F::NAME
+            );
+        } else {
+            assert_eq!(
+                (value as f64).to_bits(),
+                expected.to_bits(),
+                "{} {bits:#04x}",
+                F::NAME
+            );
+            // e5m2's infinities saturate
+            let back = if expected.is_infinite() {
+                bits & 0x80 | F::MAX
+            } else {
+                bits
+            };
+            assert_eq!(
+                F::from_f32(value).to_bits(),
+                back,
+                "{} {bits:#04x}",
+                F::NAME
+            );
+        }
+    }
+}
+
+#[test]
+fn every_encoding_round_trips() {
+    round_trip::<Fp8E4M3>();
+    round_trip::<Fp8E5M2>();
+}
+
+fn from_every_f16<F: Fp8>() {
+    for bits in 0..=u16::MAX {
+        let half = Half::from_bits(bits);
+        let value = half.to_f64();
+        assert_converts(F::from_f32(half.to_f32()), value);
+        let pair = F::from_f16x2(f16x2::from_bits(bits as u32 | 0x3c00 << 16));
+        assert_converts(pair[0], value);
+        assert_converts(pair[1], 1.0);
+    }
+}
+
+#[test]
+fn every_f16() {
+    from_every_f16::<Fp8E4M3>();
+    from_every_f16::<Fp8E5M2>();
+}
+
+fn ties<F: Fp8>() {
+    let values: Vec<f64> = (0..=F::MAX).map(decode::<F>).collect();
+    let (max, below) = (values[values.len() - 1], values[values.len() - 2]);
+    // the midpoints between neighbours, and past the largest finite value the one to the next binade
+    let midpoints = values.windows(2).map(|pair| (pair[0] + pair[1]) / 2.0);
+    for midpoint in midpoints.chain([max + (max - below) / 2.0]) {
+        for sign in [1.0, -1.0] {
+            let midpoint = (sign * midpoint) as f32;
+            for value in [midpoint, midpoint.next_up(), midpoint.next_down()] {
+                assert_converts(F::from_f32(value), value as f64);
+            }
+        }
+    }
+    for value in [
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::MAX,
+        f32::MIN,
+        f32::NAN,
+    ] {
+        assert_converts(F::from_f32(value), value as f64);
+    }
+}
+
+#[test]
+fn tie_boundaries() {
+    ties::<Fp8E4M3>();
+    ties::<Fp8E5M2>();
+}
+
+#[track_caller]
+fn assert_tf32(value: f32) {
+    let got = Tf32::from_f32(value).to_f32();
+    if value.is_nan() {
+        assert!(got.is_nan(), "{value}");
+        return;
+    }
+    // f64's round is to nearest, ties away from zero, and past f32::MAX tf32 overflows
+    let expected = round(value as f64, 11, -126, f64::round) as f32;
+    assert_eq!(
+        got.to_bits(),
+        expected.to_bits(),
+        "{value:e}: {got:e} != {expected:e}"
+    );
+}
+
+#[test]
+fn tf32_ties_away_from_zero() {
+    for bits in (0..=u32::MAX).step_by(8191) {
+        assert_tf32(f32::from_bits(bits));
+    }
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..65536 {
+        // midpoints and their neighbours of every sign and binade, subnormals included
+        let bits = (rng.next_u64() as u32 & !0x1fff) | 0x1000;
+        for bits in [bits, bits - 1, bits + 1] {
+            assert_tf32(f32::from_bits(bits));
+        }
+    }
+    // 1 + 2^-11 is halfway to 1 + 2^-10, which ties to even would not reach
+    let (midpoint, above) = (1.0 + f32::EPSILON * 4096.0, 1.0 + f32::EPSILON * 8192.0);
+    assert_eq!(Tf32::from_f32(midpoint).to_f32(), above);
+    assert_eq!(Tf32::from_f32(-midpoint).to_f32(), -above);
+    assert_eq!(Tf32::from_f32(f32::MAX).to_f32(), f32::INFINITY);
+    assert_eq!(
+        Tf32::from_f32(f32::NEG_INFINITY).to_f32(),
+        f32::NEG_INFINITY
+    );
+}
@@ -0,0 +1,301 @@
+use nvptx_sys_ulp::*;
+
+type Warp<F> = [F; WARP_SIZE as usize];
+/// `MmaReference::reference` and the like, `D = A x B + C`
+type Reference<A, B, C> = fn(&Warp<A>, &Warp<B>, &Warp<C>) -> Warp<C>;
+
+/// The values the tests draw for an element type, small enough for the float products and sums to be
+/// exact, the whole range of the integer types
+trait Values {
+    const RANGE: (i64, i64);
+    /// the accumulator's rounding of an exact result
+    fn wrap(value: f64) -> f64 {
+        value
+    }
+}
+
+macro_rules! values {
+    ($($T: ty => $range: expr),+) => {
+        $(impl Values for $T {
+            const RANGE: (i64, i64) = $range;
+        })+
+    };
+}
+
+values! {
+    Half => (-4, 4), BHalf => (-4, 4), Tf32 => (-4, 4), f32 => (-16, 16), i8 => (-128, 127),
+    u8 => (0, 255), S4 => (-8, 7), U4 => (0, 15), B1 => (0, 1)
+}
+
+impl Values for i32 {
+    const RANGE: (i64, i64) = (i32::MIN as i64, i32::MAX as i64);
+    fn wrap(value: f64) -> f64 {
+        value as i64 as i32 as f64
+    }
+}
+
+fn random<T: Values>(rng: &mut Rng, len: usize) -> Vec<f64> {
+    let (lo, hi) = T::RANGE;
+    let span = (hi - lo + 1) as u64;
+    (0..len)
+        .map(|_| (lo + (rng.next_u64() % span) as i64) as f64)
+        .collect()
+}
+
+fn fragments<F: LaneLayout>(matrix: &[f64]) -> Warp<F> {
+    std::array::from_fn(|lane| F::from_matrix(lane as u32, |row, col| matrix[row * F::COLS + col]))
+}
+
+fn gather<F: LaneLayout>(fragments: &Warp<F>) -> Vec<f64> {
+    let mut matrix = vec![f64::NAN; F::ROWS * F::COLS];
+    F::to_matrix(fragments, |row, col, value| {
+        matrix[row * F::COLS + col] = value
+    });
+    matrix
+}
+
+/// the lanes' elements are every element of the matrix once, and from_matrix & to_matrix invert each
+/// other
+fn layout<F: LaneLayout>()
+where
+    F::Element: Values,
+{
+    let mut seen = vec![0; F::ROWS * F::COLS];
+    for lane in 0..WARP_SIZE {
+        for element in 0..F::ELEMENTS {
+            let (row, col) = F::coords(lane, element);
+            assert!(
+                row < F::ROWS && col < F::COLS,
+                "lane {lane} element {element}"
+            );
+            seen[row * F::COLS + col] += 1;
+        }
+    }
+    assert!(seen.iter().all(|&n| n == 1), "{seen:?}");
+    let matrix = random::<F::Element>(&mut Rng(0x2545_f491_4f6c_dd1d), F::ROWS * F::COLS);
+    assert_eq!(gather(&fragments::<F>(&matrix)), matrix);
+}
+
+/// `wrap(product(A, B) + C)` of row-major matrices
+fn naive(
+    a: &[f64],
+    b: &[f64],
+    c: &[f64],
+    (m, n, k): (usize, usize, usize),
+    product: fn(f64, f64) -> f64,
+    wrap: fn(f64) -> f64,
+) -> Vec<f64> {
+    (0..m * n)
+        .map(|i| {
+            let (row, col) = (i / n, i % n);
+            wrap((0..k).fold(c[i], |acc, j| acc + product(a[row * k + j], b[j * n + col])))
+        })
+        .collect()
+}
+
+/// a reference of `A x B + C` against the naive product of random matrices
+fn check<A: LaneLayout, B: LaneLayout, C: LaneLayout>(
+    reference: Reference<A, B, C>,
+    product: fn(f64, f64) -> f64,
+    wrap: fn(f64) -> f64,
+) where
+    A::Element: Values,
+    B::Element: Values,
+    C::Element: Values,
+{
+    layout::<A>();
+    layout::<B>();
+    layout::<C>();
+    let shape = (A::ROWS, B::COLS, A::COLS);
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..4 {
+        let a = random::<A::Element>(&mut rng, A::ROWS * A::COLS);
+        let b = random::<B::Element>(&mut rng, B::ROWS * B::COLS);
+        let c = random::<C::Element>(&mut rng, C::ROWS * C::COLS);
+        let d = reference(&fragments(&a), &fragments(&b), &fragments(&c));
+        assert_eq!(gather(&d), naive(&a, &b, &c, shape, product, wrap));
+    }
+}
+
+fn multiply(a: f64, b: f64) -> f64 {
+    a * b
+}
+
+fn saturate(value: f64) -> f64 {
+    value.clamp(i32::MIN as f64, i32::MAX as f64)
+}
+
+macro_rules! dense {
+    ($($M: literal, $N: literal, $K: literal, $TA: ty, $TB: ty, $TC: ty);+) => {
+        $(check::<
+            Fragment<MatrixA, $M, $N, $K, $TA, RowMajor>,
+            Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+            Fragment<Accumulator, $M, $N, $K, $TC>,
+        >(MmaReference::reference, multiply, <$TC as Values>::wrap);)+
+    };
+}
+
+macro_rules! integer {
+    ($($TA: ty, $TB: ty);+ => $shapes: tt) => {
+        $(integer!(@types $TA, $TB, $shapes);)+
+    };
+    (@types $TA: ty, $TB: ty, [$([$M: literal, $N: literal, $K: literal]),+]) => {
+        $(dense!($M, $N, $K, $TA, $TB, i32);
+        check::<
+            Fragment<MatrixA, $M, $N, $K, $TA, RowMajor>,
+            Fragment<MatrixB, $M, $N, $K, $TB, ColMajor>,
+            Fragment<Accumulator, $M, $N, $K, i32>,
+        >(MmaSatfinite::reference_satfinite, multiply, saturate);)+
+    };
+}
+
+#[test]
+fn float() {
+    dense!(
+        16, 8, 8, Half, Half, Half; 16, 8, 8, Half, Half, f32; 16, 8, 16, Half, Half, Half;
+        16, 8, 16, Half, Half, f32; 16, 8, 8, BHalf, BHalf, f32; 16, 8, 16, BHalf, BHalf, f32;
+        16, 8, 4, Tf32, Tf32, f32; 16, 8, 8, Tf32, Tf32, f32
+    );
+}
+
+#[test]
+fn integers() {
+    integer!(i8, i8; u8, u8; i8, u8; u8, i8 => [[8, 8, 16], [16, 8, 16], [16, 8, 32]]);
+    integer!(S4, S4; U4, U4; S4, U4; U4, S4 => [[8, 8, 32], [16, 8, 32], [16, 8, 64]]);
+}
+
+macro_rules! popc {
+    ($([$M: literal, $N: literal, $K: literal]),+) => {
+        $(check::<
+            Fragment<MatrixA, $M, $N, $K, B1, RowMajor>,
+            Fragment<MatrixB, $M, $N, $K, B1, ColMajor>,
+            Fragment<Accumulator, $M, $N, $K, i32>,
+        >(MmaPopc::reference_and_popc, multiply, i32::wrap);
+        check::<
+            Fragment<MatrixA, $M, $N, $K, B1, RowMajor>,
+            Fragment<MatrixB, $M, $N, $K, B1, ColMajor>,
+            Fragment<Accumulator, $M, $N, $K, i32>,
+        >(MmaPopc::reference_xor_popc, |a, b| (a != b) as u8 as f64, i32::wrap);)+
+    };
+}
+
+#[test]
+fn single_bit() {
+    popc!([8, 8, 128], [16, 8, 128], [16, 8, 256]);
+}
+
+/// a random A with two of every four units of its rows kept, compressed as `mma.sp` takes it: the
+/// dense A with the others zeroed, the kept elements of every row in order and the metadata of every
+/// lane, written lane by lane from the layout described by `MmaSparse`
+fn compress<A: LaneLayout>(rng: &mut Rng, selector: u32) -> (Vec<f64>, Vec<f64>, Warp<u32>)
+where
+    A::Element: Values,
+{
+    let (rows, k) = (A::ROWS, 2 * A::COLS);
+    let element_bits = 32 / <A::Element as ReferenceElement>::PER_REG;
+    let unit_bits = element_bits.clamp(8, 16);
+    let units = k * element_bits / unit_bits;
+    let values = random::<A::Element>(rng, rows * k);
+    // which units of every row are kept, a tf32 element is two units so it keeps either half of a group
+    let kept: Vec<Vec<bool>> = (0..rows)
+        .map(|_| {
+            (0..units / 4)
+                .flat_map(|_| {
+                    let x = rng.next_u64() as usize;
+                    let (first, second) = if element_bits > unit_bits {
+                        (2 * (x % 2), 2 * (x % 2) + 1)
+                    } else {
+                        (x % 4, (x % 4 + 1 + (x >> 8) % 3) % 4)
+                    };
+                    (0..4).map(move |unit| unit == first || unit == second)
+                })
+                .collect()
+        })
+        .collect();
+    let is_kept = |row: usize, col: usize| kept[row][col * element_bits / unit_bits];
+    let dense: Vec<f64> = (0..rows * k)
+        .map(|i| {
+            if is_kept(i / k, i % k) {
+                values[i]
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let compressed: Vec<f64> = (0..rows)
+        .flat_map(|row| {
+            (0..k)
+                .filter(move |&col| is_kept(row, col))
+                .map(move |col| (row, col))
+        })
+        .map(|(row, col)| values[row * k + col])
+        .collect();
+    assert_eq!(compressed.len(), rows * A::COLS);
+    // the position within its group of four of every kept unit, in order
+    let indices: Vec<Vec<u32>> = kept
+        .iter()
+        .map(|units| {
+            (0..units.len())
+                .filter(|&u| units[u])
+                .map(|u| u as u32 % 4)
+                .collect()
+        })
+        .collect();
+    // lane 4g + q holds word q - selector * words of rows g and g + 8, 8 kept units of each
+    let words = units / 2 / 8;
+    let metadata = std::array::from_fn(|lane| {
+        let (group, quad) = (lane / 4, lane % 4);
+        let Some(word) = quad
+            .checked_sub(selector as usize * words)
+            .filter(|&w| w < words)
+        else {
+            return 0;
+        };
+        let mut bits = 0;
+        for half in 0..2 {
+            for slot in 0..8 {
+                bits |= indices[group + 8 * half][8 * word + slot] << (16 * half + 2 * slot);
+            }
+        }
+        bits
+    });
+    (dense, compressed, metadata)
+}
+
+macro_rules! sparse {
+    ($($M: literal, $N: literal, $K: literal, $T: ty, $TC: ty, [$($selector: literal)+]);+) => {
+        $($({
+            type A = Fragment<SparseMatrixA, $M, $N, $K, $T, RowMajor>;
+            type B = Fragment<MatrixB, $M, $N, $K, $T, ColMajor>;
+            type C = Fragment<Accumulator, $M, $N, $K, $TC>;
+            layout::<A>();
+            layout::<B>();
+            let mut rng = Rng(0xd1b5_4a32_d192_ed03);
+            for _ in 0..4 {
+                let (dense, compressed, metadata) = compress::<A>(&mut rng, $selector);
+                let b = random::<$T>(&mut rng, $K * $N);
+                let c = random::<$TC>(&mut rng, $M * $N);
+                let d = A::reference_sparse::<$selector>(
+                    &fragments(&compressed),
+                    &fragments::<B>(&b),
+                    &fragments::<C>(&c),
+                    &metadata,
+                );
+                let expected = naive(&dense, &b, &c, ($M, $N, $K), multiply, <$TC as Values>::wrap);
+                assert_eq!(gather(&d), expected, "selector {}", $selector);
+            }
+        })+)+
+    };
+}
+
+#[test]
+fn sparse() {
+    sparse!(
+        16, 8, 16, Half, Half, [0 1]; 16, 8, 16, Half, f32, [0 1]; 16, 8, 32, Half, Half, [0 1];
+        16, 8, 32, Half, f32, [0 1]; 16, 8, 16, BHalf, f32, [0 1]; 16, 8, 32, BHalf, f32, [0 1];
+        16, 8, 8, Tf32, f32, [0 1]; 16, 8, 16, Tf32, f32, [0 1]; 16, 8, 32, i8, i32, [0];
+        16, 8, 64, i8, i32, [0]; 16, 8, 32, u8, i32, [0]; 16, 8, 64, u8, i32, [0];
+        16, 8, 64, S4, i32, [0]; 16, 8, 128, S4, i32, [0]; 16, 8, 64, U4, i32, [0];
+        16, 8, 128, U4, i32, [0]
+    );
+}
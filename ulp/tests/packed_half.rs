@@ -0,0 +1,234 @@
+use nvptx_sys_ulp::*;
+use std::cmp::Ordering;
+
+/// The two packed formats, with their lane type and the bits of +inf
+trait Packed: Copy + std::fmt::Debug {
+    type Lane: Copy;
+    const NAME: &'static str;
+    const INFINITY: u16;
+    fn from_bits(bits: u32) -> Self;
+    fn to_bits(self) -> u32;
+    fn from_f32(lo: f32, hi: f32) -> Self;
+    fn to_f32(self) -> (f32, f32);
+    fn new(lo: Self::Lane, hi: Self::Lane) -> Self;
+    fn lanes(self) -> (Self::Lane, Self::Lane);
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min_nan(self, other: Self) -> Self;
+    fn max_nan(self, other: Self) -> Self;
+    fn lane_from_bits(bits: u16) -> Self::Lane;
+    fn lane_to_bits(lane: Self::Lane) -> u16;
+    fn lane_from_f32(value: f32) -> Self::Lane;
+    fn lane_to_f32(lane: Self::Lane) -> f32;
+}
+
+macro_rules! packed {
+    ($($F2: ident, $F: ident, $name: literal, $infinity: literal);+) => {
+        $(impl Packed for $F2 {
+            type Lane = $F;
+            const NAME: &'static str = $name;
+            const INFINITY: u16 = $infinity;
+            fn from_bits(bits: u32) -> Self {
+                $F2::from_bits(bits)
+            }
+            fn to_bits(self) -> u32 {
+                $F2::to_bits(self)
+            }
+            fn from_f32(lo: f32, hi: f32) -> Self {
+                $F2::from_f32(lo, hi)
+            }
+            fn to_f32(self) -> (f32, f32) {
+                $F2::to_f32(self)
+            }
+            fn new(lo: $F, hi: $F) -> Self {
+                $F2::new(lo, hi)
+            }
+            fn lanes(self) -> ($F, $F) {
+                self.into()
+            }
+            fn min(self, other: Self) -> Self {
+                $F2::min(self, other)
+            }
+            fn max(self, other: Self) -> Self {
+                $F2::max(self, other)
+            }
+            fn min_nan(self, other: Self) -> Self {
+                $F2::min_nan(self, other)
+            }
+            fn max_nan(self, other: Self) -> Self {
+                $F2::max_nan(self, other)
+            }
+            fn lane_from_bits(bits: u16) -> $F {
+                $F::from_bits(bits)
+            }
+            fn lane_to_bits(lane: $F) -> u16 {
+                lane.to_bits()
+            }
+            fn lane_from_f32(value: f32) -> $F {
+                $F::from_f32(value)
+            }
+            fn lane_to_f32(lane: $F) -> f32 {
+                lane.to_f32()
+            }
+        })+
+    };
+}
+
+packed! { f16x2, Half, "f16x2", 0x7c00; bf16x2, BHalf, "bf16x2", 0x7f80 }
+
+fn is_nan<F: Packed>(bits: u16) -> bool {
+    bits & 0x7fff > F::INFINITY
+}
+
+/// the order of the non-NaN bit patterns, sign and magnitude, in which -0 and +0 tie
+fn order(bits: u16) -> i32 {
+    let magnitude = (bits & 0x7fff) as i32;
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// IEEE minNum & maxNum on the bit patterns: the number if one is NaN, the canonical NaN if both are,
+/// and of the tied zeros -0 for min and +0 for max
+fn min_num<F: Packed>(a: u16, b: u16, max: bool) -> u16 {
+    match (is_nan::<F>(a), is_nan::<F>(b)) {
+        (true, true) => 0x7fff,
+        (true, false) => b,
+        (false, true) => a,
+        _ => match (order(a).cmp(&order(b)), max) {
+            (Ordering::Less, false) | (Ordering::Greater, true) => a,
+            (Ordering::Equal, false) => a | b,
+            (Ordering::Equal, true) => a & b,
+            _ => b,
+        },
+    }
+}
+
+/// the `.NaN` variants, NaN if either is
+fn min_nan<F: Packed>(a: u16, b: u16, max: bool) -> u16 {
+    if is_nan::<F>(a) || is_nan::<F>(b) {
+        0x7fff
+    } else {
+        min_num::<F>(a, b, max)
+    }
+}
+
+/// a pseudo random sample of the second operands, with the zeros, infinities and both kinds of NaN
+fn operands<F: Packed>(seed: u64) -> Vec<u16> {
+    let mut rng = Rng(seed);
+    let special = [
+        0,
+        0x8000,
+        0x0001,
+        0x8001,
+        F::INFINITY,
+        F::INFINITY | 0x8000,
+        F::INFINITY - 1,
+        F::INFINITY + 1,
+        0x7fff,
+        0xffff,
+    ];
+    special
+        .into_iter()
+        .chain((0..54).map(|_| rng.next_u64() as u16))
+        .collect()
+}
+
+/// a packed operation, its lanewise reference and whether that takes the maximum
+type Op<F> = (&'static str, fn(F, F) -> F, fn(u16, u16, bool) -> u16, bool);
+
+/// every first operand against the sample, the high lane holding `b` & `-a` so each lane is its own pair
+fn min_max<F: Packed>() {
+    let operands = operands::<F>(0x2545_f491_4f6c_dd1d);
+    let ops: [Op<F>; 4] = [
+        ("min", F::min, min_num::<F>, false),
+        ("max", F::max, min_num::<F>, true),
+        ("min_nan", F::min_nan, min_nan::<F>, false),
+        ("max_nan", F::max_nan, min_nan::<F>, true),
+    ];
+    for a in 0..=u16::MAX {
+        for &b in &operands {
+            let x = F::from_bits(a as u32 | (b as u32) << 16);
+            let y = F::from_bits(b as u32 | ((a ^ 0x8000) as u32) << 16);
+            for (name, op, reference, max) in ops {
+                let expected =
+                    reference(a, b, max) as u32 | (reference(b, a ^ 0x8000, max) as u32) << 16;
+                assert_eq!(
+                    op(x, y).to_bits(),
+                    expected,
+                    "{} {name}({x:?}, {y:?})",
+                    F::NAME
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn min_and_max() {
+    min_max::<f16x2>();
+    min_max::<bf16x2>();
+}
+
+/// the first lane in the low 16 bits, for the constructors, the accessors and both conversions
+fn lane_order<F: Packed>() {
+    for a in 0..=u16::MAX {
+        let b = a.rotate_left(8) ^ 0x8000;
+        let bits = a as u32 | (b as u32) << 16;
+        let (lo, hi) = (F::lane_from_bits(a), F::lane_from_bits(b));
+        assert_eq!(F::new(lo, hi).to_bits(), bits, "{} {bits:#010x}", F::NAME);
+        let (x, y) = F::from_bits(bits).lanes();
+        assert_eq!(
+            (F::lane_to_bits(x), F::lane_to_bits(y)),
+            (a, b),
+            "{} {bits:#010x}",
+            F::NAME
+        );
+        // the widening is exact, the scalar one is checked in tests/half.rs
+        let (x, y) = F::from_bits(bits).to_f32();
+        let (wide_lo, wide_hi) = (F::lane_to_f32(lo), F::lane_to_f32(hi));
+        assert_eq!(
+            (x.to_bits(), y.to_bits()),
+            (wide_lo.to_bits(), wide_hi.to_bits()),
+            "{} {bits:#010x}",
+            F::NAME
+        );
+        if !is_nan::<F>(a) && !is_nan::<F>(b) {
+            assert_eq!(
+                F::from_f32(wide_lo, wide_hi).to_bits(),
+                bits,
+                "{} {bits:#010x}",
+                F::NAME
+            );
+        }
+    }
+    // the narrowing rounds each lane like the scalar one
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..1 << 20 {
+        let x = rng.next_u64();
+        let (lo, hi) = (f32::from_bits(x as u32), f32::from_bits((x >> 32) as u32));
+        let expected = F::lane_to_bits(F::lane_from_f32(lo)) as u32
+            | (F::lane_to_bits(F::lane_from_f32(hi)) as u32) << 16;
+        assert_eq!(
+            F::from_f32(lo, hi).to_bits(),
+            expected,
+            "{} ({lo:e}, {hi:e})",
+            F::NAME
+        );
+    }
+    // 1 & 2 with 0x3c00 & 0x4000, or 0x3f80 & 0x4000, low first
+    let one_two = F::from_f32(1.0, 2.0).to_bits();
+    assert_eq!(one_two >> 16, 0x4000, "{}", F::NAME);
+    assert_eq!(
+        one_two & 0xffff,
+        F::lane_to_bits(F::lane_from_f32(1.0)) as u32
+    );
+}
+
+#[test]
+fn lanes_low_first() {
+    lane_order::<f16x2>();
+    lane_order::<bf16x2>();
+}
@@ -0,0 +1,180 @@
+use nvptx_sys_ulp::*;
+use std::cmp::Ordering;
+
+/// the edges of every key type's order, for the float keys those of `total_cmp`
+fn f32s() -> Vec<f32> {
+    let specials = [
+        -f32::NAN,
+        f32::NEG_INFINITY,
+        f32::MIN,
+        -1.0,
+        -f32::MIN_POSITIVE,
+        -f32::from_bits(1),
+        -0.0,
+        0.0,
+        f32::from_bits(1),
+        f32::MIN_POSITIVE,
+        1.0,
+        f32::MAX,
+        f32::INFINITY,
+        f32::NAN,
+        f32::from_bits(0x7f80_0001),
+        f32::from_bits(0xffff_ffff),
+    ];
+    let random = words(500).map(|x| f32::from_bits(x as u32));
+    specials.into_iter().chain(random).collect()
+}
+
+fn f64s() -> Vec<f64> {
+    let specials = [
+        -f64::NAN,
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.0,
+        -f64::MIN_POSITIVE,
+        -f64::from_bits(1),
+        -0.0,
+        0.0,
+        f64::from_bits(1),
+        f64::MIN_POSITIVE,
+        1.0,
+        f64::MAX,
+        f64::INFINITY,
+        f64::NAN,
+        f64::from_bits(0x7ff0_0000_0000_0001),
+        f64::from_bits(u64::MAX),
+    ];
+    specials
+        .into_iter()
+        .chain(words(500).map(f64::from_bits))
+        .collect()
+}
+
+fn i32s() -> Vec<i32> {
+    [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX]
+        .into_iter()
+        .chain(words(500).map(|x| x as i32 >> (x >> 59)))
+        .collect()
+}
+
+fn i64s() -> Vec<i64> {
+    [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX]
+        .into_iter()
+        .chain(words(500).map(|x| x as i64 >> (x >> 58)))
+        .collect()
+}
+
+/// the radix orders the keys like `cmp`, and maps back to the same bits
+fn check_order<K: RadixKey + std::fmt::Debug>(
+    keys: &[K],
+    cmp: impl Fn(&K, &K) -> Ordering,
+    bits: impl Fn(K) -> u64,
+) {
+    for &a in keys {
+        assert_eq!(bits(K::from_radix(a.to_radix())), bits(a), "{a:?}");
+        assert!(a.to_radix() >> K::BITS == 0 || K::BITS == 64, "{a:?}");
+        for &b in keys {
+            assert_eq!(a.to_radix().cmp(&b.to_radix()), cmp(&a, &b), "{a:?} {b:?}");
+        }
+    }
+}
+
+#[test]
+fn radix_orders_like_the_keys() {
+    check_order(&i32s(), Ord::cmp, |x| x as u32 as u64);
+    check_order(&i64s(), Ord::cmp, |x| x as u64);
+    // -NaN < -inf < .. < -0 < 0 < .. < inf < NaN
+    check_order(&f32s(), f32::total_cmp, |x| x.to_bits() as u64);
+    check_order(&f64s(), f64::total_cmp, f64::to_bits);
+    check_order(&[0, 1, u32::MAX], Ord::cmp, |x| x as u64);
+    check_order(&[0, 1, u64::MAX], Ord::cmp, |x| x);
+}
+
+/// where each pass of `BlockRadixSort` scatters the tile's keys: the thread ranks, the counters of every
+/// thread scanned in digit-major order, and their sum, `threads * items` keys in a blocked arrangement
+fn block_sort<K: RadixKey, const ITEMS: usize>(
+    keys: &[K],
+    begin_bit: u32,
+    end_bit: u32,
+) -> Vec<usize> {
+    let threads = keys.len() / ITEMS;
+    let mut tile: Vec<(K, usize)> = keys.iter().copied().zip(0..).collect();
+    for (bit, pass_bits) in radix_passes(begin_bit, end_bit) {
+        let mut counters = vec![0u32; threads << RADIX_BITS];
+        let mut thread_ranks = Vec::new();
+        for t in 0..threads {
+            let digits: [usize; ITEMS] =
+                std::array::from_fn(|i| radix_digit(tile[t * ITEMS + i].0, bit, pass_bits));
+            let mut counts = [0u32; 1 << RADIX_BITS];
+            for (digit, rank) in digits.iter().zip(radix_thread_ranks(&digits, &mut counts)) {
+                thread_ranks.push((*digit * threads + t, rank));
+            }
+            for (d, &count) in counts.iter().enumerate() {
+                counters[d * threads + t] = count;
+            }
+        }
+        let offsets: Vec<u32> = counters
+            .iter()
+            .scan(0, |acc, &count| {
+                *acc += count;
+                Some(*acc - count)
+            })
+            .collect();
+        let mut scattered = vec![None; tile.len()];
+        for (&item, (counter, rank)) in tile.iter().zip(thread_ranks) {
+            let slot = &mut scattered[(offsets[counter] + rank) as usize];
+            assert!(
+                slot.is_none(),
+                "two keys ranked {}",
+                offsets[counter] + rank
+            );
+            *slot = Some(item);
+        }
+        tile = scattered.into_iter().map(Option::unwrap).collect();
+    }
+    tile.into_iter().map(|(_, index)| index).collect()
+}
+
+/// the indices of `keys` stably sorted by their radix bits `begin_bit..end_bit`
+fn stable_order<K: RadixKey>(keys: &[K], begin_bit: u32, end_bit: u32) -> Vec<usize> {
+    let width = end_bit - begin_bit;
+    let mask = if width == 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    };
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by_key(|&i| (keys[i].to_radix() >> begin_bit) & mask);
+    order
+}
+
+fn check_sort<K: RadixKey>(keys: &[K], bit_ranges: &[(u32, u32)]) {
+    fn check<K: RadixKey, const ITEMS: usize>(keys: &[K], bit_ranges: &[(u32, u32)]) {
+        for threads in [1, 7, 32, 96] {
+            let keys = &keys[..threads * ITEMS];
+            for &(begin_bit, end_bit) in bit_ranges {
+                assert_eq!(
+                    block_sort::<K, ITEMS>(keys, begin_bit, end_bit),
+                    stable_order(keys, begin_bit, end_bit),
+                    "{threads} threads of {ITEMS} items, bits {begin_bit}..{end_bit}"
+                );
+            }
+        }
+    }
+    check::<K, 1>(keys, bit_ranges);
+    check::<K, 3>(keys, bit_ranges);
+    check::<K, 4>(keys, bit_ranges);
+}
+
+#[test]
+fn sorts_stably_by_the_bits() {
+    let bits32 = [(0, 32), (0, 5), (3, 17), (8, 8), (28, 32), (31, 32)];
+    let bits64 = [(0, 64), (13, 47), (60, 64), (0, 3)];
+    check_sort(&i32s(), &bits32);
+    check_sort(&f32s(), &bits32);
+    check_sort(&i64s(), &bits64);
+    check_sort(&f64s(), &bits64);
+    // few distinct digits, so stability decides most of the order
+    let small: Vec<u32> = words(400).map(|x| x as u32 % 5).collect();
+    check_sort(&small, &bits32);
+}
@@ -0,0 +1,296 @@
+use nvptx_sys_ulp::*;
+use std::cmp::Ordering;
+
+const OPS: [&str; 6] = ["add", "sub", "mul", "div", "sqrt", "fma"];
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// rounds the result to odd, f64 has enough bits more than f32 to round that once more in any mode
+fn to_odd(nearest: f64, residual: f64) -> f64 {
+    if !nearest.is_finite() || residual == 0.0 || nearest.to_bits() & 1 == 1 {
+        nearest
+    } else if residual > 0.0 {
+        nearest.next_up()
+    } else {
+        nearest.next_down()
+    }
+}
+
+/// an f32 operation's result rounded to odd in f64, and whether it is a sum of two operands with these signs
+fn exact(op: &str, a: f32, b: f32, c: f32) -> (f64, Option<(bool, bool)>) {
+    let (x, y, z) = (a as f64, b as f64, c as f64);
+    match op {
+        "add" | "sub" => {
+            let y = if op == "sub" { -y } else { y };
+            let (s, e) = two_sum(x, y);
+            (
+                to_odd(s, e),
+                Some((x.is_sign_positive(), y.is_sign_positive())),
+            )
+        }
+        "mul" => (x * y, None),
+        "div" => {
+            let q = x / y;
+            (to_odd(q, libm::fma(-q, y, x) * y.signum()), None)
+        }
+        "sqrt" => {
+            let r = libm::sqrt(x);
+            (to_odd(r, libm::fma(-r, r, x)), None)
+        }
+        _ => {
+            let (s, e) = two_sum(x * y, z);
+            (
+                to_odd(s, e),
+                Some(((x * y).is_sign_positive(), z.is_sign_positive())),
+            )
+        }
+    }
+}
+
+/// the exact result rounded down, up, toward zero and to nearest
+fn reference(value: f64, sum: Option<(bool, bool)>) -> [f32; 4] {
+    let nearest = value as f32;
+    let (down, up) = match (nearest as f64).partial_cmp(&value) {
+        Some(Ordering::Less) => (nearest, nearest.next_up()),
+        Some(Ordering::Greater) => (nearest.next_down(), nearest),
+        _ => (nearest, nearest),
+    };
+    // past the largest float only rounding away from zero gives infinity
+    let down = if down == f32::INFINITY && value.is_finite() {
+        f32::MAX
+    } else {
+        down
+    };
+    let up = if up == f32::NEG_INFINITY && value.is_finite() {
+        f32::MIN
+    } else {
+        up
+    };
+    // an exact zero sum rounded down is -0, unless both are +0
+    let down = match sum {
+        Some((a, b)) if value == 0.0 && !(a && b) => -0.0,
+        _ => down,
+    };
+    let zero = match value.partial_cmp(&0.0) {
+        Some(Ordering::Less) => up,
+        Some(Ordering::Greater) => down,
+        _ => nearest,
+    };
+    [down, up, zero, nearest].map(|x| {
+        if x == 0.0 && value != 0.0 {
+            0.0f32.copysign(value as f32)
+        } else {
+            x
+        }
+    })
+}
+
+fn apply<M: RoundingMode>(op: &str, a: f32, b: f32, c: f32) -> f32
+where
+    f32: RoundedArith<M>,
+{
+    match op {
+        "add" => RoundedArith::<M>::rounded_add(a, b),
+        "sub" => RoundedArith::<M>::rounded_sub(a, b),
+        "mul" => RoundedArith::<M>::rounded_mul(a, b),
+        "div" => RoundedArith::<M>::rounded_div(a, b),
+        "sqrt" => RoundedArith::<M>::rounded_sqrt(a),
+        _ => RoundedArith::<M>::rounded_fma(a, b, c),
+    }
+}
+
+fn flush(x: f32) -> f32 {
+    if x.is_subnormal() {
+        0.0f32.copysign(x)
+    } else {
+        x
+    }
+}
+
+fn bits(x: [f32; 4]) -> [u32; 4] {
+    x.map(|x| if x.is_nan() { u32::MAX } else { x.to_bits() })
+}
+
+/// pseudo random operands of every exponent, and every other one close to the first, for cancellation
+fn f32_operands() -> impl Iterator<Item = (f32, f32, f32)> {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    (0..200_000).map(move |i| {
+        let [a, b, c] = [rng.next_u64(), rng.next_u64(), rng.next_u64()].map(|x| x as u32);
+        if i % 2 == 0 {
+            (f32::from_bits(a), f32::from_bits(b), f32::from_bits(c))
+        } else {
+            let a = f32::from_bits(a);
+            let b = f32::from_bits(a.to_bits() ^ (b & 0x8000_0fff));
+            (a, b, -a * b)
+        }
+    })
+}
+
+#[test]
+fn f32_modes() {
+    for (a, b, c) in f32_operands() {
+        for op in OPS {
+            let (value, sum) = exact(op, a, b, c);
+            let rounded = [
+                apply::<Rm>(op, a, b, c),
+                apply::<Rp>(op, a, b, c),
+                apply::<Rz>(op, a, b, c),
+                apply::<Rn>(op, a, b, c),
+            ];
+            assert_eq!(
+                bits(rounded),
+                bits(reference(value, sum)),
+                "{op} {a:e} {b:e} {c:e}"
+            );
+            let (fa, fb, fc) = (flush(a), flush(b), flush(c));
+            let (value, sum) = exact(op, fa, fb, fc);
+            let rounded = [
+                apply::<Ftz<Rm>>(op, a, b, c),
+                apply::<Ftz<Rp>>(op, a, b, c),
+                apply::<Ftz<Rz>>(op, a, b, c),
+                apply::<Ftz<Rn>>(op, a, b, c),
+            ];
+            assert_eq!(
+                bits(rounded),
+                bits(reference(value, sum).map(flush)),
+                "ftz {op} {a:e} {b:e} {c:e}"
+            );
+        }
+    }
+}
+
+fn modes_f64(op: &str, a: f64, b: f64, c: f64) -> [f64; 4] {
+    fn apply<M: RoundingMode>(op: &str, a: f64, b: f64, c: f64) -> f64
+    where
+        f64: RoundedArith<M>,
+    {
+        match op {
+            "add" => RoundedArith::<M>::rounded_add(a, b),
+            "sub" => RoundedArith::<M>::rounded_sub(a, b),
+            "mul" => RoundedArith::<M>::rounded_mul(a, b),
+            "div" => RoundedArith::<M>::rounded_div(a, b),
+            "sqrt" => RoundedArith::<M>::rounded_sqrt(a),
+            _ => RoundedArith::<M>::rounded_fma(a, b, c),
+        }
+    }
+    [
+        apply::<Rm>(op, a, b, c),
+        apply::<Rp>(op, a, b, c),
+        apply::<Rz>(op, a, b, c),
+        apply::<Rn>(op, a, b, c),
+    ]
+}
+
+/// pseudo random f64 with exponents within 2^±64, where the fma residuals are exact
+#[test]
+fn f64_modes() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut operand = || {
+        let bits = rng.next_u64();
+        f64::from_bits((bits & 0x800f_ffff_ffff_ffff) | ((bits >> 52) % 129 + 959) << 52)
+    };
+    for _ in 0..200_000 {
+        let (a, b) = (operand(), operand());
+        for op in ["add", "mul", "div", "sqrt"] {
+            let (nearest, residual) = match op {
+                "add" => two_sum(a, b),
+                "mul" => (a * b, libm::fma(a, b, -(a * b))),
+                "div" => (a / b, libm::fma(-(a / b), b, a) * b.signum()),
+                _ => (libm::sqrt(a), libm::fma(-libm::sqrt(a), libm::sqrt(a), a)),
+            };
+            let [down, up, zero, near] = modes_f64(op, a, b, 0.0);
+            assert_eq!(near.to_bits(), nearest.to_bits(), "{op} {a:e} {b:e}");
+            if nearest.is_nan() {
+                assert!(down.is_nan() && up.is_nan() && zero.is_nan());
+                continue;
+            }
+            let expected = match residual.partial_cmp(&0.0).unwrap() {
+                Ordering::Less => (nearest.next_down(), nearest),
+                Ordering::Greater => (nearest, nearest.next_up()),
+                Ordering::Equal => (nearest, nearest),
+            };
+            assert_eq!((down, up), expected, "{op} {a:e} {b:e}");
+            assert_eq!(zero, if nearest < 0.0 { up } else { down });
+        }
+    }
+}
+
+#[test]
+fn f64_edges() {
+    let tiny = f64::from_bits(1);
+    let one = 1.0f64;
+    let [down, up, zero, near] = modes_f64("div", 1.0, 3.0, 0.0);
+    assert_eq!((up, zero, near), (down.next_up(), down, down));
+    // overflow only rounds to infinity away from zero
+    assert_eq!(
+        modes_f64("mul", f64::MAX, -2.0, 0.0),
+        [f64::NEG_INFINITY, f64::MIN, f64::MIN, f64::NEG_INFINITY]
+    );
+    assert_eq!(
+        modes_f64("div", f64::MAX, 0.5, 0.0),
+        [f64::MAX, f64::INFINITY, f64::MAX, f64::INFINITY]
+    );
+    // dividing by zero is exact
+    assert_eq!(modes_f64("div", 1.0, 0.0, 0.0), [f64::INFINITY; 4]);
+    // 1.5 2^-1074, a tie of subnormals
+    assert_eq!(
+        modes_f64("mul", 2f64.powi(-537), 1.5 * 2f64.powi(-537), 0.0),
+        [tiny, 2.0 * tiny, tiny, 2.0 * tiny]
+    );
+    assert_eq!(
+        modes_f64("mul", 2f64.powi(-540), 2f64.powi(-540), 0.0),
+        [0.0, tiny, 0.0, 0.0]
+    );
+    assert_eq!(modes_f64("div", -tiny, 3.0, 0.0), [-tiny, -0.0, -0.0, -0.0]);
+    // sqrt(2 2^-1074) = sqrt(2) 2^-537, with a residual far below the subnormals
+    let [down, up, zero, _] = modes_f64("sqrt", 2.0 * tiny, 0.0, 0.0);
+    assert_eq!((up, zero), (down.next_up(), down));
+    assert_eq!(down, 2f64.powi(-537) * modes_f64("sqrt", 2.0, 0.0, 0.0)[0]);
+    // a tiny addend decides the side of an exact product
+    assert_eq!(
+        modes_f64("fma", one, one, 2f64.powi(-1000)),
+        [one, one.next_up(), one, one]
+    );
+    assert_eq!(
+        modes_f64("fma", one, one, -tiny),
+        [one.next_down(), one, one.next_down(), one]
+    );
+    assert_eq!(
+        modes_f64("fma", -2f64.powi(-600), 2f64.powi(-600), one),
+        [one.next_down(), one, one.next_down(), one]
+    );
+    assert_eq!(
+        modes_f64("fma", 2f64.powi(600), 2f64.powi(600), -one),
+        [f64::MAX, f64::INFINITY, f64::MAX, f64::INFINITY]
+    );
+    // an exact zero sum is -0 rounding down
+    let [down, up, zero, near] = modes_f64("fma", 2.0, 3.0, -6.0);
+    assert_eq!(
+        [down, up, zero, near].map(f64::to_bits),
+        [-0.0f64, 0.0, 0.0, 0.0].map(f64::to_bits)
+    );
+    let [down, up, ..] = modes_f64("sub", 0.1, 0.1, 0.0);
+    assert_eq!(
+        [down, up].map(f64::to_bits),
+        [-0.0f64, 0.0].map(f64::to_bits)
+    );
+    assert_eq!(modes_f64("add", 0.0, 0.0, 0.0)[0].to_bits(), 0);
+}
+
+/// summing 0.1 rounded down and up brackets the exact sum
+#[test]
+fn interval() {
+    let tenth = (Rounded::<f64, Rm>::new(0.1), Rounded::<f64, Rp>::new(0.1));
+    let (mut low, mut high) = (Rounded::<f64, Rm>::new(0.0), Rounded::<f64, Rp>::new(0.0));
+    for _ in 0..10 {
+        low += tenth.0;
+        high += tenth.1;
+    }
+    // 0.1 is above 1/10, so the exact sum is above 1
+    assert!(low.0 <= 1.0 && 1.0 < high.0);
+    assert!(high.0 - low.0 < 1e-15);
+}
@@ -0,0 +1,177 @@
+use nvptx_sys_ulp::*;
+
+const WARP: usize = 32;
+
+/// x -> x a + b, composing them in order is associative but not commutative, so the order is checked
+type Affine = (u32, u32);
+
+fn then(f: Affine, g: Affine) -> Affine {
+    (
+        f.0.wrapping_mul(g.0),
+        f.1.wrapping_mul(g.0).wrapping_add(g.1),
+    )
+}
+
+fn affines(n: usize, seed: u64) -> Vec<Affine> {
+    let mut rng = Rng(seed);
+    (0..n)
+        .map(|_| {
+            let x = rng.next_u64();
+            (x as u32 | 1, (x >> 32) as u32)
+        })
+        .collect()
+}
+
+/// what BlockScan's `scan` gives each thread: the warp scans, the lane before's prefix by `shfl_up(1)`,
+/// the aggregates of the warps in shared memory, and `compose_scan`
+fn block_scan(values: &[Affine]) -> Vec<(Affine, Option<Affine>, Affine)> {
+    let inclusive: Vec<Affine> = values
+        .chunks(WARP)
+        .flat_map(|warp| {
+            warp.iter().scan(None, |acc: &mut Option<Affine>, &v| {
+                *acc = Some(acc.map_or(v, |acc| then(acc, v)));
+                *acc
+            })
+        })
+        .collect();
+    let aggregates: Vec<Affine> = values
+        .chunks(WARP)
+        .enumerate()
+        .map(|(w, warp)| inclusive[w * WARP + warp.len() - 1])
+        .collect();
+    (0..values.len())
+        .map(|tid| {
+            let (warp, lane) = (tid / WARP, tid % WARP);
+            // shfl_up(1) leaves lane 0 its own value
+            let warp_exclusive = inclusive[if lane == 0 { tid } else { tid - 1 }];
+            compose_scan(
+                warp as u32,
+                lane as u32,
+                inclusive[tid],
+                warp_exclusive,
+                aggregates.len() as u32,
+                |w| aggregates[w as usize],
+                &then,
+            )
+        })
+        .collect()
+}
+
+/// (inclusive, exclusive) sequential scans, exclusive from `initial`
+fn sequential(values: &[Affine], initial: Affine) -> (Vec<Affine>, Vec<Affine>) {
+    let mut inclusive = Vec::new();
+    let mut exclusive = Vec::new();
+    let (mut acc, mut scan) = (initial, None);
+    for &v in values {
+        exclusive.push(acc);
+        acc = then(acc, v);
+        scan = Some(scan.map_or(v, |scan| then(scan, v)));
+        inclusive.extend(scan);
+    }
+    (inclusive, exclusive)
+}
+
+const THREADS: [usize; 8] = [1, 2, 31, 32, 33, 64, 100, 1024];
+const INITIAL: Affine = (3, 7);
+
+#[test]
+fn inclusive_and_exclusive() {
+    for threads in THREADS {
+        let values = affines(threads, threads as u64);
+        let (inclusive, exclusive) = sequential(&values, INITIAL);
+        let aggregate = inclusive[threads - 1];
+        for (tid, (i, e, a)) in block_scan(&values).into_iter().enumerate() {
+            assert_eq!(i, inclusive[tid], "inclusive of {tid} of {threads}");
+            assert_eq!(
+                seed_prefix(INITIAL, e, &then),
+                exclusive[tid],
+                "exclusive of {tid} of {threads}"
+            );
+            assert_eq!(e.is_none(), tid == 0);
+            assert_eq!(a, aggregate);
+        }
+    }
+}
+
+fn items<const ITEMS: usize>() {
+    for threads in THREADS {
+        let values = affines(threads * ITEMS, (threads * ITEMS) as u64);
+        let (inclusive, exclusive) = sequential(&values, INITIAL);
+        let blocked: Vec<[Affine; ITEMS]> = values
+            .chunks(ITEMS)
+            .map(|items| items.try_into().unwrap())
+            .collect();
+        let reductions: Vec<Affine> = blocked
+            .iter()
+            .map(|items| thread_reduce(items, &then))
+            .collect();
+        for (tid, (_, e, _)) in block_scan(&reductions).into_iter().enumerate() {
+            let expected = tid * ITEMS..(tid + 1) * ITEMS;
+            assert_eq!(
+                thread_inclusive_scan(blocked[tid], e, &then)[..],
+                inclusive[expected.clone()],
+                "inclusive items of {tid} of {threads}"
+            );
+            assert_eq!(
+                thread_exclusive_scan(blocked[tid], seed_prefix(INITIAL, e, &then), &then)[..],
+                exclusive[expected],
+                "exclusive items of {tid} of {threads}"
+            );
+        }
+    }
+}
+
+#[test]
+fn blocked_items() {
+    items::<1>();
+    items::<3>();
+    items::<4>();
+}
+
+/// consecutive tiles of a block carry a running prefix
+#[test]
+fn with_prefix() {
+    const ITEMS: usize = 2;
+    for threads in THREADS {
+        let tiles = 5;
+        let values = affines(tiles * threads * ITEMS, threads as u64 + 1);
+        let (inclusive, exclusive) = sequential(&values, INITIAL);
+        let mut running = RunningPrefix::new(INITIAL, then);
+        for (t, tile) in values.chunks(threads * ITEMS).enumerate() {
+            let blocked: Vec<[Affine; ITEMS]> = tile
+                .chunks(ITEMS)
+                .map(|items| items.try_into().unwrap())
+                .collect();
+            let reductions: Vec<Affine> = blocked
+                .iter()
+                .map(|items| thread_reduce(items, &then))
+                .collect();
+            let scanned = block_scan(&reductions);
+            // thread 0 calls the callback with the block aggregate and broadcasts the prefix
+            let prefix = running.callback()(scanned[0].2);
+            for (tid, (_, e, _)) in scanned.into_iter().enumerate() {
+                let prefix = seed_prefix(prefix, e, &then);
+                let expected = (t * threads + tid) * ITEMS..(t * threads + tid + 1) * ITEMS;
+                // the inclusive prefix is seeded by the tile prefix, which starts from INITIAL
+                let seeded: Vec<Affine> = inclusive[expected.clone()]
+                    .iter()
+                    .map(|&i| then(INITIAL, i))
+                    .collect();
+                assert_eq!(
+                    thread_inclusive_scan(blocked[tid], Some(prefix), &then)[..],
+                    seeded[..],
+                    "inclusive tile {t} thread {tid} of {threads}"
+                );
+                assert_eq!(
+                    thread_exclusive_scan(blocked[tid], prefix, &then)[..],
+                    exclusive[expected],
+                    "exclusive tile {t} thread {tid} of {threads}"
+                );
+            }
+        }
+        assert_eq!(
+            running.running(),
+            then(INITIAL, inclusive[values.len() - 1])
+        );
+    }
+}
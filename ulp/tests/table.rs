@@ -0,0 +1,25 @@
+use nvptx_sys_ulp::{table, Row};
+
+/// the checked in table bounds the error, regenerate it when an approximation improves
+#[test]
+fn within_table() {
+    let expected: Vec<Row> = include_str!("../table.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| Row::parse(line).expect(line))
+        .collect();
+    let measured = table(false);
+    assert_eq!(measured.len(), expected.len(), "functions added or removed");
+    for (measured, expected) in measured.iter().zip(&expected) {
+        assert_eq!(
+            (&measured.name, &measured.ty),
+            (&expected.name, &expected.ty)
+        );
+        assert!(
+            measured.max_ulps <= expected.max_ulps
+                && measured.mean_ulps <= expected.mean_ulps + 5e-5
+                && measured.nan <= expected.nan,
+            "{measured}\nexceeds\n{expected}"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use nvptx_sys_ulp::*;
+
+/// the shared pseudo random words, two to a u128
+fn words(n: usize) -> impl Iterator<Item = u128> {
+    let mut halves = nvptx_sys_ulp::words(2 * n);
+    (0..n).map(move |_| (halves.next().unwrap() as u128) << 64 | halves.next().unwrap() as u128)
+}
+
+fn to_u128(x: U128) -> u128 {
+    x.0.iter().rev().fold(0, |x, &limb| x << 32 | limb as u128)
+}
+
+#[test]
+fn add_sub() {
+    for (a, b) in words(1000).zip(words(1001).skip(1)) {
+        let (sum, carry) = U128::from(a).overflowing_add(U128::from(b));
+        assert_eq!((to_u128(sum), carry), a.overflowing_add(b));
+        let (difference, borrow) = U128::from(a).overflowing_sub(U128::from(b));
+        assert_eq!((to_u128(difference), borrow), a.overflowing_sub(b));
+        assert_eq!(U128::from(a).cmp(&U128::from(b)), a.cmp(&b));
+    }
+    assert_eq!(U384::MAX + U384::ONE, U384::ZERO);
+    assert_eq!(U384::ZERO - U384::ONE, U384::MAX);
+}
+
+#[test]
+fn products() {
+    for (a, b) in words(1000).zip(words(1001).skip(1)) {
+        let (lo, hi) = U128::from(a).widening_mul(U128::from(b));
+        assert_eq!(to_u128(lo), a.wrapping_mul(b));
+        // the 256 bit product of the zero extended halves
+        let (wide, zero) = U256::from(a).widening_mul(U256::from(b));
+        assert_eq!((&wide.0[..4], &wide.0[4..]), (&lo.0[..], &hi.0[..]));
+        assert_eq!(zero, U256::ZERO);
+        let (a, b) = (a as u64 as u128, b as u64 as u128);
+        assert_eq!(to_u128(U128::from(a) * U128::from(b)), a * b);
+    }
+    assert_eq!(
+        U384::MAX.widening_mul(U384::MAX),
+        (U384::ONE, U384::MAX - U384::ONE)
+    );
+}
+
+#[test]
+fn montgomery() {
+    // 2^64 - 59, prime
+    let m = u64::MAX as u128 - 58;
+    let field = Montgomery::new(U128::from(m));
+    for (a, b) in words(1000).zip(words(1001).skip(1)) {
+        let (a, b) = (a % m, b % m);
+        let (x, y) = (field.to_montgomery(a.into()), field.to_montgomery(b.into()));
+        assert_eq!(to_u128(field.from_montgomery(x)), a);
+        assert_eq!(to_u128(field.from_montgomery(field.mul(x, y))), a * b % m);
+        assert_eq!(to_u128(field.from_montgomery(field.add(x, y))), (a + b) % m);
+        assert_eq!(
+            to_u128(field.from_montgomery(field.sub(x, y))),
+            (a + m - b) % m
+        );
+    }
+    // Fermat, a^(m - 1) = 1 with a modulus filling every limb
+    let p = U384::MAX - U384::from(316u32);
+    let field = Montgomery::new(p);
+    let a = field.to_montgomery(U384::from(0x1234_5678_9abc_def0u64));
+    let one = field.pow(a, p - U384::ONE);
+    assert_eq!(field.from_montgomery(one), U384::ONE);
+}
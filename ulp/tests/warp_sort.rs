@@ -0,0 +1,188 @@
+use nvptx_sys_ulp::*;
+
+/// the widths of the member masks, the lowest 2^n lanes
+const WIDTHS: [u32; 6] = [1, 2, 4, 8, 16, 32];
+
+/// what each lane of the warp holds after the steps of `warp_bitonic_sort`, whose partner is lane ^ j
+fn sort<T: PartialOrd + Copy>(lanes: &[T]) -> Vec<T> {
+    let mut lanes = lanes.to_vec();
+    for (k, j) in bitonic_sort_steps(lanes.len() as u32) {
+        lanes = (0..lanes.len() as u32)
+            .map(|l| {
+                bitonic_exchange(
+                    lanes[l as usize],
+                    lanes[(l ^ j) as usize],
+                    bitonic_keeps_min(l, k, j),
+                )
+            })
+            .collect();
+    }
+    lanes
+}
+
+/// `warp_bitonic_sort_by_key`
+fn sort_by_key<K: PartialOrd + Copy, V: Copy>(lanes: &[(K, V)]) -> Vec<(K, V)> {
+    let mut lanes = lanes.to_vec();
+    for (k, j) in bitonic_sort_steps(lanes.len() as u32) {
+        lanes = (0..lanes.len() as u32)
+            .map(|l| {
+                let (key, other) = (lanes[l as usize], lanes[(l ^ j) as usize]);
+                if bitonic_take_other(&key.0, &other.0, bitonic_keeps_min(l, k, j)) {
+                    other
+                } else {
+                    key
+                }
+            })
+            .collect();
+    }
+    lanes
+}
+
+/// `warp_bitonic_sort_items`
+fn sort_items<T: PartialOrd + Copy, const N: usize>(lanes: &[[T; N]]) -> Vec<[T; N]> {
+    let mut lanes = lanes.to_vec();
+    let n = N as u32;
+    for (k, j) in bitonic_sort_steps(lanes.len() as u32 * n) {
+        lanes = (0..lanes.len() as u32)
+            .map(|l| {
+                let mut items = lanes[l as usize];
+                if j < n {
+                    bitonic_exchange_items(&mut items, l * n, k, j);
+                } else {
+                    let other = lanes[(l ^ (j / n)) as usize];
+                    for (i, item) in items.iter_mut().enumerate() {
+                        let keep_min = bitonic_keeps_min(l * n + i as u32, k, j);
+                        *item = bitonic_exchange(*item, other[i], keep_min);
+                    }
+                }
+                items
+            })
+            .collect();
+    }
+    lanes
+}
+
+/// `warp_merge`, the lower & upper halves
+fn merge<T: PartialOrd + Copy>(a: &[T], b: &[T]) -> (Vec<T>, Vec<T>) {
+    let width = a.len();
+    let pairs: Vec<(T, T)> = (0..width)
+        .map(|l| bitonic_merge_pair(a[l], b[width - 1 - l]))
+        .collect();
+    let merge_lanes = |mut lanes: Vec<T>| {
+        for j in bitonic_merge_distances(width as u32) {
+            lanes = (0..width as u32)
+                .map(|l| bitonic_exchange(lanes[l as usize], lanes[(l ^ j) as usize], l & j == 0))
+                .collect();
+        }
+        lanes
+    };
+    (
+        merge_lanes(pairs.iter().map(|p| p.0).collect()),
+        merge_lanes(pairs.iter().map(|p| p.1).collect()),
+    )
+}
+
+/// a pseudo random permutation of 0..n
+fn permutation(n: u32, rng: &mut Rng) -> Vec<u32> {
+    let mut values: Vec<u32> = (0..n).collect();
+    for i in (1..values.len()).rev() {
+        values.swap(i, rng.next_u64() as usize % (i + 1));
+    }
+    values
+}
+
+#[test]
+fn sorts_permutations() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for width in WIDTHS {
+        for _ in 0..1000 {
+            let values = permutation(width, &mut rng);
+            assert_eq!(sort(&values), (0..width).collect::<Vec<_>>(), "{values:?}");
+        }
+    }
+}
+
+#[test]
+fn sorts_duplicates_and_keeps_nan() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for width in WIDTHS {
+        for _ in 0..1000 {
+            let values: Vec<u32> = (0..width).map(|_| rng.next_u64() as u32 % 4).collect();
+            let mut expected = values.clone();
+            expected.sort();
+            assert_eq!(sort(&values), expected, "{values:?}");
+            // NaNs are neither duplicated nor lost
+            let floats: Vec<f32> = values
+                .iter()
+                .map(|&v| if v == 0 { f32::NAN } else { v as f32 })
+                .collect();
+            let mut sorted: Vec<u32> = sort(&floats).iter().map(|f| f.to_bits()).collect();
+            let mut bits: Vec<u32> = floats.iter().map(|f| f.to_bits()).collect();
+            sorted.sort();
+            bits.sort();
+            assert_eq!(sorted, bits, "{floats:?}");
+        }
+    }
+}
+
+#[test]
+fn sorts_by_key() {
+    let mut rng = Rng(0x1234_5678_9abc_def1);
+    for width in WIDTHS {
+        for _ in 0..1000 {
+            // few keys, so values must follow their own key rather than any equal one
+            let pairs: Vec<(u32, u32)> = (0..width)
+                .map(|lane| (rng.next_u64() as u32 % 4, lane))
+                .collect();
+            let sorted = sort_by_key(&pairs);
+            assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0), "{pairs:?}");
+            let mut moved = sorted.clone();
+            moved.sort_by_key(|p| p.1);
+            assert_eq!(moved, pairs);
+        }
+    }
+}
+
+#[test]
+fn sorts_items() {
+    fn check<const N: usize>(rng: &mut Rng) {
+        for width in WIDTHS {
+            for _ in 0..200 {
+                let values = permutation(width * N as u32, rng);
+                let lanes: Vec<[u32; N]> = values
+                    .chunks(N)
+                    .map(|items| items.try_into().unwrap())
+                    .collect();
+                // blocked, lane l holds l * N..(l + 1) * N
+                let sorted: Vec<u32> = sort_items(&lanes).concat();
+                assert_eq!(
+                    sorted,
+                    (0..width * N as u32).collect::<Vec<_>>(),
+                    "{values:?}"
+                );
+            }
+        }
+    }
+    let mut rng = Rng(0x0bad_cafe_dead_beef);
+    check::<1>(&mut rng);
+    check::<2>(&mut rng);
+    check::<4>(&mut rng);
+    check::<8>(&mut rng);
+}
+
+#[test]
+fn merges_sorted_runs() {
+    let mut rng = Rng(0x5555_aaaa_3333_cccc);
+    for width in WIDTHS {
+        for _ in 0..1000 {
+            let mut a: Vec<u32> = (0..width).map(|_| rng.next_u64() as u32 % 64).collect();
+            let mut b: Vec<u32> = (0..width).map(|_| rng.next_u64() as u32 % 64).collect();
+            a.sort();
+            b.sort();
+            let (lo, hi) = merge(&a, &b);
+            let mut expected = [a.clone(), b.clone()].concat();
+            expected.sort();
+            assert_eq!([lo, hi].concat(), expected, "{a:?} {b:?}");
+        }
+    }
+}